@@ -0,0 +1,26 @@
+ALTER TABLE incident
+ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    to_tsvector('russian', coalesce(description, ''))
+) STORED;
+
+ALTER TABLE incident_type
+ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    to_tsvector('russian', name)
+) STORED;
+
+ALTER TABLE address
+ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    to_tsvector('russian',
+        coalesce(region, '') || ' ' || coalesce(city, '') || ' ' || coalesce(street, ''))
+) STORED;
+
+ALTER TABLE employee
+ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    to_tsvector('russian',
+        last_name || ' ' || first_name || ' ' || coalesce(middle_name, ''))
+) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_incident_search_vector ON incident USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_incident_type_search_vector ON incident_type USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_address_search_vector ON address USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_employee_search_vector ON employee USING GIN (search_vector);
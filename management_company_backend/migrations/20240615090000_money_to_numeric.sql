@@ -0,0 +1,13 @@
+-- Replace the locale-dependent money type with exact numeric amounts and an explicit currency
+
+BEGIN TRANSACTION;
+
+ALTER TABLE position_at_work
+ALTER COLUMN salary TYPE numeric(14, 2) USING salary::numeric,
+ADD COLUMN currency char(3) NOT NULL DEFAULT 'RUB' CHECK(currency ~ '^[A-Z]{3}$');
+
+ALTER TABLE financial_operation
+ALTER COLUMN amount TYPE numeric(14, 2) USING amount::numeric,
+ADD COLUMN currency char(3) NOT NULL DEFAULT 'RUB' CHECK(currency ~ '^[A-Z]{3}$');
+
+COMMIT TRANSACTION;
@@ -0,0 +1,33 @@
+-- Record who changed what through the API; the log is readable by administrators only
+
+BEGIN TRANSACTION;
+
+ALTER TABLE user_account
+ADD COLUMN IF NOT EXISTS is_admin boolean NOT NULL DEFAULT false;
+
+DO $$
+BEGIN
+    BEGIN
+        CREATE TYPE audit_action AS ENUM ('create', 'update', 'delete');
+    EXCEPTION
+        WHEN duplicate_object THEN
+            -- Do nothing, type already exists
+    END;
+END $$;
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v1mc(),
+    actor_id uuid REFERENCES user_account(id) ON DELETE SET NULL,
+    action audit_action NOT NULL,
+    entity_type varchar(50) NOT NULL,
+    entity_id uuid NOT NULL,
+    before jsonb, -- changed fields before the change, NULL for create
+    after jsonb, -- changed fields after the change, NULL for delete
+    created_at timestamp WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log(entity_type, entity_id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor_id ON audit_log(actor_id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_created_at ON audit_log(created_at);
+
+COMMIT TRANSACTION;
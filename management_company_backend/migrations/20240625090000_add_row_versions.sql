@@ -0,0 +1,30 @@
+-- Row versions for optimistic concurrency: the API returns them as ETag and checks If-Match
+
+BEGIN TRANSACTION;
+
+ALTER TABLE employee ADD COLUMN IF NOT EXISTS version integer NOT NULL DEFAULT 1;
+ALTER TABLE incident ADD COLUMN IF NOT EXISTS version integer NOT NULL DEFAULT 1;
+
+-- Bump the version only when something actually changed, whoever runs the UPDATE.
+-- Generated columns are not computed yet in BEFORE triggers, so search_vector is ignored.
+CREATE OR REPLACE FUNCTION bump_row_version() RETURNS trigger AS $$
+BEGIN
+    IF (to_jsonb(NEW) - 'search_vector' - 'version')
+        IS DISTINCT FROM (to_jsonb(OLD) - 'search_vector' - 'version') THEN
+        NEW.version := OLD.version + 1;
+    ELSE
+        NEW.version := OLD.version;
+    END IF;
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS employee_version ON employee;
+CREATE TRIGGER employee_version BEFORE UPDATE ON employee
+FOR EACH ROW EXECUTE FUNCTION bump_row_version();
+
+DROP TRIGGER IF EXISTS incident_version ON incident;
+CREATE TRIGGER incident_version BEFORE UPDATE ON incident
+FOR EACH ROW EXECUTE FUNCTION bump_row_version();
+
+COMMIT TRANSACTION;
@@ -0,0 +1,18 @@
+-- Soft deletion: deleted rows stay in place for financial history and can be restored
+
+BEGIN TRANSACTION;
+
+ALTER TABLE employee ADD COLUMN IF NOT EXISTS deleted_at timestamptz;
+ALTER TABLE building ADD COLUMN IF NOT EXISTS deleted_at timestamptz;
+ALTER TABLE apartment ADD COLUMN IF NOT EXISTS deleted_at timestamptz;
+ALTER TABLE incident ADD COLUMN IF NOT EXISTS deleted_at timestamptz;
+ALTER TABLE repair ADD COLUMN IF NOT EXISTS deleted_at timestamptz;
+
+-- The trash listing only ever looks at the few deleted rows.
+CREATE INDEX IF NOT EXISTS idx_employee_deleted_at ON employee(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_building_deleted_at ON building(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_apartment_deleted_at ON apartment(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_incident_deleted_at ON incident(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_repair_deleted_at ON repair(deleted_at) WHERE deleted_at IS NOT NULL;
+
+COMMIT TRANSACTION;
@@ -0,0 +1,76 @@
+-- Live incident feed: triggers publish incident and repair events with NOTIFY, so every
+-- backend instance sees every change whichever instance (or script) made it
+
+BEGIN TRANSACTION;
+
+-- Status names as the API serializes them.
+CREATE OR REPLACE FUNCTION incident_status_name(status incident_status) RETURNS text AS $$
+    SELECT CASE status
+        WHEN 'reported' THEN 'Reported'
+        WHEN 'in_progress' THEN 'InProgress'
+        WHEN 'resolved' THEN 'Resolved'
+        WHEN 'closed' THEN 'Closed'
+        WHEN 'cancelled' THEN 'Cancelled'
+    END
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION notify_incident_event() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM pg_notify('incident_events', json_build_object(
+            'kind', 'incident_created',
+            'buildingId', NEW.building_id,
+            'incidentId', NEW.id,
+            'status', incident_status_name(NEW.status),
+            'at', now()
+        )::text);
+    ELSIF NEW.status IS DISTINCT FROM OLD.status AND NEW.deleted_at IS NULL THEN
+        PERFORM pg_notify('incident_events', json_build_object(
+            'kind', 'incident_status_changed',
+            'buildingId', NEW.building_id,
+            'incidentId', NEW.id,
+            'status', incident_status_name(NEW.status),
+            'previousStatus', incident_status_name(OLD.status),
+            'at', now()
+        )::text);
+    END IF;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS incident_event ON incident;
+CREATE TRIGGER incident_event AFTER INSERT OR UPDATE OF status ON incident
+FOR EACH ROW EXECUTE FUNCTION notify_incident_event();
+
+CREATE OR REPLACE FUNCTION notify_repair_event() RETURNS trigger AS $$
+DECLARE
+    kind text;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        kind := CASE WHEN NEW.ended_at IS NULL THEN 'repair_opened' ELSE 'repair_closed' END;
+    ELSIF NEW.deleted_at IS NOT NULL THEN
+        RETURN NULL;
+    ELSIF OLD.ended_at IS NULL AND NEW.ended_at IS NOT NULL THEN
+        kind := 'repair_closed';
+    ELSIF OLD.ended_at IS NOT NULL AND NEW.ended_at IS NULL THEN
+        kind := 'repair_opened';
+    ELSE
+        RETURN NULL;
+    END IF;
+
+    PERFORM pg_notify('incident_events', json_build_object(
+        'kind', kind,
+        'buildingId', NEW.building_id,
+        'incidentId', NEW.incident_id,
+        'repairId', NEW.id,
+        'at', now()
+    )::text);
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS repair_event ON repair;
+CREATE TRIGGER repair_event AFTER INSERT OR UPDATE OF ended_at ON repair
+FOR EACH ROW EXECUTE FUNCTION notify_repair_event();
+
+COMMIT TRANSACTION;
@@ -0,0 +1,118 @@
+-- Outgoing webhooks: events of the live feed are also written to an outbox in the transaction
+-- that changed the incident or repair, and a worker delivers them to subscribed URLs
+
+BEGIN TRANSACTION;
+
+DO $$
+BEGIN
+    BEGIN
+        CREATE TYPE event_kind AS ENUM (
+            'incident_created', 'incident_status_changed', 'repair_opened', 'repair_closed'
+        );
+    EXCEPTION
+        WHEN duplicate_object THEN
+            -- Do nothing, type already exists
+    END;
+END $$;
+
+CREATE TABLE IF NOT EXISTS webhook_subscription (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v1mc(),
+    url text NOT NULL,
+    secret text NOT NULL, -- HMAC key of the X-Webhook-Signature header
+    event_types event_kind[] NOT NULL,
+    created_at timestamp WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One row per event and subscription, so every subscriber is retried on its own.
+CREATE TABLE IF NOT EXISTS webhook_outbox (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v1mc(),
+    subscription_id uuid NOT NULL REFERENCES webhook_subscription(id) ON DELETE CASCADE,
+    event_type event_kind NOT NULL,
+    payload jsonb NOT NULL,
+    created_at timestamp WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    attempts integer NOT NULL DEFAULT 0,
+    next_attempt_at timestamp WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP, -- NULL once settled
+    delivered_at timestamp WITH TIME ZONE,
+    failed_at timestamp WITH TIME ZONE -- set when the worker gives up
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_outbox_next_attempt_at
+ON webhook_outbox(next_attempt_at) WHERE next_attempt_at IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS webhook_delivery (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v1mc(),
+    outbox_id uuid NOT NULL REFERENCES webhook_outbox(id) ON DELETE CASCADE,
+    attempt integer NOT NULL,
+    attempted_at timestamp WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    response_status integer, -- NULL if no response was received
+    error text,
+    duration_ms integer NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_delivery_outbox_id ON webhook_delivery(outbox_id);
+CREATE INDEX IF NOT EXISTS idx_webhook_delivery_attempted_at ON webhook_delivery(attempted_at);
+
+-- Both the live feed and the outbox receive every event.
+CREATE OR REPLACE FUNCTION publish_event(event jsonb) RETURNS void AS $$
+BEGIN
+    PERFORM pg_notify('incident_events', event::text);
+
+    INSERT INTO webhook_outbox (subscription_id, event_type, payload)
+    SELECT id, (event->>'kind')::event_kind, event
+    FROM webhook_subscription
+    WHERE (event->>'kind')::event_kind = ANY(event_types);
+END
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION notify_incident_event() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM publish_event(jsonb_build_object(
+            'kind', 'incident_created',
+            'buildingId', NEW.building_id,
+            'incidentId', NEW.id,
+            'status', incident_status_name(NEW.status),
+            'at', now()
+        ));
+    ELSIF NEW.status IS DISTINCT FROM OLD.status AND NEW.deleted_at IS NULL THEN
+        PERFORM publish_event(jsonb_build_object(
+            'kind', 'incident_status_changed',
+            'buildingId', NEW.building_id,
+            'incidentId', NEW.id,
+            'status', incident_status_name(NEW.status),
+            'previousStatus', incident_status_name(OLD.status),
+            'at', now()
+        ));
+    END IF;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION notify_repair_event() RETURNS trigger AS $$
+DECLARE
+    kind text;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        kind := CASE WHEN NEW.ended_at IS NULL THEN 'repair_opened' ELSE 'repair_closed' END;
+    ELSIF NEW.deleted_at IS NOT NULL THEN
+        RETURN NULL;
+    ELSIF OLD.ended_at IS NULL AND NEW.ended_at IS NOT NULL THEN
+        kind := 'repair_closed';
+    ELSIF OLD.ended_at IS NOT NULL AND NEW.ended_at IS NULL THEN
+        kind := 'repair_opened';
+    ELSE
+        RETURN NULL;
+    END IF;
+
+    PERFORM publish_event(jsonb_build_object(
+        'kind', kind,
+        'buildingId', NEW.building_id,
+        'incidentId', NEW.incident_id,
+        'repairId', NEW.id,
+        'at', now()
+    ));
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+COMMIT TRANSACTION;
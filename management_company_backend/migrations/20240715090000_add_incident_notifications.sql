@@ -0,0 +1,53 @@
+-- Email notifications to committee members: incident changes queue a notification in the
+-- transaction that made them, a worker renders and sends the emails
+
+BEGIN TRANSACTION;
+
+DO $$
+BEGIN
+    BEGIN
+        CREATE TYPE notification_kind AS ENUM ('incident_created', 'incident_status_changed', 'sla_breached');
+    EXCEPTION
+        WHEN duplicate_object THEN
+            -- Do nothing, type already exists
+    END;
+END $$;
+
+CREATE TABLE IF NOT EXISTS notification_outbox (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v1mc(),
+    kind notification_kind NOT NULL,
+    incident_id uuid NOT NULL REFERENCES incident(id) ON DELETE CASCADE,
+    status incident_status NOT NULL, -- status the notification is about
+    previous_status incident_status,
+    created_at timestamp WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    attempts integer NOT NULL DEFAULT 0,
+    next_attempt_at timestamp WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP, -- NULL once settled
+    sent_at timestamp WITH TIME ZONE,
+    failed_at timestamp WITH TIME ZONE -- set when the worker gives up
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_outbox_next_attempt_at
+ON notification_outbox(next_attempt_at) WHERE next_attempt_at IS NOT NULL;
+
+-- An incident breaches its SLA once, however many backend instances notice it.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notification_outbox_sla_breached
+ON notification_outbox(incident_id) WHERE kind = 'sla_breached';
+
+CREATE OR REPLACE FUNCTION queue_incident_notification() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO notification_outbox (kind, incident_id, status)
+        VALUES ('incident_created', NEW.id, NEW.status);
+    ELSIF NEW.status IS DISTINCT FROM OLD.status AND NEW.deleted_at IS NULL THEN
+        INSERT INTO notification_outbox (kind, incident_id, status, previous_status)
+        VALUES ('incident_status_changed', NEW.id, NEW.status, OLD.status);
+    END IF;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS incident_notification ON incident;
+CREATE TRIGGER incident_notification AFTER INSERT OR UPDATE OF status ON incident
+FOR EACH ROW EXECUTE FUNCTION queue_incident_notification();
+
+COMMIT TRANSACTION;
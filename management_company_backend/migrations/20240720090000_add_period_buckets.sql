@@ -0,0 +1,24 @@
+-- Steps of the statistics series: every local day, week or month of the company time zone
+-- that overlaps [period_start, period_end), with the first and last step clipped to the period
+
+BEGIN TRANSACTION;
+
+CREATE OR REPLACE FUNCTION period_buckets(
+    period_start timestamptz,
+    period_end timestamptz,
+    time_zone text,
+    unit text
+) RETURNS TABLE (bucket timestamp, bucket_start timestamptz, bucket_end timestamptz) AS $$
+    SELECT
+        step,
+        GREATEST(step AT TIME ZONE time_zone, period_start),
+        LEAST((step + ('1 ' || unit)::interval) AT TIME ZONE time_zone, period_end)
+    FROM
+        generate_series(
+            date_trunc(unit, period_start AT TIME ZONE time_zone),
+            date_trunc(unit, (period_end - INTERVAL '1 microsecond') AT TIME ZONE time_zone),
+            ('1 ' || unit)::interval
+        ) AS step
+$$ LANGUAGE sql STABLE;
+
+COMMIT TRANSACTION;
@@ -0,0 +1,16 @@
+-- Row versions for buildings and repairs, so deleting them can be checked with If-Match too
+
+BEGIN TRANSACTION;
+
+ALTER TABLE building ADD COLUMN IF NOT EXISTS version integer NOT NULL DEFAULT 1;
+ALTER TABLE repair ADD COLUMN IF NOT EXISTS version integer NOT NULL DEFAULT 1;
+
+DROP TRIGGER IF EXISTS building_version ON building;
+CREATE TRIGGER building_version BEFORE UPDATE ON building
+FOR EACH ROW EXECUTE FUNCTION bump_row_version();
+
+DROP TRIGGER IF EXISTS repair_version ON repair;
+CREATE TRIGGER repair_version BEFORE UPDATE ON repair
+FOR EACH ROW EXECUTE FUNCTION bump_row_version();
+
+COMMIT TRANSACTION;
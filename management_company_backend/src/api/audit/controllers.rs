@@ -0,0 +1,185 @@
+use axum::{extract::State, Json};
+
+use crate::api::{
+    envelope::{Envelope, ErrorEnvelope},
+    extractor::AdminUser,
+    pagination::{fetch_page, ListQuery, ListSource, PageParams},
+    ApiContext, Error,
+};
+
+use super::models::{AuditEntry, AuditFilter, AuditSortField};
+
+const AUDIT_LOG: ListSource = ListSource {
+    select: r#"
+        SELECT
+            a.id,
+            a.actor_id,
+            a.action,
+            a.entity_type,
+            a.entity_id,
+            a.before,
+            a.after,
+            a.created_at
+        "#,
+    from: r#"
+        FROM
+            audit_log a
+        "#,
+    condition: "TRUE",
+    id_column: "a.id",
+};
+
+/// Журнал изменений: по записи (`entityType` и `entityId`), по пользователю или за период.
+#[utoipa::path(
+    get,
+    path = "/api/v2/audit_log",
+    tag = "audit",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<AuditSortField>, Query, description = "Поле сортировки"),
+        AuditFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница журнала изменений", body = Envelope<Vec<AuditEntry>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 403, description = "Нужны права администратора", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_audit_log(
+    _: AdminUser,
+    State(ctx): State<ApiContext>,
+    query: ListQuery<AuditFilter, AuditSortField>,
+) -> Result<Json<Envelope<Vec<AuditEntry>>>, Error> {
+    let (entries, page_info) =
+        fetch_page(&ctx.db, &AUDIT_LOG, &query, |entry: &AuditEntry| entry.id).await?;
+
+    Ok(Envelope::page(entries, page_info))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{
+            header::{AUTHORIZATION, CONTENT_TYPE, IF_MATCH},
+            Method, Request, StatusCode,
+        },
+        Router,
+    };
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::api::{api_router, extractor::AuthUser, test_context, ApiContext};
+
+    const PETROVA: Uuid = Uuid::from_u128(0xe2);
+    const SIDOROV: Uuid = Uuid::from_u128(0xe3);
+
+    async fn sign_up(ctx: &ApiContext, email: &str, is_admin: bool) -> (Uuid, String) {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO user_account (email, password_hash, employee_id, is_admin)
+            VALUES (($1::text)::domain_email, '', $2, $3)
+            RETURNING id
+            "#,
+            email,
+            PETROVA,
+            is_admin
+        )
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+        (user_id, AuthUser { user_id }.to_jwt(ctx))
+    }
+
+    async fn send(app: &Router, method: Method, uri: &str, token: &str) -> (StatusCode, Value) {
+        send_json(app, method, uri, token, Value::Null).await
+    }
+
+    async fn send_json(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(AUTHORIZATION, format!("Bearer {token}"))
+                    .header(CONTENT_TYPE, "application/json")
+                    // Проверка версий покрыта тестами `conditional`.
+                    .header(IF_MATCH, "*")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn changes_are_logged_and_visible_to_admins_only(pool: PgPool) {
+        let ctx = test_context(pool);
+        let (_, admin) = sign_up(&ctx, "admin@example.com", true).await;
+        let (clerk_id, clerk) = sign_up(&ctx, "clerk@example.com", false).await;
+        let app = api_router(ctx);
+
+        let uri = format!("/api/v2/employees/{PETROVA}");
+        let phone = json!({ "phone": "+7 900 000-00-00" });
+        let (status, _) = send_json(&app, Method::PUT, &uri, &clerk, phone.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        // Повтор без изменений в журнал не попадает.
+        send_json(&app, Method::PUT, &uri, &clerk, phone).await;
+        let uri = format!("/api/v2/employees/{SIDOROV}");
+        let (status, _) = send(&app, Method::DELETE, &uri, &clerk).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let log = format!("/api/v2/audit_log?entityType=employee&entityId={PETROVA}");
+        let (status, _) = send(&app, Method::GET, &log, &clerk).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = send(&app, Method::GET, &log, &admin).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["meta"]["total"], 1);
+        let update = &body["data"][0];
+        assert_eq!(update["action"], "update");
+        assert_eq!(update["actorId"], json!(clerk_id));
+        assert_eq!(update["before"], json!({ "phone": null, "version": 1 }));
+        assert_eq!(
+            update["after"],
+            json!({ "phone": "+7 900 000-00-00", "version": 2 })
+        );
+
+        let by_actor = format!("/api/v2/audit_log?actorId={clerk_id}");
+        let (_, body) = send(&app, Method::GET, &by_actor, &admin).await;
+        assert_eq!(body["meta"]["total"], 2);
+        let delete = &body["data"][0];
+        assert_eq!(delete["action"], "delete");
+        assert_eq!(delete["entityId"], json!(SIDOROV));
+        assert_eq!(delete["before"]["last_name"], "Сидоров");
+        assert_eq!(delete["after"], Value::Null);
+
+        let (_, body) = send(
+            &app,
+            Method::GET,
+            "/api/v2/audit_log?createdTo=2000-01-01",
+            &admin,
+        )
+        .await;
+        assert_eq!(body["meta"]["total"], 0);
+    }
+}
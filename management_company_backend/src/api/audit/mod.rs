@@ -0,0 +1,22 @@
+use axum::{routing::get, Router};
+use controllers::get_audit_log;
+use utoipa::OpenApi;
+
+use super::ApiContext;
+
+mod controllers;
+pub mod models;
+pub mod utils;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(controllers::get_audit_log),
+    components(schemas(models::AuditSortField, models::AuditEntity, models::AuditAction)),
+    tags((name = "audit", description = "Журнал изменений"))
+)]
+pub(crate) struct ApiDoc;
+
+/// Журнал появился после выхода v2, поэтому есть только в ней.
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new().route("/api/v2/audit_log", get(get_audit_log))
+}
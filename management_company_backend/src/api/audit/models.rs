@@ -0,0 +1,120 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::Type, FromRow, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::pagination::{ListFilter, SortField, SortOrder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Type, ToSchema)]
+#[sqlx(type_name = "audit_action", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// Вид изменённой записи; в журнале хранится как `entity_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEntity {
+    User,
+    Employee,
+    Building,
+    Apartment,
+    Incident,
+    Repair,
+    Webhook,
+}
+
+impl AuditEntity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Employee => "employee",
+            Self::Building => "building",
+            Self::Apartment => "apartment",
+            Self::Incident => "incident",
+            Self::Repair => "repair",
+            Self::Webhook => "webhook",
+        }
+    }
+}
+
+/// Запись журнала: кто, когда и что изменил. В `before` и `after` — только изменившиеся поля.
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub id: Uuid,
+    /// Пользователь, выполнивший запрос; при регистрации — сам новый пользователь.
+    pub actor_id: Option<Uuid>,
+    pub action: AuditAction,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct AuditFilter {
+    pub entity_type: Option<AuditEntity>,
+    pub entity_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub created_from: Option<NaiveDate>,
+    pub created_to: Option<NaiveDate>,
+}
+
+impl ListFilter for AuditFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(entity_type) = self.entity_type {
+            builder
+                .push(" AND a.entity_type = ")
+                .push_bind(entity_type.as_str());
+        }
+        if let Some(entity_id) = self.entity_id {
+            builder.push(" AND a.entity_id = ").push_bind(entity_id);
+        }
+        if let Some(actor_id) = self.actor_id {
+            builder.push(" AND a.actor_id = ").push_bind(actor_id);
+        }
+        if let Some(action) = self.action {
+            builder.push(" AND a.action = ").push_bind(action);
+        }
+        if let Some(created_from) = self.created_from {
+            builder
+                .push(" AND a.created_at >= ")
+                .push_bind(created_from)
+                .push("::date");
+        }
+        if let Some(created_to) = self.created_to {
+            builder
+                .push(" AND a.created_at < ")
+                .push_bind(created_to)
+                .push("::date + 1");
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditSortField {
+    #[default]
+    CreatedAt,
+}
+
+impl SortField for AuditSortField {
+    fn expression(self) -> &'static str {
+        match self {
+            Self::CreatedAt => "a.created_at",
+        }
+    }
+
+    fn default_order(self) -> SortOrder {
+        SortOrder::Desc
+    }
+}
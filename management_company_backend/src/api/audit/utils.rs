@@ -0,0 +1,141 @@
+use serde_json::{Map, Value};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::api::Error;
+
+use super::models::{AuditAction, AuditEntity};
+
+/// Изменение одной записи, которое нужно занести в журнал.
+///
+/// Снимки `before` и `after` — строки таблицы в JSON (`to_jsonb`); для изменения в журнал
+/// попадают только поля, значения которых различаются.
+pub struct AuditRecord {
+    action: AuditAction,
+    entity: AuditEntity,
+    entity_id: Uuid,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+impl AuditRecord {
+    pub fn created(entity: AuditEntity, entity_id: Uuid, after: Value) -> Self {
+        Self {
+            action: AuditAction::Create,
+            entity,
+            entity_id,
+            before: None,
+            after: Some(after),
+        }
+    }
+
+    pub fn updated(entity: AuditEntity, entity_id: Uuid, before: Value, after: Value) -> Self {
+        let (before, after) = changed_fields(before, after);
+        Self {
+            action: AuditAction::Update,
+            entity,
+            entity_id,
+            before: Some(before),
+            after: Some(after),
+        }
+    }
+
+    pub fn deleted(entity: AuditEntity, entity_id: Uuid, before: Value) -> Self {
+        Self {
+            action: AuditAction::Delete,
+            entity,
+            entity_id,
+            before: Some(before),
+            after: None,
+        }
+    }
+
+    fn is_noop(&self) -> bool {
+        self.action == AuditAction::Update && self.after.as_ref().is_some_and(is_empty_object)
+    }
+
+    /// Записать изменение в той же транзакции, что и само изменение: без записи в журнале
+    /// оно не сохранится. Изменение, после которого запись не поменялась, не записывается.
+    pub async fn save(self, conn: &mut PgConnection, actor_id: Option<Uuid>) -> Result<(), Error> {
+        if self.is_noop() {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_log (actor_id, action, entity_type, entity_id, before, after)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            actor_id,
+            self.action as AuditAction,
+            self.entity.as_str(),
+            self.entity_id,
+            self.before,
+            self.after,
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn is_empty_object(value: &Value) -> bool {
+    value.as_object().is_some_and(Map::is_empty)
+}
+
+/// Оставить в снимках только поля, которые различаются; снимки не-объекты остаются как есть.
+fn changed_fields(before: Value, after: Value) -> (Value, Value) {
+    let (mut before, mut after) = match (before, after) {
+        (Value::Object(before), Value::Object(after)) => (before, after),
+        snapshots => return snapshots,
+    };
+
+    let unchanged: Vec<String> = before
+        .iter()
+        .filter(|(key, value)| after.get(*key) == Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in unchanged {
+        before.remove(&key);
+        after.remove(&key);
+    }
+
+    (Value::Object(before), Value::Object(after))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn updates_keep_only_changed_fields() {
+        let record = AuditRecord::updated(
+            AuditEntity::Employee,
+            Uuid::nil(),
+            json!({ "first_name": "Иван", "phone": "+7 900", "middle_name": null }),
+            json!({ "first_name": "Иван", "phone": "+7 901", "middle_name": "Петрович" }),
+        );
+
+        assert_eq!(
+            record.before,
+            Some(json!({ "phone": "+7 900", "middle_name": null }))
+        );
+        assert_eq!(
+            record.after,
+            Some(json!({ "phone": "+7 901", "middle_name": "Петрович" }))
+        );
+        assert!(!record.is_noop());
+    }
+
+    #[test]
+    fn updates_without_changes_are_not_recorded() {
+        let row = json!({ "first_name": "Иван" });
+        let record = AuditRecord::updated(AuditEntity::Employee, Uuid::nil(), row.clone(), row);
+
+        assert!(record.is_noop());
+        assert!(!AuditRecord::created(AuditEntity::Employee, Uuid::nil(), json!({})).is_noop());
+    }
+}
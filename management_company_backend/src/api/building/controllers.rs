@@ -1,31 +1,66 @@
-use axum::{extract::State, Json};
-use sqlx::query;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
 
-use crate::api::{ApiContext, Error};
+use crate::api::{
+    audit::{models::AuditEntity, utils::AuditRecord},
+    conditional::IfMatch,
+    extractor::AuthUser,
+    pagination::{fetch_page, ListQuery, ListSource, PageParams},
+    trash::{models::TrashEntity, utils::soft_delete},
+    ApiContext, Error,
+};
 
-use super::models::{Address, Building, BuildingList};
+use super::models::{
+    Address, Building, BuildingFilter, BuildingList, BuildingRow, BuildingSortField,
+};
 
-pub async fn get_all_buildings(State(ctx): State<ApiContext>) -> Result<Json<BuildingList>, Error> {
-    let db_buildings = query!(
-        r#"
+const BUILDING_LIST: ListSource = ListSource {
+    select: r#"
         SELECT
             b.id,
             b.number,
             b.construction_date,
             b.number_of_floors,
-            a.id AS address_id,
             a.country,
             a.region,
             a.city,
-            a.street
+            a.street,
+            b.version
+        "#,
+    from: r#"
         FROM
             building b
         JOIN
             address a ON b.address_id = a.id
-        "#
+        "#,
+    condition: "b.deleted_at IS NULL",
+    id_column: "b.id",
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/buildings",
+    tag = "buildings",
+    params(
+        PageParams,
+        ("sortBy" = Option<BuildingSortField>, Query, description = "Поле сортировки"),
+        BuildingFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка домов", body = BuildingList),
+        (status = 422, description = "Неверные параметры списка"),
     )
-    .fetch_all(&ctx.db)
-    .await?;
+)]
+pub async fn get_all_buildings(
+    State(ctx): State<ApiContext>,
+    query: ListQuery<BuildingFilter, BuildingSortField>,
+) -> Result<Json<BuildingList>, Error> {
+    let query = query.whole_list_unless_paged();
+    let (db_buildings, page_info) =
+        fetch_page(&ctx.db, &BUILDING_LIST, &query, |row: &BuildingRow| row.id).await?;
 
     let buildings = db_buildings
         .into_iter()
@@ -40,8 +75,48 @@ pub async fn get_all_buildings(State(ctx): State<ApiContext>) -> Result<Json<Bui
                 city: row.city.unwrap_or_default(),
                 street: row.street.unwrap_or_default(),
             },
+            version: row.version,
         })
         .collect();
 
-    Ok(Json(BuildingList { buildings }))
+    Ok(Json(BuildingList {
+        buildings,
+        page_info,
+    }))
+}
+
+/// Удалить дом в корзину вместе с его квартирами, авариями и ремонтами; маршрут есть только в v2.
+pub async fn delete_building(
+    user: AuthUser,
+    State(ctx): State<ApiContext>,
+    Path(id): Path<Uuid>,
+    if_match: IfMatch,
+) -> Result<(), Error> {
+    let mut transaction = ctx.db.begin().await?;
+
+    let version = sqlx::query_scalar!(
+        r#"
+        SELECT version
+        FROM building
+        WHERE id = $1 AND deleted_at IS NULL
+        FOR UPDATE
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(Error::BuildingNotFound)?;
+    if_match.check(version)?;
+
+    let before = soft_delete(&mut transaction, TrashEntity::Building, id)
+        .await?
+        .ok_or(Error::BuildingNotFound)?;
+    AuditRecord::deleted(AuditEntity::Building, id, before)
+        .save(&mut transaction, Some(user.user_id))
+        .await?;
+    transaction.commit().await?;
+
+    ctx.statistics_cache.invalidate();
+
+    Ok(())
 }
@@ -1,10 +1,24 @@
 use axum::{routing::get, Router};
 use controllers::get_all_buildings;
+use utoipa::OpenApi;
 
 use super::ApiContext;
 
 mod controllers;
 pub mod models;
+pub(crate) mod v2;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::get_all_buildings,
+        v2::get_all_buildings,
+        v2::delete_building,
+    ),
+    components(schemas(models::BuildingSortField)),
+    tags((name = "buildings", description = "Дома"))
+)]
+pub(crate) struct ApiDoc;
 
 pub(crate) fn router() -> Router<ApiContext> {
     Router::new().route("/api/buildings", get(get_all_buildings))
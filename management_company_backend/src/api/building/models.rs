@@ -1,8 +1,12 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize)]
+use crate::api::pagination::{ListFilter, PageInfo, SortField};
+
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Address {
     pub country: String,
@@ -11,7 +15,7 @@ pub struct Address {
     pub street: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Building {
     pub id: Uuid,
@@ -19,10 +23,69 @@ pub struct Building {
     pub number_of_floors: i16,
     pub address: Address,
     pub constructed_date: NaiveDate,
+    /// Версия записи; её же сервер ждёт в `If-Match` при удалении.
+    pub version: i32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BuildingList {
     pub buildings: Vec<Building>,
+    #[serde(flatten)]
+    pub page_info: PageInfo,
+}
+
+#[derive(FromRow)]
+pub struct BuildingRow {
+    pub id: Uuid,
+    pub number: i32,
+    pub construction_date: NaiveDate,
+    pub number_of_floors: i16,
+    pub country: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub street: Option<String>,
+    pub version: i32,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct BuildingFilter {
+    pub city: Option<String>,
+    pub committee_id: Option<Uuid>,
+}
+
+impl ListFilter for BuildingFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(city) = &self.city {
+            builder.push(" AND a.city = ").push_bind(city.clone());
+        }
+        if let Some(committee_id) = self.committee_id {
+            builder
+                .push(" AND b.committee_id = ")
+                .push_bind(committee_id);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BuildingSortField {
+    #[default]
+    Address,
+    Number,
+    ConstructionDate,
+    NumberOfFloors,
+}
+
+impl SortField for BuildingSortField {
+    fn expression(self) -> &'static str {
+        match self {
+            Self::Address => "CONCAT_WS(', ', a.city, a.street, LPAD(b.number::text, 6, '0'))",
+            Self::Number => "b.number",
+            Self::ConstructionDate => "b.construction_date",
+            Self::NumberOfFloors => "b.number_of_floors",
+        }
+    }
 }
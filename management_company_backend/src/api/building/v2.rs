@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::api::{
+    conditional::IfMatch,
+    envelope::{Envelope, ErrorEnvelope},
+    extractor::AuthUser,
+    pagination::{ListQuery, PageParams},
+    ApiContext, Error,
+};
+
+use super::{
+    controllers,
+    models::{Building, BuildingFilter, BuildingSortField},
+};
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/api/v2/buildings", get(get_all_buildings))
+        .route("/api/v2/buildings/:id", delete(delete_building))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/buildings",
+    tag = "buildings",
+    params(
+        PageParams,
+        ("sortBy" = Option<BuildingSortField>, Query, description = "Поле сортировки"),
+        BuildingFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка домов", body = Envelope<Vec<Building>>),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_all_buildings(
+    ctx: State<ApiContext>,
+    query: ListQuery<BuildingFilter, BuildingSortField>,
+) -> Result<Json<Envelope<Vec<Building>>>, Error> {
+    let Json(list) = controllers::get_all_buildings(ctx, query.paged()).await?;
+    Ok(Envelope::page(list.buildings, list.page_info))
+}
+
+/// Удалить дом в корзину вместе с его квартирами, авариями и ремонтами.
+#[utoipa::path(
+    delete,
+    path = "/api/v2/buildings/{id}",
+    tag = "buildings",
+    security(("bearer" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Идентификатор дома"),
+        ("If-Match" = String, Header, description = "`ETag` удаляемой версии"),
+    ),
+    responses(
+        (status = 204, description = "Дом удалён"),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Дом не найден", body = ErrorEnvelope),
+        (status = 412, description = "Дом уже изменили", body = ErrorEnvelope),
+        (status = 428, description = "Нет заголовка `If-Match`", body = ErrorEnvelope),
+    )
+)]
+pub async fn delete_building(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    id: Path<Uuid>,
+    if_match: IfMatch,
+) -> Result<StatusCode, Error> {
+    controllers::delete_building(user, ctx, id, if_match).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
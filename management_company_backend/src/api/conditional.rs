@@ -0,0 +1,370 @@
+use axum::{
+    async_trait,
+    body::Body,
+    extract::{FromRequestParts, Request},
+    http::{
+        header::{ETAG, IF_MATCH, IF_NONE_MATCH},
+        request::Parts,
+        HeaderMap, HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::Error;
+
+/// `ETag` ресурса — номер версии его строки. Версию увеличивает триггер `bump_row_version`
+/// при каждом изменении строки, поэтому тег меняется, только когда ресурс действительно изменён.
+pub fn etag(version: i32) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{version}\"")).expect("quoted number is a valid header value")
+}
+
+/// Ответ с версией ресурса в `ETag`.
+pub struct Tagged<T> {
+    pub version: i32,
+    pub body: T,
+}
+
+impl<T> Tagged<T> {
+    pub fn new(version: i32, body: T) -> Self {
+        Self { version, body }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Tagged<U> {
+        Tagged::new(self.version, f(self.body))
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for Tagged<T> {
+    fn into_response(self) -> Response {
+        ([(ETAG, etag(self.version))], self.body).into_response()
+    }
+}
+
+/// Условие `If-Match` запроса на изменение.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfMatch {
+    /// Заголовка нет.
+    Absent,
+    /// `If-Match: *` — подходит любая версия существующего ресурса.
+    Any,
+    /// Перечисленные теги; слабые и нечисловые теги не совпадают ни с одной версией.
+    Tags(Vec<String>),
+}
+
+impl IfMatch {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let mut tags = Vec::new();
+        for value in headers.get_all(IF_MATCH) {
+            let Ok(value) = value.to_str() else {
+                tags.push(String::new());
+                continue;
+            };
+            for tag in value
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+            {
+                if tag == "*" {
+                    return Self::Any;
+                }
+                tags.push(tag.to_string());
+            }
+        }
+
+        if tags.is_empty() {
+            Self::Absent
+        } else {
+            Self::Tags(tags)
+        }
+    }
+
+    /// Проверить условие по текущей версии ресурса. Изменение без `If-Match` отклоняется с 428:
+    /// клиент должен показать, какую версию он видел, иначе затрёт чужую правку. При
+    /// несовпадении версии — 412.
+    pub fn check(&self, version: i32) -> Result<(), Error> {
+        let current = etag(version);
+        match self {
+            Self::Absent => Err(Error::PreconditionRequired),
+            Self::Any => Ok(()),
+            Self::Tags(tags) if tags.iter().any(|tag| tag.as_bytes() == current.as_bytes()) => {
+                Ok(())
+            }
+            Self::Tags(_) => Err(Error::PreconditionFailed),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// Ответить `304 Not Modified` на GET, если `ETag` ответа есть в `If-None-Match` запроса.
+///
+/// Ресурс всё равно читается из базы, но клиент, который опрашивает его по таймеру, не получает
+/// тело заново, пока ресурс не изменится.
+pub async fn not_modified(request: Request, next: Next) -> Response {
+    let if_none_match = (request.method() == Method::GET)
+        .then(|| request.headers().get(IF_NONE_MATCH).cloned())
+        .flatten();
+    let response = next.run(request).await;
+
+    let (Some(if_none_match), Some(current)) = (if_none_match, response.headers().get(ETAG)) else {
+        return response;
+    };
+    if response.status() != StatusCode::OK || !matches_any(&if_none_match, current) {
+        return response;
+    }
+
+    let mut not_modified = Response::new(Body::empty());
+    *not_modified.status_mut() = StatusCode::NOT_MODIFIED;
+    not_modified.headers_mut().insert(ETAG, current.clone());
+    not_modified
+}
+
+/// Слабое сравнение, как требует RFC 9110 для `If-None-Match`: `W/` не учитывается.
+fn matches_any(if_none_match: &HeaderValue, current: &HeaderValue) -> bool {
+    let Ok(if_none_match) = if_none_match.to_str() else {
+        return false;
+    };
+    let current = current.as_bytes();
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/").as_bytes() == current)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        http::{
+            header::{AUTHORIZATION, CONTENT_TYPE},
+            HeaderName,
+        },
+        middleware,
+        routing::get,
+        Router,
+    };
+    use serde_json::json;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::api::{api_router, extractor::AuthUser, test_context};
+
+    const PETROVA: Uuid = Uuid::from_u128(0xe2);
+    const LEAK: Uuid = Uuid::from_u128(0x202);
+
+    fn if_match(value: &str) -> IfMatch {
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_MATCH, HeaderValue::from_str(value).unwrap());
+        IfMatch::from_headers(&headers)
+    }
+
+    #[test]
+    fn if_match_compares_versions_strongly() {
+        assert!(if_match("\"3\"").check(3).is_ok());
+        assert!(if_match("\"2\", \"3\"").check(3).is_ok());
+        assert!(if_match("*").check(3).is_ok());
+        assert!(matches!(
+            if_match("\"2\"").check(3),
+            Err(Error::PreconditionFailed)
+        ));
+        assert!(matches!(
+            if_match("W/\"3\"").check(3),
+            Err(Error::PreconditionFailed)
+        ));
+        assert!(matches!(
+            IfMatch::from_headers(&HeaderMap::new()).check(3),
+            Err(Error::PreconditionRequired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unchanged_resources_are_not_sent_again() {
+        let app = Router::new()
+            .route("/employee", get(|| async { Tagged::new(3, "{}") }))
+            .layer(middleware::from_fn(not_modified));
+        let get = |tag: &'static str| {
+            app.clone().oneshot(
+                Request::get("/employee")
+                    .header(IF_NONE_MATCH, tag)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        let unchanged = get("W/\"3\"").await.unwrap();
+        assert_eq!(unchanged.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(unchanged.headers()[ETAG], "\"3\"");
+
+        let changed = get("\"2\"").await.unwrap();
+        assert_eq!(changed.status(), StatusCode::OK);
+        assert_eq!(changed.headers()[ETAG], "\"3\"");
+    }
+
+    async fn send(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        token: &str,
+        headers: Vec<(HeaderName, &str)>,
+        body: Option<serde_json::Value>,
+    ) -> Response {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .header(CONTENT_TYPE, "application/json");
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let body = body.map_or_else(Body::empty, |body| Body::from(body.to_string()));
+        app.clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test(fixtures("statistics/fixtures/statistics.sql"))]
+    async fn stale_versions_are_rejected(pool: PgPool) {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO user_account (email, password_hash, employee_id)
+            VALUES ('dispatcher@example.com', '', $1)
+            RETURNING id
+            "#,
+            PETROVA
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let ctx = test_context(pool);
+        let token = AuthUser { user_id }.to_jwt(&ctx);
+        let app = api_router(ctx);
+
+        let uri = format!("/api/v2/employees/{PETROVA}");
+        let read = send(&app, Method::GET, &uri, &token, vec![], None).await;
+        assert_eq!(read.status(), StatusCode::OK);
+        assert_eq!(read.headers()[ETAG], "\"1\"");
+
+        let phone = json!({ "phone": "+7 900 000-00-00" });
+        let put = |tag: &'static str| {
+            let headers = if tag.is_empty() {
+                vec![]
+            } else {
+                vec![(IF_MATCH, tag)]
+            };
+            send(
+                &app,
+                Method::PUT,
+                &uri,
+                &token,
+                headers,
+                Some(phone.clone()),
+            )
+        };
+        assert_eq!(put("").await.status(), StatusCode::PRECONDITION_REQUIRED);
+        assert_eq!(put("\"0\"").await.status(), StatusCode::PRECONDITION_FAILED);
+        let updated = put("\"1\"").await;
+        assert_eq!(updated.status(), StatusCode::OK);
+        assert_eq!(updated.headers()[ETAG], "\"2\"");
+        // Второй диспетчер видел первую версию, и его изменение не затирает чужое.
+        assert_eq!(put("\"1\"").await.status(), StatusCode::PRECONDITION_FAILED);
+        // Запись без изменений версию не увеличивает.
+        assert_eq!(put("\"2\"").await.headers()[ETAG], "\"2\"");
+
+        let poll = |tag: &'static str| {
+            send(
+                &app,
+                Method::GET,
+                &uri,
+                &token,
+                vec![(IF_NONE_MATCH, tag)],
+                None,
+            )
+        };
+        assert_eq!(poll("\"2\"").await.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(poll("\"1\"").await.status(), StatusCode::OK);
+
+        let v1 = format!("/api/employee/{PETROVA}");
+        let legacy = |headers: Vec<(HeaderName, &'static str)>| {
+            send(
+                &app,
+                Method::PUT,
+                &v1,
+                &token,
+                headers,
+                Some(json!({ "phone": "" })),
+            )
+        };
+        assert_eq!(
+            legacy(vec![]).await.status(),
+            StatusCode::PRECONDITION_REQUIRED
+        );
+        let updated = legacy(vec![(IF_MATCH, "\"2\"")]).await;
+        assert_eq!(updated.status(), StatusCode::OK);
+        assert_eq!(updated.headers()[ETAG], "\"3\"");
+        let deleted = send(&app, Method::DELETE, &v1, &token, vec![], None).await;
+        assert_eq!(deleted.status(), StatusCode::PRECONDITION_REQUIRED);
+
+        let uri = format!("/api/v2/incidents/{LEAK}");
+        let resolve = json!({ "status": "Resolved", "resolvedAt": "2024-01-20T10:00:00Z" });
+        let patch = |tag: &'static str| {
+            send(
+                &app,
+                Method::PATCH,
+                &uri,
+                &token,
+                vec![(IF_MATCH, tag)],
+                Some(resolve.clone()),
+            )
+        };
+        let resolved = patch("\"1\"").await;
+        assert_eq!(resolved.status(), StatusCode::OK);
+        assert_eq!(resolved.headers()[ETAG], "\"2\"");
+        assert_eq!(
+            patch("\"1\"").await.status(),
+            StatusCode::PRECONDITION_FAILED
+        );
+
+        let read = send(
+            &app,
+            Method::GET,
+            &uri,
+            &token,
+            vec![(IF_NONE_MATCH, "\"2\"")],
+            None,
+        )
+        .await;
+        assert_eq!(read.status(), StatusCode::NOT_MODIFIED);
+
+        let repairs = send(&app, Method::GET, "/api/v2/repairs", &token, vec![], None).await;
+        let bytes = axum::body::to_bytes(repairs.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let repairs: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let repair = &repairs["data"][0];
+        assert_eq!(repair["version"], 1);
+        let uri = format!("/api/v2/repairs/{}", repair["id"].as_str().unwrap());
+        let delete = |headers| send(&app, Method::DELETE, &uri, &token, headers, None);
+        assert_eq!(
+            delete(vec![]).await.status(),
+            StatusCode::PRECONDITION_REQUIRED
+        );
+        assert_eq!(
+            delete(vec![(IF_MATCH, "\"0\"")]).await.status(),
+            StatusCode::PRECONDITION_FAILED
+        );
+        assert_eq!(
+            delete(vec![(IF_MATCH, "\"1\"")]).await.status(),
+            StatusCode::NO_CONTENT
+        );
+    }
+}
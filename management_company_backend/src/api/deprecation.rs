@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{HeaderName, LINK},
+        HeaderValue,
+    },
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+/// Заголовок `Deprecation` (RFC 9745): с какого момента ресурс устарел.
+pub static DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+/// Заголовок `Sunset` (RFC 8594): когда ресурс перестанет отвечать.
+pub static SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// Заголовки, которыми помечаются ответы устаревшей версии API.
+#[derive(Clone)]
+pub struct V1Deprecation {
+    deprecation: HeaderValue,
+    sunset: HeaderValue,
+    link: HeaderValue,
+}
+
+impl V1Deprecation {
+    /// Даты — начала суток по UTC, как их задают `--api-v1-deprecated-at` и `--api-v1-sunset`.
+    pub fn new(deprecated_at: NaiveDate, sunset: NaiveDate) -> Self {
+        let deprecated_at = deprecated_at.and_time(NaiveTime::MIN).and_utc();
+        let sunset = sunset.and_time(NaiveTime::MIN).and_utc();
+        Self {
+            deprecation: HeaderValue::from_str(&format!("@{}", deprecated_at.timestamp()))
+                .expect("timestamp is a valid header value"),
+            sunset: http_date(sunset),
+            link: HeaderValue::from_static(r#"</api/v2>; rel="successor-version""#),
+        }
+    }
+}
+
+fn http_date(at: DateTime<Utc>) -> HeaderValue {
+    HeaderValue::from_str(&at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+        .expect("formatted date is a valid header value")
+}
+
+/// Добавить к ответу `Deprecation`, `Sunset` и ссылку на новую версию.
+pub async fn deprecate_v1(
+    State(headers): State<V1Deprecation>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    let response_headers = response.headers_mut();
+    response_headers.insert(DEPRECATION.clone(), headers.deprecation);
+    response_headers.insert(SUNSET.clone(), headers.sunset);
+    response_headers.append(LINK, headers.link);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        middleware,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn v1_responses_announce_sunset_and_successor() {
+        let deprecated_at = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let sunset = NaiveDate::from_ymd_opt(2027, 4, 30).unwrap();
+        let app = Router::new()
+            .route("/api/buildings", get(|| async { "[]" }))
+            .layer(middleware::from_fn_with_state(
+                V1Deprecation::new(deprecated_at, sunset),
+                deprecate_v1,
+            ));
+
+        let response = app
+            .oneshot(Request::get("/api/buildings").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[&DEPRECATION], "@1792368000");
+        assert_eq!(response.headers()[&SUNSET], "Fri, 30 Apr 2027 00:00:00 GMT");
+        assert_eq!(
+            response.headers()[LINK],
+            r#"</api/v2>; rel="successor-version""#
+        );
+    }
+}
@@ -2,37 +2,73 @@ use axum::{
     extract::{Path, State},
     Json,
 };
-use sqlx::query;
+use sqlx::{query, query_scalar};
 use uuid::Uuid;
 
-use crate::api::{extractor::AuthUser, ApiContext, Error};
+use crate::api::{
+    audit::{models::AuditEntity, utils::AuditRecord},
+    conditional::{IfMatch, Tagged},
+    extractor::AuthUser,
+    money::Money,
+    pagination::{fetch_page, ListQuery, ListSource, PageParams},
+    trash::{models::TrashEntity, utils::soft_delete},
+    ApiContext, Error,
+};
 
 use super::{
     models::{
-        Employee, EmployeeBody, EmployeeDetails, EmployeeDetailsList, NewEmployee, UpdateEmployee,
+        Employee, EmployeeBody, EmployeeDetails, EmployeeDetailsList, EmployeeDetailsRow,
+        EmployeeFilter, EmployeeSortField, NewEmployee, UpdateEmployee,
     },
-    utils::{insert_employee, insert_passport, position_exists},
+    utils::{employee_snapshot, insert_employee, insert_passport, position_exists},
 };
 
+/// Принять сотрудника на работу вместе с его паспортными данными.
+#[utoipa::path(
+    post,
+    path = "/api/employees",
+    tag = "employees",
+    security(("bearer" = [])),
+    request_body = EmployeeBody<NewEmployee>,
+    responses(
+        (status = 200, description = "Сотрудник добавлен", body = Employee,
+            headers(("ETag" = String, description = "Версия сотрудника"))),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 404, description = "Должность не найдена"),
+    )
+)]
 pub async fn add_employee(
-    _: AuthUser,
+    user: AuthUser,
     ctx: State<ApiContext>,
     Json(req): Json<EmployeeBody<NewEmployee>>,
-) -> Result<Json<Employee>, Error> {
+) -> Result<Tagged<Json<Employee>>, Error> {
     if !position_exists(&ctx.db, req.employee.position_id).await? {
         return Err(Error::PositionNotFound);
     }
 
+    let mut transaction = ctx.db.begin().await?;
+
     let passport_id = insert_passport(
-        &ctx.db,
+        &mut *transaction,
         req.employee.passport_series,
         req.employee.passport_number,
     )
     .await?;
 
-    let employee_id = insert_employee(&ctx.db, &req.employee, passport_id).await?;
+    let employee_id = insert_employee(&mut *transaction, &req.employee, passport_id).await?;
+
+    let (version, snapshot) = employee_snapshot(&mut transaction, employee_id)
+        .await?
+        .ok_or(Error::EmployeeNotFound)?;
+    AuditRecord::created(AuditEntity::Employee, employee_id, snapshot)
+        .save(&mut transaction, Some(user.user_id))
+        .await?;
 
-    Ok(Json(Employee {
+    transaction.commit().await?;
+
+    ctx.statistics_cache.invalidate();
+
+    let employee = Employee {
         id: employee_id,
         first_name: req.employee.first_name.clone(),
         last_name: req.employee.last_name.clone(),
@@ -42,36 +78,68 @@ pub async fn add_employee(
         gender: req.employee.gender.clone(),
         position_id: req.employee.position_id,
         passport_id,
-    }))
+        version,
+    };
+    Ok(Tagged::new(version, Json(employee)))
 }
 
-pub async fn get_all_employees(
-    _: AuthUser,
-    ctx: State<ApiContext>,
-) -> Result<Json<EmployeeDetailsList>, Error> {
-    let db_employees = query!(
-        r#"
+const EMPLOYEE_LIST: ListSource = ListSource {
+    select: r#"
         SELECT
             e.id,
             e.first_name,
             e.last_name,
             e.middle_name,
-            e.email,
+            e.email::text,
             e.phone,
             e.gender::text,
             p.name AS position_name,
-            p.salary::text AS position_salary,
+            p.salary AS position_salary,
+            p.currency AS position_salary_currency,
             ps.series AS passport_series,
-            ps.number AS passport_number
+            ps.number AS passport_number,
+            e.version
+        "#,
+    from: r#"
         FROM
             employee e
         JOIN
             position_at_work p ON e.position_id = p.id
         JOIN
             passport ps ON e.passport_id = ps.id
-        "#
+        "#,
+    condition: "e.deleted_at IS NULL",
+    id_column: "e.id",
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/employees",
+    tag = "employees",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<EmployeeSortField>, Query, description = "Поле сортировки"),
+        EmployeeFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка сотрудников", body = EmployeeDetailsList),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверные параметры списка"),
+    )
+)]
+pub async fn get_all_employees(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    query: ListQuery<EmployeeFilter, EmployeeSortField>,
+) -> Result<Json<EmployeeDetailsList>, Error> {
+    let query = query.whole_list_unless_paged();
+    let (db_employees, page_info) = fetch_page(
+        &ctx.db,
+        &EMPLOYEE_LIST,
+        &query,
+        |row: &EmployeeDetailsRow| row.id,
     )
-    .fetch_all(&ctx.db)
     .await?;
 
     let employees = db_employees
@@ -85,20 +153,39 @@ pub async fn get_all_employees(
             phone: employee.phone.unwrap_or("".to_string()),
             gender: employee.gender.unwrap_or("".to_string()),
             position_name: employee.position_name,
-            position_salary: employee.position_salary.unwrap_or("Не указано".to_string()),
+            position_salary: employee.position_salary,
+            position_salary_currency: employee.position_salary_currency,
             passport_series: employee.passport_series,
             passport_number: employee.passport_number,
+            version: employee.version,
         })
         .collect();
 
-    Ok(Json(EmployeeDetailsList { employees }))
+    Ok(Json(EmployeeDetailsList {
+        employees,
+        page_info,
+    }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/employee/{id}",
+    tag = "employees",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "Идентификатор сотрудника")),
+    responses(
+        (status = 200, description = "Сотрудник", body = EmployeeDetails,
+            headers(("ETag" = String, description = "Версия сотрудника"))),
+        (status = 304, description = "Сотрудник не изменился с версии из `If-None-Match`"),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 404, description = "Сотрудник не найден"),
+    )
+)]
 pub async fn get_employee(
     _: AuthUser,
     ctx: State<ApiContext>,
     Path(id): Path<Uuid>,
-) -> Result<Json<EmployeeDetails>, Error> {
+) -> Result<Tagged<Json<EmployeeDetails>>, Error> {
     let optional_employee = query!(
         r#"
         SELECT
@@ -110,9 +197,11 @@ pub async fn get_employee(
             e.phone,
             e.gender::text,
             p.name AS position_name,
-            p.salary::text AS position_salary,
+            p.salary AS "position_salary: Money",
+            p.currency AS position_salary_currency,
             ps.series AS passport_series,
-            ps.number AS passport_number
+            ps.number AS passport_number,
+            e.version
         FROM
             employee e
         JOIN
@@ -121,6 +210,7 @@ pub async fn get_employee(
             passport ps ON e.passport_id = ps.id
         WHERE
             e.id = $1
+            AND e.deleted_at IS NULL
         "#,
         id
     )
@@ -132,28 +222,61 @@ pub async fn get_employee(
         None => return Err(Error::NotFound),
     };
 
-    Ok(Json(EmployeeDetails {
-        id: employee.id,
-        first_name: employee.first_name,
-        last_name: employee.last_name,
-        middle_name: employee.middle_name,
-        email: employee.email.unwrap_or("".to_string()),
-        phone: employee.phone.unwrap_or("".to_string()),
-        gender: employee.gender.unwrap_or("".to_string()),
-        position_name: employee.position_name,
-        position_salary: employee.position_salary.unwrap_or("Не указано".to_string()),
-        passport_series: employee.passport_series,
-        passport_number: employee.passport_number,
-    }))
+    Ok(Tagged::new(
+        employee.version,
+        Json(EmployeeDetails {
+            id: employee.id,
+            first_name: employee.first_name,
+            last_name: employee.last_name,
+            middle_name: employee.middle_name,
+            email: employee.email.unwrap_or("".to_string()),
+            phone: employee.phone.unwrap_or("".to_string()),
+            gender: employee.gender.unwrap_or("".to_string()),
+            position_name: employee.position_name,
+            position_salary: employee.position_salary,
+            position_salary_currency: employee.position_salary_currency,
+            passport_series: employee.passport_series,
+            passport_number: employee.passport_number,
+            version: employee.version,
+        }),
+    ))
 }
 
+/// Изменить данные сотрудника; поля, которых нет в запросе, не меняются.
+#[utoipa::path(
+    put,
+    path = "/api/employee/{id}",
+    tag = "employees",
+    security(("bearer" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Идентификатор сотрудника"),
+        ("If-Match" = String, Header, description = "`ETag` изменяемой версии"),
+    ),
+    request_body = UpdateEmployee,
+    responses(
+        (status = 200, description = "Сотрудник после изменения", body = EmployeeDetails,
+            headers(("ETag" = String, description = "Новая версия сотрудника"))),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 404, description = "Сотрудник не найден"),
+        (status = 412, description = "Сотрудника уже изменили"),
+        (status = 428, description = "Нет заголовка `If-Match`"),
+    )
+)]
 pub async fn update_employee(
     user: AuthUser,
     ctx: State<ApiContext>,
     Path(id): Path<Uuid>,
+    if_match: IfMatch,
     Json(payload): Json<UpdateEmployee>,
-) -> Result<Json<EmployeeDetails>, Error> {
-    query!(
+) -> Result<Tagged<Json<EmployeeDetails>>, Error> {
+    let mut transaction = ctx.db.begin().await?;
+
+    let (version, before) = employee_snapshot(&mut transaction, id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    if_match.check(version)?;
+
+    let after = query_scalar!(
         r#"
         UPDATE employee
         SET
@@ -165,6 +288,7 @@ pub async fn update_employee(
             position_id = COALESCE($6, position_id)
         WHERE
             id = $7
+        RETURNING to_jsonb(employee) - 'search_vector' AS "snapshot!"
         "#,
         payload.first_name,
         payload.last_name,
@@ -174,47 +298,59 @@ pub async fn update_employee(
         payload.position_id,
         id
     )
-    .execute(&ctx.db)
+    .fetch_one(&mut *transaction)
     .await?;
 
+    AuditRecord::updated(AuditEntity::Employee, id, before, after)
+        .save(&mut transaction, Some(user.user_id))
+        .await?;
+    transaction.commit().await?;
+
+    ctx.statistics_cache.invalidate();
+
     get_employee(user, ctx, Path(id)).await
 }
 
+/// Удалить сотрудника в корзину; участие в комитетах восстановится вместе с ним.
+#[utoipa::path(
+    delete,
+    path = "/api/employee/{id}",
+    tag = "employees",
+    security(("bearer" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Идентификатор сотрудника"),
+        ("If-Match" = String, Header, description = "`ETag` удаляемой версии"),
+    ),
+    responses(
+        (status = 200, description = "Сотрудник удалён"),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 404, description = "Сотрудник не найден"),
+        (status = 412, description = "Сотрудника уже изменили"),
+        (status = 428, description = "Нет заголовка `If-Match`"),
+    )
+)]
 pub async fn delete_employee(
-    _: AuthUser,
+    user: AuthUser,
     ctx: State<ApiContext>,
     Path(id): Path<Uuid>,
+    if_match: IfMatch,
 ) -> Result<(), Error> {
     let mut transaction = ctx.db.begin().await?;
 
-    let rows_affected_committee = query!(
-        r#"
-        DELETE FROM committee_employee
-        WHERE employee_id = $1
-        "#,
-        id
-    )
-    .execute(&mut *transaction)
-    .await?
-    .rows_affected();
+    let (version, _) = employee_snapshot(&mut transaction, id)
+        .await?
+        .ok_or(Error::EmployeeNotFound)?;
+    if_match.check(version)?;
 
-    let rows_affected_employee = query!(
-        r#"
-        DELETE FROM employee
-        WHERE id = $1
-        "#,
-        id
-    )
-    .execute(&mut *transaction)
-    .await?
-    .rows_affected();
-
-    if rows_affected_committee == 0 && rows_affected_employee == 0 {
-        transaction.rollback().await?;
-        return Err(Error::EmployeeNotFound);
-    } else {
-        transaction.commit().await?;
-    }
+    let before = soft_delete(&mut transaction, TrashEntity::Employee, id)
+        .await?
+        .ok_or(Error::EmployeeNotFound)?;
+    AuditRecord::deleted(AuditEntity::Employee, id, before)
+        .save(&mut transaction, Some(user.user_id))
+        .await?;
+    transaction.commit().await?;
+
+    ctx.statistics_cache.invalidate();
 
     Ok(())
 }
@@ -4,11 +4,34 @@ use axum::Router;
 use controllers::{
     add_employee, delete_employee, get_all_employees, get_employee, update_employee,
 };
+use utoipa::OpenApi;
 
 mod controllers;
 mod models;
 mod position;
 pub mod utils;
+pub(crate) mod v2;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::add_employee,
+        controllers::get_all_employees,
+        controllers::get_employee,
+        controllers::update_employee,
+        controllers::delete_employee,
+        position::controllers::get_all_positions,
+        v2::add_employee,
+        v2::get_all_employees,
+        v2::get_employee,
+        v2::update_employee,
+        v2::delete_employee,
+        v2::get_all_positions,
+    ),
+    components(schemas(models::EmployeeSortField)),
+    tags((name = "employees", description = "Сотрудники и должности"))
+)]
+pub(crate) struct ApiDoc;
 
 pub(crate) fn router() -> Router<ApiContext> {
     Router::new()
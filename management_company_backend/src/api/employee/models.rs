@@ -1,24 +1,98 @@
 use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize)]
+use crate::api::{
+    money::Money,
+    pagination::{ListFilter, PageInfo, SortField},
+};
+
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct EmployeeBody<T> {
     pub employee: T,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EmployeeList {
     pub employees: Vec<Employee>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EmployeeDetailsList {
     pub employees: Vec<EmployeeDetails>,
+    #[serde(flatten)]
+    pub page_info: PageInfo,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(FromRow)]
+pub struct EmployeeDetailsRow {
+    pub id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub middle_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub gender: Option<String>,
+    pub position_name: String,
+    pub position_salary: Money,
+    pub position_salary_currency: String,
+    pub passport_series: i32,
+    pub passport_number: i32,
+    pub version: i32,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct EmployeeFilter {
+    pub position_id: Option<Uuid>,
+    /// Только работающие сейчас (`true`) или только уволенные (`false`).
+    pub active: Option<bool>,
+}
+
+impl ListFilter for EmployeeFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(position_id) = self.position_id {
+            builder.push(" AND e.position_id = ").push_bind(position_id);
+        }
+        match self.active {
+            Some(true) => {
+                builder.push(" AND (e.ended_at IS NULL OR e.ended_at > NOW())");
+            }
+            Some(false) => {
+                builder.push(" AND e.ended_at <= NOW()");
+            }
+            None => (),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EmployeeSortField {
+    #[default]
+    LastName,
+    FirstName,
+    PositionName,
+    StartedAt,
+}
+
+impl SortField for EmployeeSortField {
+    fn expression(self) -> &'static str {
+        match self {
+            Self::LastName => "e.last_name",
+            Self::FirstName => "e.first_name",
+            Self::PositionName => "p.name",
+            Self::StartedAt => "e.started_at",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EmployeeDetails {
     pub id: Uuid,
@@ -29,12 +103,15 @@ pub struct EmployeeDetails {
     pub phone: String,
     pub gender: String,
     pub position_name: String,
-    pub position_salary: String,
+    pub position_salary: Money,
+    pub position_salary_currency: String,
     pub passport_series: i32,
     pub passport_number: i32,
+    /// Версия записи; её же сервер отдаёт в `ETag` и ждёт в `If-Match` при изменении.
+    pub version: i32,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NewEmployee {
     pub first_name: String,
@@ -48,7 +125,7 @@ pub struct NewEmployee {
     pub passport_number: i32,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateEmployee {
     pub first_name: Option<String>,
@@ -56,11 +133,13 @@ pub struct UpdateEmployee {
     pub middle_name: Option<String>,
     pub email: Option<String>,
     pub phone: Option<String>,
+    /// Принимается, но не изменяется: пол задаётся при создании сотрудника.
+    #[allow(dead_code)]
     pub gender: Option<String>,
     pub position_id: Option<Uuid>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Employee {
     pub id: Uuid,
@@ -72,4 +151,6 @@ pub struct Employee {
     pub gender: String,
     pub position_id: Uuid,
     pub passport_id: Uuid,
+    /// Версия записи; её же сервер отдаёт в `ETag` и ждёт в `If-Match` при изменении.
+    pub version: i32,
 }
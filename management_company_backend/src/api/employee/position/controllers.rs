@@ -1,20 +1,34 @@
 use axum::{extract::State, Json};
-use sqlx::query;
+use sqlx::query_as;
 
-use crate::api::{employee::position::models::Position, extractor::AuthUser, ApiContext, Error};
+use crate::api::{
+    employee::position::models::Position, extractor::AuthUser, money::Money, ApiContext, Error,
+};
 
 use super::models::PositionList;
 
+#[utoipa::path(
+    get,
+    path = "/api/positions",
+    tag = "employees",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Все должности", body = PositionList),
+        (status = 401, description = "Нет действующего токена"),
+    )
+)]
 pub async fn get_all_positions(
     _: AuthUser,
     ctx: State<ApiContext>,
 ) -> Result<Json<PositionList>, Error> {
-    let db_positions = query!(
+    let positions = query_as!(
+        Position,
         r#"
         SELECT
             id,
             name,
-            salary::text AS salary
+            salary AS "salary: Money",
+            currency
         FROM
             position_at_work
         "#
@@ -22,14 +36,5 @@ pub async fn get_all_positions(
     .fetch_all(&ctx.db)
     .await?;
 
-    let positions = db_positions
-        .into_iter()
-        .map(|position| Position {
-            id: position.id,
-            name: position.name,
-            salary: position.salary.unwrap_or_else(|| "Не указано".to_string()),
-        })
-        .collect();
-
     Ok(Json(PositionList { positions }))
 }
@@ -1,14 +1,18 @@
 use serde::Serialize;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Serialize)]
+use crate::api::money::Money;
+
+#[derive(Serialize, ToSchema)]
 pub struct Position {
     pub id: Uuid,
     pub name: String,
-    pub salary: String,
+    pub salary: Money,
+    pub currency: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct PositionList {
     pub positions: Vec<Position>,
 }
@@ -1,4 +1,5 @@
-use sqlx::{query_scalar, PgPool};
+use serde_json::Value;
+use sqlx::{query, query_scalar, PgConnection, PgExecutor, PgPool};
 use uuid::Uuid;
 
 use crate::api::Error;
@@ -19,7 +20,11 @@ pub async fn position_exists(pool: &PgPool, position_id: Uuid) -> Result<bool, E
     Ok(exists.unwrap_or(false))
 }
 
-pub async fn insert_passport(pool: &PgPool, series: i32, number: i32) -> Result<Uuid, Error> {
+pub async fn insert_passport(
+    executor: impl PgExecutor<'_>,
+    series: i32,
+    number: i32,
+) -> Result<Uuid, Error> {
     let passport_id = query_scalar!(
         r#"
         INSERT INTO passport (series, number)
@@ -29,14 +34,14 @@ pub async fn insert_passport(pool: &PgPool, series: i32, number: i32) -> Result<
         series,
         number
     )
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await?;
 
     Ok(passport_id)
 }
 
 pub async fn insert_employee(
-    pool: &PgPool,
+    executor: impl PgExecutor<'_>,
     new_employee: &NewEmployee,
     passport_id: Uuid,
 ) -> Result<Uuid, Error> {
@@ -55,7 +60,7 @@ pub async fn insert_employee(
         new_employee.phone,
         new_employee.email
     )
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await?;
 
     Ok(employee_id)
@@ -64,7 +69,7 @@ pub async fn insert_employee(
 pub async fn employee_exists(pool: &PgPool, employee_id: Uuid) -> Result<bool, Error> {
     let exists: Option<bool> = query_scalar!(
         r#"
-        SELECT EXISTS(SELECT 1 FROM employee WHERE id = $1)
+        SELECT EXISTS(SELECT 1 FROM employee WHERE id = $1 AND deleted_at IS NULL)
         "#,
         employee_id
     )
@@ -74,3 +79,24 @@ pub async fn employee_exists(pool: &PgPool, employee_id: Uuid) -> Result<bool, E
 
     Ok(exists.unwrap_or(false))
 }
+
+/// Версия и строка сотрудника в JSON для журнала изменений; строка блокируется до конца
+/// транзакции, чтобы версию не изменили между проверкой `If-Match` и записью.
+pub async fn employee_snapshot(
+    conn: &mut PgConnection,
+    employee_id: Uuid,
+) -> Result<Option<(i32, Value)>, Error> {
+    let snapshot = query!(
+        r#"
+        SELECT version, to_jsonb(e) - 'search_vector' AS "snapshot!"
+        FROM employee e
+        WHERE id = $1 AND deleted_at IS NULL
+        FOR UPDATE
+        "#,
+        employee_id
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(snapshot.map(|row| (row.version, row.snapshot)))
+}
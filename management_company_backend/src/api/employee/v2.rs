@@ -0,0 +1,190 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::api::{
+    conditional::{IfMatch, Tagged},
+    envelope::{created, Envelope, ErrorEnvelope},
+    extractor::AuthUser,
+    pagination::{ListQuery, PageParams},
+    ApiContext, Error,
+};
+
+use super::{
+    controllers,
+    models::{
+        Employee, EmployeeBody, EmployeeDetails, EmployeeFilter, EmployeeSortField, NewEmployee,
+        UpdateEmployee,
+    },
+    position::{self, models::Position},
+};
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route(
+            "/api/v2/employees",
+            get(get_all_employees).post(add_employee),
+        )
+        .route(
+            "/api/v2/employees/:id",
+            get(get_employee)
+                .put(update_employee)
+                .delete(delete_employee),
+        )
+        .route("/api/v2/positions", get(get_all_positions))
+}
+
+/// Принять сотрудника на работу вместе с его паспортными данными.
+#[utoipa::path(
+    post,
+    path = "/api/v2/employees",
+    tag = "employees",
+    security(("bearer" = [])),
+    request_body = NewEmployee,
+    responses(
+        (status = 201, description = "Сотрудник добавлен", body = Envelope<Employee>,
+            headers(("ETag" = String, description = "Версия сотрудника"))),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Должность не найдена", body = ErrorEnvelope),
+    )
+)]
+pub async fn add_employee(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    Json(employee): Json<NewEmployee>,
+) -> Result<Response, Error> {
+    let tagged = controllers::add_employee(user, ctx, Json(EmployeeBody { employee })).await?;
+    Ok(tagged
+        .map(|Json(employee)| created(employee))
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/employees",
+    tag = "employees",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<EmployeeSortField>, Query, description = "Поле сортировки"),
+        EmployeeFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка сотрудников", body = Envelope<Vec<EmployeeDetails>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_all_employees(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    query: ListQuery<EmployeeFilter, EmployeeSortField>,
+) -> Result<Json<Envelope<Vec<EmployeeDetails>>>, Error> {
+    let Json(list) = controllers::get_all_employees(user, ctx, query.paged()).await?;
+    Ok(Envelope::page(list.employees, list.page_info))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/employees/{id}",
+    tag = "employees",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "Идентификатор сотрудника")),
+    responses(
+        (status = 200, description = "Сотрудник", body = Envelope<EmployeeDetails>,
+            headers(("ETag" = String, description = "Версия сотрудника"))),
+        (status = 304, description = "Сотрудник не изменился с версии из `If-None-Match`"),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Сотрудник не найден", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_employee(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    id: Path<Uuid>,
+) -> Result<Tagged<Json<Envelope<EmployeeDetails>>>, Error> {
+    let tagged = controllers::get_employee(user, ctx, id).await?;
+    Ok(tagged.map(|Json(employee)| Envelope::new(employee)))
+}
+
+/// Изменить данные сотрудника; поля, которых нет в запросе, не меняются.
+#[utoipa::path(
+    put,
+    path = "/api/v2/employees/{id}",
+    tag = "employees",
+    security(("bearer" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Идентификатор сотрудника"),
+        ("If-Match" = String, Header, description = "`ETag` изменяемой версии"),
+    ),
+    request_body = UpdateEmployee,
+    responses(
+        (status = 200, description = "Сотрудник после изменения", body = Envelope<EmployeeDetails>,
+            headers(("ETag" = String, description = "Новая версия сотрудника"))),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Сотрудник не найден", body = ErrorEnvelope),
+        (status = 412, description = "Сотрудника уже изменили", body = ErrorEnvelope),
+        (status = 428, description = "Нет заголовка `If-Match`", body = ErrorEnvelope),
+    )
+)]
+pub async fn update_employee(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    id: Path<Uuid>,
+    if_match: IfMatch,
+    payload: Json<UpdateEmployee>,
+) -> Result<Tagged<Json<Envelope<EmployeeDetails>>>, Error> {
+    let tagged = controllers::update_employee(user, ctx, id, if_match, payload).await?;
+    Ok(tagged.map(|Json(employee)| Envelope::new(employee)))
+}
+
+/// Удалить сотрудника вместе с его участием в комитетах.
+#[utoipa::path(
+    delete,
+    path = "/api/v2/employees/{id}",
+    tag = "employees",
+    security(("bearer" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Идентификатор сотрудника"),
+        ("If-Match" = String, Header, description = "`ETag` удаляемой версии"),
+    ),
+    responses(
+        (status = 204, description = "Сотрудник удалён"),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Сотрудник не найден", body = ErrorEnvelope),
+        (status = 412, description = "Сотрудника уже изменили", body = ErrorEnvelope),
+        (status = 428, description = "Нет заголовка `If-Match`", body = ErrorEnvelope),
+    )
+)]
+pub async fn delete_employee(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    id: Path<Uuid>,
+    if_match: IfMatch,
+) -> Result<StatusCode, Error> {
+    controllers::delete_employee(user, ctx, id, if_match).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/positions",
+    tag = "employees",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Все должности", body = Envelope<Vec<Position>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_all_positions(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+) -> Result<Json<Envelope<Vec<Position>>>, Error> {
+    let Json(list) = position::controllers::get_all_positions(user, ctx).await?;
+    Ok(Envelope::new(list.positions))
+}
@@ -0,0 +1,159 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+        HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::api::pagination::PageInfo;
+
+/// Больше этого текст ошибки не бывает; всё, что длиннее, — не ответ `Error`.
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// Ответ API v2: ресурс или список ресурсов в `data`, сведения о странице списка в `meta`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Envelope<T> {
+    pub data: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PageInfo>,
+}
+
+impl<T> Envelope<T> {
+    pub fn new(data: T) -> Json<Self> {
+        Json(Self { data, meta: None })
+    }
+}
+
+impl<T> Envelope<Vec<T>> {
+    pub fn page(data: Vec<T>, page_info: PageInfo) -> Json<Self> {
+        Json(Self {
+            data,
+            meta: Some(page_info),
+        })
+    }
+}
+
+/// Ошибка API v2: `{"error": {"status": 404, "message": "..."}}`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorEnvelope {
+    pub error: ErrorBody,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub status: u16,
+    pub message: String,
+}
+
+/// Завернуть текстовые ответы об ошибках в [`ErrorEnvelope`].
+///
+/// `Error` и отказы экстракторов axum отвечают простым текстом; для v2 он становится `message`,
+/// а статус и остальные заголовки, например `WWW-Authenticate`, остаются прежними.
+pub async fn json_errors(response: Response) -> Response {
+    let status = response.status();
+    let is_text = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/plain"));
+    if !(status.is_client_error() || status.is_server_error()) || !is_text {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let message = match to_bytes(body, MAX_ERROR_BODY).await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(_) => status
+            .canonical_reason()
+            .unwrap_or("Unknown error")
+            .to_string(),
+    };
+    let envelope = ErrorEnvelope {
+        error: ErrorBody {
+            status: status.as_u16(),
+            message,
+        },
+    };
+
+    parts.headers.remove(CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    let body = serde_json::to_vec(&envelope).unwrap_or_default();
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Ресурс создан: `201 Created` с ресурсом в `data`.
+pub fn created<T: Serialize>(data: T) -> Response {
+    (StatusCode::CREATED, Envelope::new(data)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::header::WWW_AUTHENTICATE;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::api::Error;
+
+    async fn json(response: Response) -> serde_json::Value {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn errors_are_wrapped_with_their_status() {
+        let response = json_errors(Error::Unauthorized.into_response()).await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[WWW_AUTHENTICATE], "Token");
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(
+            json(response).await,
+            serde_json::json!({
+                "error": { "status": 401, "message": "Authentication required" }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn successful_and_json_responses_are_left_alone() {
+        let ok = json_errors("fine".into_response()).await;
+        assert_eq!(ok.headers()[CONTENT_TYPE], "text/plain; charset=utf-8");
+
+        let already_json = (StatusCode::SERVICE_UNAVAILABLE, Json(serde_json::json!({})));
+        let response = json_errors(already_json.into_response()).await;
+        assert_eq!(json(response).await, serde_json::json!({}));
+    }
+
+    #[test]
+    fn lists_carry_page_info_in_meta() {
+        let id = Uuid::nil();
+        let Json(envelope) = Envelope::page(
+            vec![1, 2],
+            PageInfo {
+                total: 3,
+                page: None,
+                page_size: 2,
+                next_cursor: Some(id),
+            },
+        );
+
+        assert_eq!(
+            serde_json::to_value(envelope).unwrap(),
+            serde_json::json!({
+                "data": [1, 2],
+                "meta": { "total": 3, "page": null, "pageSize": 2, "nextCursor": id }
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(Envelope::new("x").0).unwrap(),
+            serde_json::json!({ "data": "x" })
+        );
+    }
+}
@@ -3,7 +3,7 @@ use axum::{
     response::{IntoResponse, Response},
 };
 
-#[derive(thiserror::Error, Debug)]
+#[derive(thiserror::Error, Debug, Default)]
 pub enum Error {
     #[error("Authentication required")]
     Unauthorized,
@@ -18,6 +18,7 @@ pub enum Error {
     Forbidden,
 
     #[error("Request path not found")]
+    #[default]
     NotFound,
 
     #[error("An error occurred with the database")]
@@ -28,47 +29,61 @@ pub enum Error {
 
     #[error("Position ID does not exist")]
     PositionNotFound,
-}
 
-impl Default for Error {
-    fn default() -> Self {
-        Self::NotFound
-    }
+    #[error("Building ID does not exist")]
+    BuildingNotFound,
+
+    #[error("{0}")]
+    UnprocessableEntity(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("The resource has been changed by someone else, reload it and try again")]
+    PreconditionFailed,
+
+    #[error("If-Match header with the resource ETag is required")]
+    PreconditionRequired,
 }
 
 impl Error {
     fn status_code(&self) -> StatusCode {
         match self {
             Self::Unauthorized | Self::UserNotFound => StatusCode::UNAUTHORIZED,
-            Self::NotFound | Self::EmployeeNotFound | Self::PositionNotFound => {
-                StatusCode::NOT_FOUND
-            }
-            Self::Sqlx(_) | Self::Anyhow(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::NotFound
+            | Self::EmployeeNotFound
+            | Self::PositionNotFound
+            | Self::BuildingNotFound => StatusCode::NOT_FOUND,
+            Self::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::PreconditionFailed => StatusCode::PRECONDITION_FAILED,
+            Self::PreconditionRequired => StatusCode::PRECONDITION_REQUIRED,
+            Self::Sqlx(_) | Self::Anyhow(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
 
 impl IntoResponse for Error {
+    /// Каждая ошибка попадает в журнал внутри спана запроса, то есть с его `request_id`.
     fn into_response(self) -> Response {
+        let status = self.status_code();
         match self {
-            Self::Unauthorized => {
-                return (
-                    self.status_code(),
-                    [(WWW_AUTHENTICATE, "Token")],
-                    self.to_string(),
-                )
-                    .into_response();
-            }
             Self::Sqlx(ref e) => {
-                log::error!("Sqlx error: {:?}", e);
+                tracing::error!(status = status.as_u16(), error = ?e, "database error");
             }
             Self::Anyhow(ref e) => {
-                log::error!("Generic error: {:?}", e);
+                tracing::error!(status = status.as_u16(), error = ?e, "internal error");
             }
-            _ => (),
+            _ => {
+                tracing::info!(status = status.as_u16(), error = %self, "request failed");
+            }
+        }
+
+        if let Self::Unauthorized = self {
+            return (status, [(WWW_AUTHENTICATE, "Token")], self.to_string()).into_response();
         }
 
-        (self.status_code(), self.to_string()).into_response()
+        (status, self.to_string()).into_response()
     }
 }
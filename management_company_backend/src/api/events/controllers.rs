@@ -0,0 +1,260 @@
+use std::{collections::HashSet, convert::Infallible};
+
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream, WatchStream},
+    Stream, StreamExt,
+};
+use uuid::Uuid;
+
+use crate::api::{envelope::ErrorEnvelope, extractor::AuthUser, ApiContext, Error};
+
+use super::{
+    feed::FeedMessage,
+    models::{FeedEvent, FeedFilter},
+};
+
+/// Лента событий в формате Server-Sent Events: новые аварии, смена их статуса, начало и
+/// окончание ремонтов.
+///
+/// Имя события SSE совпадает с `kind`. Событие `resync` означает, что часть событий
+/// потеряна и данные нужно перечитать.
+#[utoipa::path(
+    get,
+    path = "/api/v2/events",
+    tag = "events",
+    security(("bearer" = [])),
+    params(FeedFilter),
+    responses(
+        (status = 200, description = "Поток событий", content_type = "text/event-stream", body = FeedEvent),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Дом или комитет не найден", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_events(
+    _: AuthUser,
+    State(ctx): State<ApiContext>,
+    Query(filter): Query<FeedFilter>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Error> {
+    // Подписываемся до проверки фильтра, чтобы не пропустить события, пришедшие за это время.
+    let (events, closed) = ctx.events.subscribe();
+    let buildings = watched_buildings(&ctx, &filter).await?;
+
+    let closed = WatchStream::new(closed)
+        .filter(|closed| *closed)
+        .map(|_| None);
+    let events = BroadcastStream::new(events)
+        .map(Some)
+        .merge(closed)
+        .take_while(Option::is_some)
+        .filter_map(move |message| {
+            match message {
+                Some(Ok(FeedMessage::Event(event))) => {
+                    let visible = match (&buildings, event.building_id) {
+                        (None, _) => true,
+                        (Some(buildings), Some(building_id)) => buildings.contains(&building_id),
+                        (Some(_), None) => false,
+                    };
+                    visible.then(|| {
+                        Event::default()
+                            .event(event.kind.as_str())
+                            .json_data(&*event)
+                            .expect("feed events always serialize")
+                    })
+                }
+                Some(Ok(FeedMessage::Resync) | Err(BroadcastStreamRecvError::Lagged(_))) => {
+                    Some(Event::default().event("resync").data(""))
+                }
+                None => None,
+            }
+            .map(Ok)
+        });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Дома, события которых попадают в ленту; `None` — все дома.
+async fn watched_buildings(
+    ctx: &ApiContext,
+    filter: &FeedFilter,
+) -> Result<Option<HashSet<Uuid>>, Error> {
+    let mut buildings = None;
+
+    if let Some(building_id) = filter.building_id {
+        sqlx::query_scalar!(
+            r#"SELECT id FROM building WHERE id = $1 AND deleted_at IS NULL"#,
+            building_id
+        )
+        .fetch_optional(&ctx.db)
+        .await?
+        .ok_or(Error::BuildingNotFound)?;
+        buildings = Some(HashSet::from([building_id]));
+    }
+
+    if let Some(committee_id) = filter.committee_id {
+        sqlx::query_scalar!(r#"SELECT id FROM committee WHERE id = $1"#, committee_id)
+            .fetch_optional(&ctx.db)
+            .await?
+            .ok_or(Error::NotFound)?;
+        let served: HashSet<Uuid> = sqlx::query_scalar!(
+            r#"SELECT id FROM building WHERE committee_id = $1 AND deleted_at IS NULL"#,
+            committee_id
+        )
+        .fetch_all(&ctx.db)
+        .await?
+        .into_iter()
+        .collect();
+        buildings = Some(match buildings {
+            Some(building) => served.intersection(&building).copied().collect(),
+            None => served,
+        });
+    }
+
+    Ok(buildings)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::{
+        body::{Body, BodyDataStream},
+        http::{header::AUTHORIZATION, Request, StatusCode},
+    };
+    use sqlx::PgPool;
+    use tokio_stream::StreamExt;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::api::{api_router, extractor::AuthUser, test_context};
+
+    const PETROVA: Uuid = Uuid::from_u128(0xe2);
+    const COMMITTEE: Uuid = Uuid::from_u128(0xc1);
+    const LENINA: Uuid = Uuid::from_u128(0xb1);
+    const KHIMKI: Uuid = Uuid::from_u128(0xb2);
+    const LEAK: Uuid = Uuid::from_u128(0x101);
+    const ROOF: Uuid = Uuid::from_u128(0x202);
+    const ROOF_REPAIR: Uuid = Uuid::from_u128(0x302);
+
+    /// Следующее событие SSE как пара (имя, данные); `None`, если поток закончился.
+    async fn next_event(
+        body: &mut BodyDataStream,
+        buffer: &mut String,
+    ) -> Option<(String, serde_json::Value)> {
+        loop {
+            if let Some(end) = buffer.find("\n\n") {
+                let frame: String = buffer.drain(..end + 2).collect();
+                let field = |name: &str| {
+                    frame
+                        .lines()
+                        .find_map(|line| line.strip_prefix(name))
+                        .map(str::to_owned)
+                };
+                // Пустые кадры keep-alive пропускаем.
+                if let Some(event) = field("event: ") {
+                    let data = field("data: ").unwrap_or_default();
+                    return Some((event, serde_json::from_str(&data).unwrap_or_default()));
+                }
+                continue;
+            }
+            let chunk = tokio::time::timeout(Duration::from_secs(5), body.next())
+                .await
+                .expect("no event within 5 seconds")?
+                .unwrap();
+            buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn committee_sees_only_its_buildings(pool: PgPool) {
+        let ctx = test_context(pool.clone());
+        ctx.events.listen(&pool).await.unwrap();
+        let user_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO user_account (email, password_hash, employee_id)
+            VALUES ('petrova@example.com', '', $1)
+            RETURNING id
+            "#,
+            PETROVA
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let token = AuthUser { user_id }.to_jwt(&ctx);
+        let events = ctx.events.clone();
+        let app = api_router(ctx);
+
+        let get = |uri: String| {
+            Request::builder()
+                .uri(uri)
+                .header(AUTHORIZATION, format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = app
+            .clone()
+            .oneshot(get(format!("/api/v2/events?committeeId={}", Uuid::nil())))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .oneshot(get(format!("/api/v2/events?committeeId={COMMITTEE}")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let mut body = response.into_body().into_data_stream();
+        let mut buffer = String::new();
+
+        for building_id in [KHIMKI, LENINA] {
+            sqlx::query!(
+                r#"
+                INSERT INTO incident (building_id, status, description, incident_type_id)
+                VALUES ($1, 'reported', 'Протечка', $2)
+                "#,
+                building_id,
+                LEAK
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query!(
+            "UPDATE incident SET status = 'resolved' WHERE id = $1",
+            ROOF
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "UPDATE repair SET ended_at = now() WHERE id = $1",
+            ROOF_REPAIR
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Событие по дому в Химках, который комитет не обслуживает, в ленту не попадает.
+        let (name, created) = next_event(&mut body, &mut buffer).await.unwrap();
+        assert_eq!(name, "incident_created");
+        assert_eq!(created["buildingId"], LENINA.to_string());
+        assert_eq!(created["status"], "Reported");
+
+        let (name, changed) = next_event(&mut body, &mut buffer).await.unwrap();
+        assert_eq!(name, "incident_status_changed");
+        assert_eq!(changed["incidentId"], ROOF.to_string());
+        assert_eq!(changed["previousStatus"], "InProgress");
+        assert_eq!(changed["status"], "Resolved");
+
+        let (name, closed) = next_event(&mut body, &mut buffer).await.unwrap();
+        assert_eq!(name, "repair_closed");
+        assert_eq!(closed["repairId"], ROOF_REPAIR.to_string());
+
+        events.close();
+        assert_eq!(next_event(&mut body, &mut buffer).await, None);
+    }
+}
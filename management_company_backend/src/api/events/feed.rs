@@ -0,0 +1,98 @@
+use std::{sync::Arc, time::Duration};
+
+use sqlx::{postgres::PgListener, PgPool};
+use tokio::sync::{broadcast, watch};
+
+use super::models::FeedEvent;
+
+/// Канал Postgres, в который триггеры `incident` и `repair` публикуют события.
+pub const CHANNEL: &str = "incident_events";
+
+/// Сколько событий ждёт медленного подписчика; отставший получает `resync`.
+const CAPACITY: usize = 256;
+
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
+pub enum FeedMessage {
+    Event(Arc<FeedEvent>),
+    /// Часть событий потеряна: клиенту нужно перечитать данные.
+    Resync,
+}
+
+/// Рассылка событий из `LISTEN` всем открытым потокам SSE этого экземпляра.
+pub struct EventFeed {
+    events: broadcast::Sender<FeedMessage>,
+    closed: watch::Sender<bool>,
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventFeed {
+    pub fn new() -> Self {
+        Self {
+            events: broadcast::channel(CAPACITY).0,
+            closed: watch::channel(false).0,
+        }
+    }
+
+    /// Подписаться на канал и пересылать события, пока лента не закрыта.
+    ///
+    /// Подписка выполнена, когда функция вернула управление: события, опубликованные
+    /// после этого, дойдут до подписчиков.
+    pub async fn listen(self: &Arc<Self>, db: &PgPool) -> Result<(), sqlx::Error> {
+        let mut listener = PgListener::connect_with(db).await?;
+        listener.listen(CHANNEL).await?;
+
+        let feed = Arc::clone(self);
+        tokio::spawn(async move { feed.forward(listener).await });
+        Ok(())
+    }
+
+    async fn forward(&self, mut listener: PgListener) {
+        let mut closed = self.closed.subscribe();
+        loop {
+            let notification = tokio::select! {
+                notification = listener.try_recv() => notification,
+                _ = closed.wait_for(|closed| *closed) => return,
+            };
+
+            // Ошибка `send` означает только, что сейчас никто не подписан.
+            match notification {
+                Ok(Some(notification)) => match serde_json::from_str(notification.payload()) {
+                    Ok(event) => {
+                        let _ = self.events.send(FeedMessage::Event(Arc::new(event)));
+                    }
+                    Err(e) => tracing::error!(
+                        error = %e,
+                        payload = notification.payload(),
+                        "malformed feed event"
+                    ),
+                },
+                // Следующий вызов переподключится, но события, пришедшие без соединения,
+                // потеряны.
+                Ok(None) => {
+                    tracing::warn!("lost the feed connection to the database, reconnecting");
+                    let _ = self.events.send(FeedMessage::Resync);
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "failed to reconnect the feed to the database");
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+            }
+        }
+    }
+
+    pub fn subscribe(&self) -> (broadcast::Receiver<FeedMessage>, watch::Receiver<bool>) {
+        (self.events.subscribe(), self.closed.subscribe())
+    }
+
+    /// Завершить все потоки SSE, чтобы сервер мог остановиться, не дожидаясь клиентов.
+    pub fn close(&self) {
+        self.closed.send_replace(true);
+    }
+}
@@ -0,0 +1,23 @@
+use axum::{routing::get, Router};
+use controllers::get_events;
+use utoipa::OpenApi;
+
+use super::ApiContext;
+
+mod controllers;
+pub mod feed;
+pub mod models;
+pub mod outbox;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(controllers::get_events),
+    components(schemas(models::FeedEvent, models::FeedEventKind)),
+    tags((name = "events", description = "Лента событий по авариям и ремонтам"))
+)]
+pub(crate) struct ApiDoc;
+
+/// Лента появилась после выхода v2, поэтому есть только в ней.
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new().route("/api/v2/events", get(get_events))
+}
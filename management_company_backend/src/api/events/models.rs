@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    postgres::{PgHasArrayType, PgTypeInfo},
+    Type,
+};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::incident::models::IncidentStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Type, ToSchema)]
+#[sqlx(type_name = "event_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum FeedEventKind {
+    IncidentCreated,
+    IncidentStatusChanged,
+    RepairOpened,
+    RepairClosed,
+}
+
+impl FeedEventKind {
+    /// Имя события SSE; клиент подписывается на него через `addEventListener`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::IncidentCreated => "incident_created",
+            Self::IncidentStatusChanged => "incident_status_changed",
+            Self::RepairOpened => "repair_opened",
+            Self::RepairClosed => "repair_closed",
+        }
+    }
+}
+
+/// Подписки на вебхуки хранят виды событий массивом `event_kind[]`.
+impl PgHasArrayType for FeedEventKind {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_event_kind")
+    }
+}
+
+/// Событие ленты. Его формируют триггеры `incident` и `repair` и публикуют через `NOTIFY`.
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedEvent {
+    pub kind: FeedEventKind,
+    pub building_id: Option<Uuid>,
+    pub incident_id: Option<Uuid>,
+    /// Только для событий ремонта.
+    pub repair_id: Option<Uuid>,
+    /// Только для событий аварии.
+    pub status: Option<IncidentStatus>,
+    /// Только для смены статуса.
+    pub previous_status: Option<IncidentStatus>,
+    pub at: DateTime<Utc>,
+}
+
+/// Какие дома показывать в ленте; без параметров лента содержит события всех домов.
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct FeedFilter {
+    /// Дом.
+    pub building_id: Option<Uuid>,
+    /// Комитет: дома, которые он обслуживает на момент подписки.
+    pub committee_id: Option<Uuid>,
+}
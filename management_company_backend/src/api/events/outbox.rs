@@ -0,0 +1,61 @@
+use std::{future::Future, time::Duration};
+
+use tokio::task::JoinHandle;
+
+use super::feed::EventFeed;
+
+/// Пауза перед второй попыткой; дальше она удваивается с каждой неудачей.
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(30);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Рассыльщик очереди, которую пополняют те же транзакции, что публикуют события ленты:
+/// вебхуки из `webhook_outbox` и письма из `notification_outbox`.
+pub trait OutboxWorker: Send + Sync + 'static {
+    /// Как часто проверять очередь, даже если событий в ленте нет: для повторных попыток.
+    fn poll_interval(&self) -> Duration;
+
+    /// Обработать всё, чему подошёл срок; ошибки рассыльщик записывает в журнал сам.
+    fn poll(&self) -> impl Future<Output = ()> + Send;
+
+    /// Опрашивать очередь, пока лента не закрыта: сразу после каждого события ленты и раз
+    /// в `poll_interval`. Начатый опрос доводится до конца.
+    fn spawn(self, feed: &EventFeed) -> JoinHandle<()>
+    where
+        Self: Sized,
+    {
+        let (mut events, mut closed) = feed.subscribe();
+        tokio::spawn(async move {
+            loop {
+                self.poll().await;
+                tokio::select! {
+                    _ = events.recv() => {}
+                    _ = tokio::time::sleep(self.poll_interval()) => {}
+                    _ = closed.wait_for(|closed| *closed) => return,
+                }
+            }
+        })
+    }
+}
+
+/// Пауза после неудачной попытки номер `attempt`: 30 секунд, минута, две… но не больше 6 часов.
+pub fn retry_delay(attempt: i32) -> chrono::Duration {
+    let exponent = (attempt - 1).clamp(0, 16) as u32;
+    let delay = FIRST_RETRY_DELAY
+        .saturating_mul(2u32.pow(exponent))
+        .min(MAX_RETRY_DELAY);
+    chrono::Duration::from_std(delay).expect("retry delay fits chrono::Duration")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_back_off_exponentially_up_to_a_limit() {
+        let delays: Vec<i64> = [1, 2, 3, 12, 40]
+            .map(|attempt| retry_delay(attempt).num_seconds())
+            .into();
+
+        assert_eq!(delays, [30, 60, 120, 6 * 60 * 60, 6 * 60 * 60]);
+    }
+}
@@ -0,0 +1,408 @@
+use anyhow::Context;
+use axum::{
+    body::Body,
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Deserialize;
+use sqlx::{postgres::PgRow, FromRow, PgPool, Postgres, QueryBuilder};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tracing::Instrument;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::api::{
+    money::Money,
+    pagination::{list_all_query, ListFilter, ListQuery, ListSource, SortField},
+    Error,
+};
+
+const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+const XLSX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+/// Метка порядка байтов, по которой Excel узнаёт UTF-8 и правильно показывает кириллицу.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+/// Excel с русской локалью ожидает точку с запятой в качестве разделителя.
+const CSV_DELIMITER: u8 = b';';
+/// Сколько строк списка собирать перед отправкой очередного куска CSV.
+const CSV_BATCH_SIZE: usize = 500;
+/// XLSX собирается в памяти целиком, поэтому больший список можно выгрузить только в CSV.
+const MAX_XLSX_ROWS: usize = 100_000;
+
+const MONEY_FORMAT: &str = "#,##0.00";
+const PERCENT_FORMAT: &str = "0.00%";
+const DATE_TIME_FORMAT: &str = "dd.mm.yyyy hh:mm";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportParams {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Значение ячейки выгрузки; тип определяет числовой формат в XLSX.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Empty,
+    Text(String),
+    Integer(i64),
+    Money(Money),
+    /// Доля, где 1.0 — это 100%.
+    Percent(f64),
+    DateTime(DateTime<Utc>),
+}
+
+impl Cell {
+    pub fn text(value: impl Into<String>) -> Self {
+        Self::Text(value.into())
+    }
+
+    /// Процент из строки вида `"+12.50%"`, как его отдаёт статистика; непонятная строка остаётся текстом.
+    pub fn percent(value: &str) -> Self {
+        value
+            .trim_end_matches('%')
+            .parse::<f64>()
+            .map(|percent| Self::Percent(percent / 100.0))
+            .unwrap_or_else(|_| Self::text(value))
+    }
+
+    fn to_csv(&self, timezone: Tz) -> String {
+        match self {
+            Self::Empty => String::new(),
+            Self::Text(text) => csv_text(text),
+            Self::Integer(value) => value.to_string(),
+            Self::Money(value) => value.to_string(),
+            Self::Percent(value) => format!("{:.2}%", value * 100.0),
+            Self::DateTime(value) => value
+                .with_timezone(&timezone)
+                .format("%d.%m.%Y %H:%M")
+                .to_string(),
+        }
+    }
+}
+
+/// Текст, который Excel принял бы за формулу, выгружается с апострофом в начале: описание
+/// аварии вида `=HYPERLINK(...)` иначе выполнилось бы у того, кто откроет файл.
+fn csv_text(text: &str) -> String {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{text}")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Раздел выгрузки: отдельный лист в XLSX и отдельный блок в CSV.
+pub struct Sheet {
+    pub title: &'static str,
+    pub headers: &'static [&'static str],
+    pub rows: Vec<Vec<Cell>>,
+}
+
+/// Строка списка, которую можно выгрузить в таблицу.
+pub trait ExportRow: for<'r> FromRow<'r, PgRow> + Send + Unpin + 'static {
+    const SHEET_TITLE: &'static str;
+    const HEADERS: &'static [&'static str];
+
+    fn cells(self) -> Vec<Cell>;
+}
+
+/// Выгрузить разделы в выбранном формате одним файлом.
+pub fn sheets_response(
+    sheets: &[Sheet],
+    format: ExportFormat,
+    file_name: &str,
+    timezone: Tz,
+) -> Result<Response, Error> {
+    match format {
+        ExportFormat::Csv => {
+            let mut body = UTF8_BOM.to_vec();
+            for (i, sheet) in sheets.iter().enumerate() {
+                if i > 0 {
+                    body.push(b'\n');
+                }
+                body.extend(write_csv(
+                    Some(sheet.title),
+                    sheet.headers,
+                    &sheet.rows,
+                    timezone,
+                )?);
+            }
+            Ok(file_response(body, format, file_name))
+        }
+        ExportFormat::Xlsx => Ok(file_response(
+            write_xlsx(sheets, timezone)?,
+            format,
+            file_name,
+        )),
+    }
+}
+
+/// Выгрузить весь список с фильтрами и сортировкой из `query`; параметры страницы не учитываются.
+///
+/// CSV отправляется по мере чтения строк из базы, XLSX собирается целиком.
+pub async fn list_response<F, S, T>(
+    pool: PgPool,
+    source: &'static ListSource,
+    query: ListQuery<F, S>,
+    format: ExportFormat,
+    file_name: &str,
+    timezone: Tz,
+) -> Result<Response, Error>
+where
+    F: ListFilter + 'static,
+    S: SortField + 'static,
+    T: ExportRow,
+{
+    if format == ExportFormat::Xlsx {
+        let mut builder = list_all_query(source, &query);
+        builder.push(" LIMIT ").push_bind(MAX_XLSX_ROWS as i64 + 1);
+        let rows: Vec<T> = builder.build_query_as().fetch_all(&pool).await?;
+        if rows.len() > MAX_XLSX_ROWS {
+            return Err(Error::UnprocessableEntity(format!(
+                "XLSX export is limited to {MAX_XLSX_ROWS} rows, narrow the filters or export CSV"
+            )));
+        }
+        let sheet = Sheet {
+            title: T::SHEET_TITLE,
+            headers: T::HEADERS,
+            rows: rows.into_iter().map(ExportRow::cells).collect(),
+        };
+        return sheets_response(&[sheet], format, file_name, timezone);
+    }
+
+    let builder = list_all_query(source, &query);
+    let (tx, rx) = mpsc::channel(4);
+    tokio::spawn(
+        async move {
+            if let Err(e) = stream_csv::<T>(&pool, builder, timezone, &tx).await {
+                tracing::error!(error = ?e, "failed to export list");
+                let _ = tx.send(Err(e)).await;
+            }
+        }
+        .instrument(tracing::Span::current()),
+    );
+
+    Ok(file_response(
+        Body::from_stream(ReceiverStream::new(rx)),
+        format,
+        file_name,
+    ))
+}
+
+/// Отправлять CSV кусками по мере чтения строк; если клиент отключился, чтение прекращается.
+async fn stream_csv<T: ExportRow>(
+    pool: &PgPool,
+    mut builder: QueryBuilder<'static, Postgres>,
+    timezone: Tz,
+    tx: &mpsc::Sender<Result<Vec<u8>, Error>>,
+) -> Result<(), Error> {
+    let mut header = UTF8_BOM.to_vec();
+    header.extend(write_csv(None, T::HEADERS, &[], timezone)?);
+    if tx.send(Ok(header)).await.is_err() {
+        return Ok(());
+    }
+
+    let mut rows = builder.build_query_as::<T>().fetch(pool);
+    let mut batch = Vec::with_capacity(CSV_BATCH_SIZE);
+    loop {
+        let row = rows.next().await.transpose()?;
+        let done = row.is_none();
+        batch.extend(row.map(ExportRow::cells));
+
+        if done || batch.len() == CSV_BATCH_SIZE {
+            let chunk = write_csv(None, &[], &batch, timezone)?;
+            batch.clear();
+            if tx.send(Ok(chunk)).await.is_err() || done {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn file_response(body: impl Into<Body>, format: ExportFormat, file_name: &str) -> Response {
+    let (content_type, extension) = match format {
+        ExportFormat::Csv => (CSV_CONTENT_TYPE, "csv"),
+        ExportFormat::Xlsx => (XLSX_CONTENT_TYPE, "xlsx"),
+    };
+    (
+        [
+            (CONTENT_TYPE, content_type.to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}.{extension}\""),
+            ),
+        ],
+        body.into(),
+    )
+        .into_response()
+}
+
+fn write_csv(
+    title: Option<&str>,
+    headers: &[&str],
+    rows: &[Vec<Cell>],
+    timezone: Tz,
+) -> Result<Vec<u8>, Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(CSV_DELIMITER)
+        .flexible(true)
+        .from_writer(Vec::new());
+
+    if let Some(title) = title {
+        writer
+            .write_record([title])
+            .context("failed to write CSV")?;
+    }
+    if !headers.is_empty() {
+        writer
+            .write_record(headers)
+            .context("failed to write CSV")?;
+    }
+    for row in rows {
+        writer
+            .write_record(row.iter().map(|cell| cell.to_csv(timezone)))
+            .context("failed to write CSV")?;
+    }
+
+    Ok(writer.into_inner().context("failed to write CSV")?)
+}
+
+fn write_xlsx(sheets: &[Sheet], timezone: Tz) -> Result<Vec<u8>, Error> {
+    let header_format = Format::new().set_bold();
+    let money_format = Format::new().set_num_format(MONEY_FORMAT);
+    let percent_format = Format::new().set_num_format(PERCENT_FORMAT);
+    let date_time_format = Format::new().set_num_format(DATE_TIME_FORMAT);
+
+    let mut workbook = Workbook::new();
+    for sheet in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet
+            .set_name(sheet.title)
+            .context("invalid worksheet name")?;
+
+        for (col, header) in (0..).zip(sheet.headers) {
+            worksheet
+                .write_string_with_format(0, col, *header, &header_format)
+                .context("failed to write XLSX")?;
+        }
+        for (row, cells) in (1..).zip(&sheet.rows) {
+            for (col, cell) in (0..).zip(cells) {
+                match cell {
+                    Cell::Empty => continue,
+                    // Строковая ячейка: текст с `=` в начале не становится формулой.
+                    Cell::Text(text) => worksheet.write_string(row, col, text),
+                    Cell::Integer(value) => worksheet.write_number(row, col, *value as f64),
+                    Cell::Money(value) => {
+                        worksheet.write_number_with_format(row, col, value.to_f64(), &money_format)
+                    }
+                    Cell::Percent(value) => {
+                        worksheet.write_number_with_format(row, col, *value, &percent_format)
+                    }
+                    Cell::DateTime(value) => worksheet.write_datetime_with_format(
+                        row,
+                        col,
+                        value.with_timezone(&timezone).naive_local(),
+                        &date_time_format,
+                    ),
+                }
+                .context("failed to write XLSX")?;
+            }
+        }
+
+        worksheet
+            .set_freeze_panes(1, 0)
+            .context("failed to write XLSX")?;
+        worksheet.autofit();
+    }
+
+    Ok(workbook
+        .save_to_buffer()
+        .context("failed to build XLSX workbook")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_sections_use_semicolons_and_company_timezone() {
+        let rows = vec![vec![
+            Cell::text("Протечка; подвал"),
+            Cell::Money("1500.5".parse().unwrap()),
+            Cell::percent("+12.50%"),
+            Cell::DateTime("2024-03-31T21:30:00Z".parse().unwrap()),
+        ]];
+
+        let csv = write_csv(
+            Some("Аварии"),
+            &["Тип", "Сумма", "Изменение", "Дата"],
+            &rows,
+            Tz::Europe__Moscow,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "Аварии\nТип;Сумма;Изменение;Дата\n\"Протечка; подвал\";1500.50;12.50%;01.04.2024 00:30\n"
+        );
+    }
+
+    #[test]
+    fn csv_text_is_never_a_formula() {
+        let rows = vec![vec![
+            Cell::text("=HYPERLINK(\"http://example.com\")"),
+            Cell::text("+7 900 000-00-00"),
+            Cell::text("-"),
+            Cell::text("@SUM(A1)"),
+            Cell::text("Протечка = 2 м"),
+            Cell::Money("-1500.5".parse().unwrap()),
+        ]];
+
+        let csv = write_csv(None, &[], &rows, Tz::UTC).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "\"'=HYPERLINK(\"\"http://example.com\"\")\";'+7 900 000-00-00;'-;'@SUM(A1);Протечка = 2 м;-1500.50\n"
+        );
+    }
+
+    #[test]
+    fn unparsable_percent_is_kept_as_text() {
+        assert_eq!(Cell::percent("-100.00%"), Cell::Percent(-1.0));
+        assert_eq!(Cell::percent("н/д"), Cell::text("н/д"));
+    }
+
+    #[test]
+    fn xlsx_has_one_sheet_per_section() {
+        let sheets = [
+            Sheet {
+                title: "Сводка",
+                headers: &["Показатель", "Значение"],
+                rows: vec![vec![Cell::text("Аварии"), Cell::Integer(3)]],
+            },
+            Sheet {
+                title: "Затраты",
+                headers: &["Тип аварии", "Затраты"],
+                rows: vec![vec![Cell::text("Протечка"), Cell::Money(Money::default())]],
+            },
+        ];
+
+        let workbook = write_xlsx(&sheets, Tz::UTC).unwrap();
+
+        assert!(workbook.starts_with(b"PK"));
+        let contents = String::from_utf8_lossy(&workbook);
+        assert!(contents.contains("xl/worksheets/sheet2.xml"));
+        assert!(!contents.contains("xl/worksheets/sheet3.xml"));
+    }
+}
@@ -11,16 +11,20 @@ use sha2::Sha384;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
-const DEFAULT_SESSION_LENGTH: time::Duration = time::Duration::weeks(1);
-
 const SCHEME_PREFIX: &str = "Bearer ";
 
 pub struct AuthUser {
     pub user_id: Uuid,
 }
 
+#[allow(dead_code)]
 pub struct MaybeAuthUser(pub Option<AuthUser>);
 
+/// Пользователь с правами администратора; остальным пользователям запрос отвечает 403.
+pub struct AdminUser {
+    pub user_id: Uuid,
+}
+
 #[derive(Serialize, Deserialize)]
 struct AuthUserClaims {
     user_id: Uuid,
@@ -34,7 +38,7 @@ impl AuthUser {
 
         AuthUserClaims {
             user_id: self.user_id,
-            exp: (OffsetDateTime::now_utc() + DEFAULT_SESSION_LENGTH).unix_timestamp(),
+            exp: (OffsetDateTime::now_utc() + ctx.config.session_length()).unix_timestamp(),
         }
         .sign_with_key(&hmac)
         .expect("HMAC signing should be infallible")
@@ -75,7 +79,7 @@ fn get_claims(
 
 fn get_token_from_header(
     auth_header: &HeaderValue,
-) -> Result<Token<jwt::Header, AuthUserClaims, jwt::Unverified>, Error> {
+) -> Result<Token<jwt::Header, AuthUserClaims, jwt::Unverified<'_>>, Error> {
     let auth_header = auth_header.to_str().map_err(|_| {
         tracing::debug!("Authorization header is not UTF-8");
         Error::Unauthorized
@@ -145,3 +149,33 @@ where
         ))
     }
 }
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AdminUser
+where
+    S: Send + Sync,
+    ApiContext: FromRef<S>,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let AuthUser { user_id } = AuthUser::from_request_parts(parts, state).await?;
+        let ctx: ApiContext = ApiContext::from_ref(state);
+
+        let is_admin = sqlx::query_scalar!(
+            r#"
+            SELECT is_admin FROM user_account WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&ctx.db)
+        .await?
+        .ok_or(Error::Unauthorized)?;
+
+        if !is_admin {
+            return Err(Error::Forbidden);
+        }
+
+        Ok(Self { user_id })
+    }
+}
@@ -0,0 +1,132 @@
+use axum::{
+    extract::{Query, State},
+    response::Response,
+    Json,
+};
+
+use crate::api::{
+    export::{list_response, ExportParams},
+    extractor::AuthUser,
+    pagination::{fetch_page, ListQuery, ListSource, PageParams, SortOrder},
+    ApiContext, Error,
+};
+
+use super::models::{
+    FinancialOperation, FinancialOperationFilter, FinancialOperationList, FinancialOperationRow,
+    FinancialOperationSortField,
+};
+
+const FINANCIAL_OPERATION_LIST: ListSource = ListSource {
+    select: r#"
+        SELECT
+            fo.id,
+            fo.happen_at,
+            fo.type AS operation_type,
+            fo.amount,
+            fo.currency,
+            fo.description,
+            b.number AS building_number,
+            a.region,
+            a.city,
+            a.street
+        "#,
+    from: r#"
+        FROM
+            financial_operation fo
+        LEFT JOIN
+            repair r ON fo.repair_id = r.id
+        LEFT JOIN
+            building b ON r.building_id = b.id
+        LEFT JOIN
+            address a ON b.address_id = a.id
+        "#,
+    condition: "TRUE",
+    id_column: "fo.id",
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/financial_operations",
+    tag = "financial_operations",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<FinancialOperationSortField>, Query, description = "Поле сортировки"),
+        FinancialOperationFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка операций", body = FinancialOperationList),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверные параметры списка"),
+    )
+)]
+pub async fn get_all_financial_operations(
+    _: AuthUser,
+    State(ctx): State<ApiContext>,
+    query: ListQuery<FinancialOperationFilter, FinancialOperationSortField>,
+) -> Result<Json<FinancialOperationList>, Error> {
+    let query = query.whole_list_unless_paged();
+    let (db_operations, page_info) = fetch_page(
+        &ctx.db,
+        &FINANCIAL_OPERATION_LIST,
+        &query,
+        |row: &FinancialOperationRow| row.id,
+    )
+    .await?;
+
+    let financial_operations = db_operations
+        .into_iter()
+        .map(|row| FinancialOperation {
+            id: row.id,
+            happened_at: row.happen_at,
+            operation_type: row.operation_type.to_string(),
+            amount: row.amount,
+            building_address: row.building_address(),
+            currency: row.currency,
+            description: row.description,
+        })
+        .collect();
+
+    Ok(Json(FinancialOperationList {
+        financial_operations,
+        page_info,
+    }))
+}
+
+/// Выгрузить все операции с фильтрами и сортировкой списка в CSV или XLSX.
+#[utoipa::path(
+    get,
+    path = "/api/financial_operations/export",
+    tag = "financial_operations",
+    security(("bearer" = [])),
+    params(
+        ExportParams,
+        ("sortBy" = Option<FinancialOperationSortField>, Query, description = "Поле сортировки"),
+        ("sortOrder" = Option<SortOrder>, Query, description = "Порядок сортировки"),
+        FinancialOperationFilter,
+    ),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверные параметры списка"),
+    )
+)]
+pub async fn export_financial_operations(
+    _: AuthUser,
+    State(ctx): State<ApiContext>,
+    Query(params): Query<ExportParams>,
+    query: ListQuery<FinancialOperationFilter, FinancialOperationSortField>,
+) -> Result<Response, Error> {
+    list_response::<_, _, FinancialOperationRow>(
+        ctx.db,
+        &FINANCIAL_OPERATION_LIST,
+        query,
+        params.format,
+        "financial_operations",
+        ctx.config.timezone,
+    )
+    .await
+}
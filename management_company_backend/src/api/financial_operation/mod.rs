@@ -1,2 +1,34 @@
+use axum::{routing::get, Router};
+use controllers::{export_financial_operations, get_all_financial_operations};
+use utoipa::OpenApi;
+
+use super::ApiContext;
+
 mod controllers;
-mod models;
+pub mod models;
+pub(crate) mod v2;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::get_all_financial_operations,
+        controllers::export_financial_operations,
+        v2::get_all_financial_operations,
+        v2::export_financial_operations,
+    ),
+    components(schemas(models::FinancialOperationSortField)),
+    tags((name = "financial_operations", description = "Финансовые операции"))
+)]
+pub(crate) struct ApiDoc;
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route(
+            "/api/financial_operations",
+            get(get_all_financial_operations),
+        )
+        .route(
+            "/api/financial_operations/export",
+            get(export_financial_operations),
+        )
+}
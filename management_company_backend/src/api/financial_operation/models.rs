@@ -0,0 +1,173 @@
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::Type, FromRow, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::{
+    export::{Cell, ExportRow},
+    money::Money,
+    pagination::{ListFilter, PageInfo, SortField, SortOrder},
+};
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Type, ToSchema)]
+#[sqlx(type_name = "financial_operation_type", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum FinancialOperationType {
+    Deposit,
+    Withdrawal,
+    Transfer,
+    Payment,
+    Adjustment,
+}
+
+impl fmt::Display for FinancialOperationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Deposit => "Поступление",
+            Self::Withdrawal => "Списание",
+            Self::Transfer => "Перевод",
+            Self::Payment => "Оплата",
+            Self::Adjustment => "Корректировка",
+        })
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FinancialOperation {
+    pub id: Uuid,
+    pub happened_at: Option<DateTime<Utc>>,
+    pub operation_type: String,
+    pub amount: Money,
+    pub currency: String,
+    pub description: Option<String>,
+    pub building_address: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FinancialOperationList {
+    pub financial_operations: Vec<FinancialOperation>,
+    #[serde(flatten)]
+    pub page_info: PageInfo,
+}
+
+#[derive(FromRow)]
+pub struct FinancialOperationRow {
+    pub id: Uuid,
+    pub happen_at: Option<DateTime<Utc>>,
+    pub operation_type: FinancialOperationType,
+    pub amount: Money,
+    pub currency: String,
+    pub description: Option<String>,
+    pub building_number: Option<i32>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub street: Option<String>,
+}
+
+impl FinancialOperationRow {
+    /// Адрес дома, если операция относится к ремонту.
+    pub fn building_address(&self) -> Option<String> {
+        self.building_number.map(|number| {
+            format!(
+                "{}, {}, {}, дом {}",
+                self.region.as_deref().unwrap_or_default(),
+                self.city.as_deref().unwrap_or_default(),
+                self.street.as_deref().unwrap_or_default(),
+                number
+            )
+        })
+    }
+}
+
+impl ExportRow for FinancialOperationRow {
+    const SHEET_TITLE: &'static str = "Финансовые операции";
+    const HEADERS: &'static [&'static str] = &[
+        "Дата",
+        "Тип операции",
+        "Сумма",
+        "Валюта",
+        "Адрес",
+        "Описание",
+    ];
+
+    fn cells(self) -> Vec<Cell> {
+        vec![
+            self.happen_at.map_or(Cell::Empty, Cell::DateTime),
+            Cell::text(self.operation_type.to_string()),
+            Cell::Money(self.amount),
+            Cell::text(self.currency.as_str()),
+            self.building_address().map_or(Cell::Empty, Cell::Text),
+            self.description.map_or(Cell::Empty, Cell::Text),
+        ]
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct FinancialOperationFilter {
+    pub operation_type: Option<FinancialOperationType>,
+    pub currency: Option<String>,
+    pub building_id: Option<Uuid>,
+    pub happened_from: Option<NaiveDate>,
+    pub happened_to: Option<NaiveDate>,
+}
+
+impl ListFilter for FinancialOperationFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(operation_type) = self.operation_type {
+            builder.push(" AND fo.type = ").push_bind(operation_type);
+        }
+        if let Some(currency) = &self.currency {
+            builder
+                .push(" AND fo.currency = ")
+                .push_bind(currency.clone());
+        }
+        if let Some(building_id) = self.building_id {
+            builder.push(" AND r.building_id = ").push_bind(building_id);
+        }
+        if let Some(happened_from) = self.happened_from {
+            builder
+                .push(" AND fo.happen_at >= ")
+                .push_bind(happened_from)
+                .push("::date");
+        }
+        if let Some(happened_to) = self.happened_to {
+            builder
+                .push(" AND fo.happen_at < ")
+                .push_bind(happened_to)
+                .push("::date + 1");
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FinancialOperationSortField {
+    #[default]
+    HappenedAt,
+    Amount,
+    OperationType,
+}
+
+impl SortField for FinancialOperationSortField {
+    fn expression(self) -> &'static str {
+        match self {
+            Self::HappenedAt => "COALESCE(fo.happen_at, '-infinity')",
+            Self::Amount => "fo.amount",
+            Self::OperationType => "fo.type",
+        }
+    }
+
+    fn default_order(self) -> SortOrder {
+        match self {
+            Self::HappenedAt | Self::Amount => SortOrder::Desc,
+            Self::OperationType => SortOrder::Asc,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Query, State},
+    response::Response,
+    routing::get,
+    Json, Router,
+};
+
+use crate::api::{
+    envelope::{Envelope, ErrorEnvelope},
+    export::ExportParams,
+    extractor::AuthUser,
+    pagination::{ListQuery, PageParams, SortOrder},
+    ApiContext, Error,
+};
+
+use super::{
+    controllers,
+    models::{FinancialOperation, FinancialOperationFilter, FinancialOperationSortField},
+};
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route(
+            "/api/v2/financial_operations",
+            get(get_all_financial_operations),
+        )
+        .route(
+            "/api/v2/financial_operations/export",
+            get(export_financial_operations),
+        )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/financial_operations",
+    tag = "financial_operations",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<FinancialOperationSortField>, Query, description = "Поле сортировки"),
+        FinancialOperationFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка операций", body = Envelope<Vec<FinancialOperation>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_all_financial_operations(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    query: ListQuery<FinancialOperationFilter, FinancialOperationSortField>,
+) -> Result<Json<Envelope<Vec<FinancialOperation>>>, Error> {
+    let Json(list) = controllers::get_all_financial_operations(user, ctx, query.paged()).await?;
+    Ok(Envelope::page(list.financial_operations, list.page_info))
+}
+
+/// Выгрузить все операции с фильтрами и сортировкой списка в CSV или XLSX.
+#[utoipa::path(
+    get,
+    path = "/api/v2/financial_operations/export",
+    tag = "financial_operations",
+    security(("bearer" = [])),
+    params(
+        ExportParams,
+        ("sortBy" = Option<FinancialOperationSortField>, Query, description = "Поле сортировки"),
+        ("sortOrder" = Option<SortOrder>, Query, description = "Порядок сортировки"),
+        FinancialOperationFilter,
+    ),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn export_financial_operations(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<ExportParams>,
+    query: ListQuery<FinancialOperationFilter, FinancialOperationSortField>,
+) -> Result<Response, Error> {
+    controllers::export_financial_operations(user, ctx, params, query).await
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode, Json};
+use sqlx::{migrate::Migrator, PgPool};
+
+use crate::{api::ApiContext, MIGRATOR};
+
+use super::models::{ComponentHealth, HealthStatus, Liveness, Readiness, ReadinessComponents};
+
+/// Сколько ждать ответа базы, прежде чем считать её недоступной.
+const DATABASE_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Сервер запущен; для проверок, которым достаточно ответа в виде текста.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "Сервер запущен", body = String, content_type = "text/plain")),
+)]
+pub async fn healthy() -> &'static str {
+    "healthy"
+}
+
+/// Процесс жив, пока отвечает на запросы; зависимости здесь не проверяются.
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses((status = 200, description = "Процесс жив", body = Liveness)),
+)]
+pub async fn live() -> Json<Liveness> {
+    Json(Liveness {
+        status: HealthStatus::Up,
+    })
+}
+
+/// Готовность принимать трафик: база доступна и все миграции применены.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Сервер готов принимать трафик", body = Readiness),
+        (status = 503, description = "База недоступна или миграции не применены", body = Readiness),
+    ),
+)]
+pub async fn ready(State(ctx): State<ApiContext>) -> (StatusCode, Json<Readiness>) {
+    let readiness = check_readiness(&ctx.db, &MIGRATOR).await;
+    let status = match readiness.status {
+        HealthStatus::Up => StatusCode::OK,
+        HealthStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (status, Json(readiness))
+}
+
+async fn check_readiness(pool: &PgPool, migrator: &Migrator) -> Readiness {
+    let applied = tokio::time::timeout(
+        DATABASE_CHECK_TIMEOUT,
+        sqlx::query!(
+            r#"
+            SELECT version, checksum
+            FROM _sqlx_migrations
+            WHERE success
+            "#
+        )
+        .fetch_all(pool),
+    )
+    .await;
+
+    let (database, migrations) = match applied {
+        Ok(Ok(applied)) => {
+            let applied: Vec<(i64, Vec<u8>)> = applied
+                .into_iter()
+                .map(|row| (row.version, row.checksum))
+                .collect();
+            (ComponentHealth::up(), check_migrations(migrator, &applied))
+        }
+        Ok(Err(e)) => {
+            log::error!("Readiness check failed: {:?}", e);
+            (
+                ComponentHealth::down("database query failed"),
+                ComponentHealth::down("could not read applied migrations"),
+            )
+        }
+        Err(_) => (
+            ComponentHealth::down(format!(
+                "no response within {} s",
+                DATABASE_CHECK_TIMEOUT.as_secs()
+            )),
+            ComponentHealth::down("could not read applied migrations"),
+        ),
+    };
+
+    let status = if database.status == HealthStatus::Up && migrations.status == HealthStatus::Up {
+        HealthStatus::Up
+    } else {
+        HealthStatus::Down
+    };
+    Readiness {
+        status,
+        components: ReadinessComponents {
+            database,
+            migrations,
+        },
+    }
+}
+
+/// Все миграции сборки должны быть применены и не изменены после применения.
+fn check_migrations(migrator: &Migrator, applied: &[(i64, Vec<u8>)]) -> ComponentHealth {
+    let mut pending = Vec::new();
+    let mut modified = Vec::new();
+    for migration in migrator
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration())
+    {
+        match applied
+            .iter()
+            .find(|(version, _)| *version == migration.version)
+        {
+            None => pending.push(migration.version.to_string()),
+            Some((_, checksum)) if *checksum != *migration.checksum => {
+                modified.push(migration.version.to_string())
+            }
+            Some(_) => {}
+        }
+    }
+
+    match (pending.is_empty(), modified.is_empty()) {
+        (true, true) => ComponentHealth::up(),
+        (false, _) => ComponentHealth::down(format!("pending: {}", pending.join(", "))),
+        (true, false) => ComponentHealth::down(format!(
+            "modified after being applied: {}",
+            modified.join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn ready_when_all_migrations_are_applied(pool: PgPool) {
+        let readiness = check_readiness(&pool, &MIGRATOR).await;
+
+        assert_eq!(readiness.status, HealthStatus::Up);
+        assert_eq!(readiness.components.database, ComponentHealth::up());
+        assert_eq!(readiness.components.migrations, ComponentHealth::up());
+    }
+
+    #[sqlx::test]
+    async fn not_ready_with_pending_migration(pool: PgPool) {
+        let last = MIGRATOR.iter().last().unwrap().version;
+        sqlx::query!("DELETE FROM _sqlx_migrations WHERE version = $1", last)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let readiness = check_readiness(&pool, &MIGRATOR).await;
+
+        assert_eq!(readiness.status, HealthStatus::Down);
+        assert_eq!(readiness.components.database, ComponentHealth::up());
+        assert_eq!(
+            readiness.components.migrations,
+            ComponentHealth::down(format!("pending: {last}"))
+        );
+    }
+
+    #[sqlx::test]
+    async fn not_ready_with_modified_migration(pool: PgPool) {
+        let first = MIGRATOR.iter().next().unwrap().version;
+        sqlx::query!(
+            "UPDATE _sqlx_migrations SET checksum = '\\x00' WHERE version = $1",
+            first
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let readiness = check_readiness(&pool, &MIGRATOR).await;
+
+        assert_eq!(
+            readiness.components.migrations.details.as_deref(),
+            Some(format!("modified after being applied: {first}").as_str())
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use axum::{routing::get, Router};
+use controllers::{healthy, live, ready};
+use utoipa::OpenApi;
+
+use super::ApiContext;
+
+mod controllers;
+mod models;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(controllers::healthy, controllers::live, controllers::ready),
+    tags((name = "health", description = "Проверки живости и готовности"))
+)]
+pub(crate) struct ApiDoc;
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/health", get(healthy))
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+}
@@ -0,0 +1,54 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentHealth {
+    pub status: HealthStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl ComponentHealth {
+    pub fn up() -> Self {
+        Self {
+            status: HealthStatus::Up,
+            details: None,
+        }
+    }
+
+    pub fn down(details: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Down,
+            details: Some(details.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Liveness {
+    pub status: HealthStatus,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Readiness {
+    /// `up`, только если все компоненты `up`.
+    pub status: HealthStatus,
+    pub components: ReadinessComponents,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessComponents {
+    pub database: ComponentHealth,
+    pub migrations: ComponentHealth,
+}
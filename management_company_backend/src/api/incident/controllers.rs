@@ -1,34 +1,42 @@
-use axum::{extract::State, Json};
-use sqlx::query;
-use uuid::Uuid;
+use axum::{
+    extract::{Path, Query, State},
+    response::Response,
+    Json,
+};
 
-use crate::api::{extractor::AuthUser, incident::models::IncidentStatus, ApiContext, Error};
+use crate::api::{
+    audit::{models::AuditEntity, utils::AuditRecord},
+    conditional::{IfMatch, Tagged},
+    export::{list_response, ExportParams},
+    extractor::AuthUser,
+    incident::models::IncidentStatus,
+    pagination::{fetch_page, ListQuery, ListSource, PageParams, SortOrder},
+    trash::{models::TrashEntity, utils::soft_delete},
+    ApiContext, Error,
+};
 
 use super::models::{
-    Incident, IncidentDetails, IncidentList, IncidentType, IncidentTypeList, NewIncident,
+    Incident, IncidentDetails, IncidentDetailsRow, IncidentFilter, IncidentList, IncidentSortField,
+    IncidentType, IncidentTypeList, NewIncident, UpdateIncident,
 };
+use uuid::Uuid;
 
-pub async fn get_all_incidents(
-    _: AuthUser,
-    ctx: State<ApiContext>,
-) -> Result<Json<IncidentList>, Error> {
-    let db_incidents = query!(
-        r#"
+const INCIDENT_LIST: ListSource = ListSource {
+    select: r#"
         SELECT
             i.id,
             i.reported_at,
             i.resolved_at,
-            i.status as "status: IncidentStatus",
+            i.status,
             i.description,
-            it.name AS "incident_type_name: String",
-            b.id AS "building_id: Uuid",
-            b.number AS "building_number: i32",
-            b.number_of_floors AS "building_number_of_floors: i16",
-            b.construction_date,
-            a.country AS "address_country!: String",
-            a.region AS "address_region!: String",
-            a.city AS "address_city!: String",
-            a.street AS "address_street!: String"
+            it.name AS incident_type_name,
+            b.number AS building_number,
+            a.region AS address_region,
+            a.city AS address_city,
+            a.street AS address_street,
+            i.version
+        "#,
+    from: r#"
         FROM
             incident i
         JOIN
@@ -37,44 +45,115 @@ pub async fn get_all_incidents(
             building b ON i.building_id = b.id
         JOIN
             address a ON b.address_id = a.id
-        "#
+        "#,
+    condition: "i.deleted_at IS NULL",
+    id_column: "i.id",
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/incidents",
+    tag = "incidents",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<IncidentSortField>, Query, description = "Поле сортировки"),
+        IncidentFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка аварий", body = IncidentList),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверные параметры списка"),
+    )
+)]
+pub async fn get_all_incidents(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    query: ListQuery<IncidentFilter, IncidentSortField>,
+) -> Result<Json<IncidentList>, Error> {
+    let query = query.whole_list_unless_paged();
+    let (db_incidents, page_info) = fetch_page(
+        &ctx.db,
+        &INCIDENT_LIST,
+        &query,
+        |row: &IncidentDetailsRow| row.id,
     )
-    .fetch_all(&ctx.db)
     .await?;
 
     let incidents = db_incidents
         .into_iter()
-        .map(|incident| IncidentDetails {
-            id: incident.id,
-            reported_at: incident.reported_at.unwrap_or_default(),
-            resolved_at: incident.resolved_at,
-            status: incident.status.to_string(),
-            description: incident.description,
-            incident_type_name: incident.incident_type_name,
-            building_address: format!(
-                "{}, {}, {}, дом {}",
-                incident.address_region,
-                incident.address_city,
-                incident.address_street,
-                incident.building_number
-            ),
-        })
+        .map(IncidentDetails::from)
         .collect();
 
-    Ok(Json(IncidentList { incidents }))
+    Ok(Json(IncidentList {
+        incidents,
+        page_info,
+    }))
 }
 
-pub async fn add_incident(
+/// Выгрузить все аварии с фильтрами и сортировкой списка в CSV или XLSX.
+#[utoipa::path(
+    get,
+    path = "/api/incidents/export",
+    tag = "incidents",
+    security(("bearer" = [])),
+    params(
+        ExportParams,
+        ("sortBy" = Option<IncidentSortField>, Query, description = "Поле сортировки"),
+        ("sortOrder" = Option<SortOrder>, Query, description = "Порядок сортировки"),
+        IncidentFilter,
+    ),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверные параметры списка"),
+    )
+)]
+pub async fn export_incidents(
     _: AuthUser,
     State(ctx): State<ApiContext>,
+    Query(params): Query<ExportParams>,
+    query: ListQuery<IncidentFilter, IncidentSortField>,
+) -> Result<Response, Error> {
+    list_response::<_, _, IncidentDetailsRow>(
+        ctx.db,
+        &INCIDENT_LIST,
+        query,
+        params.format,
+        "incidents",
+        ctx.config.timezone,
+    )
+    .await
+}
+
+/// Зарегистрировать аварию в доме.
+#[utoipa::path(
+    post,
+    path = "/api/incidents",
+    tag = "incidents",
+    security(("bearer" = [])),
+    request_body = NewIncident,
+    responses(
+        (status = 200, description = "Авария зарегистрирована", body = Incident),
+        (status = 401, description = "Нет действующего токена"),
+    )
+)]
+pub async fn add_incident(
+    user: AuthUser,
+    State(ctx): State<ApiContext>,
     Json(new_incident): Json<NewIncident>,
 ) -> Result<Json<Incident>, Error> {
+    let mut transaction = ctx.db.begin().await?;
+
     let incident = sqlx::query_as_unchecked!(
         Incident,
         r#"
         INSERT INTO incident (building_id, resolved_at, status, description, incident_type_id)
         VALUES ($1, $2,  $3::incident_status, $4, $5)
-        RETURNING id, building_id, reported_at, resolved_at, status AS "status: _", description, incident_type_id
+        RETURNING id, building_id, reported_at, resolved_at, status AS "status: _", description, incident_type_id, version
         "#,
         new_incident.building_id,
         new_incident.resolved_at,
@@ -82,12 +161,147 @@ pub async fn add_incident(
         new_incident.description,
         new_incident.incident_type_id
     )
-    .fetch_one(&ctx.db)
+    .fetch_one(&mut *transaction)
+    .await?;
+
+    let snapshot = sqlx::query_scalar!(
+        r#"
+        SELECT to_jsonb(i) - 'search_vector' AS "snapshot!"
+        FROM incident i
+        WHERE id = $1
+        "#,
+        incident.id
+    )
+    .fetch_one(&mut *transaction)
     .await?;
+    AuditRecord::created(AuditEntity::Incident, incident.id, snapshot)
+        .save(&mut transaction, Some(user.user_id))
+        .await?;
+
+    transaction.commit().await?;
+
+    ctx.statistics_cache.invalidate();
 
     Ok(Json(incident))
 }
 
+/// Авария по идентификатору; маршрут есть только в v2.
+pub async fn get_incident(
+    _: AuthUser,
+    State(ctx): State<ApiContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Tagged<Json<IncidentDetails>>, Error> {
+    let incident = sqlx::query_as::<_, IncidentDetailsRow>(&format!(
+        "{} {} WHERE {} AND i.id = $1",
+        INCIDENT_LIST.select, INCIDENT_LIST.from, INCIDENT_LIST.condition
+    ))
+    .bind(id)
+    .fetch_optional(&ctx.db)
+    .await?
+    .ok_or(Error::NotFound)?;
+
+    Ok(Tagged::new(incident.version, Json(incident.into())))
+}
+
+/// Изменить статус, дату устранения или описание аварии; маршрут есть только в v2.
+pub async fn update_incident(
+    user: AuthUser,
+    State(ctx): State<ApiContext>,
+    Path(id): Path<Uuid>,
+    if_match: IfMatch,
+    Json(payload): Json<UpdateIncident>,
+) -> Result<Tagged<Json<IncidentDetails>>, Error> {
+    let mut transaction = ctx.db.begin().await?;
+
+    let before = sqlx::query!(
+        r#"
+        SELECT version, to_jsonb(i) - 'search_vector' AS "snapshot!"
+        FROM incident i
+        WHERE id = $1 AND deleted_at IS NULL
+        FOR UPDATE
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(Error::NotFound)?;
+    if_match.check(before.version)?;
+
+    let after = sqlx::query_scalar!(
+        r#"
+        UPDATE incident
+        SET
+            status = COALESCE($1, status),
+            resolved_at = COALESCE($2, resolved_at),
+            description = COALESCE($3, description)
+        WHERE
+            id = $4
+        RETURNING to_jsonb(incident) - 'search_vector' AS "snapshot!"
+        "#,
+        payload.status as Option<IncidentStatus>,
+        payload.resolved_at,
+        payload.description,
+        id
+    )
+    .fetch_one(&mut *transaction)
+    .await?;
+
+    AuditRecord::updated(AuditEntity::Incident, id, before.snapshot, after)
+        .save(&mut transaction, Some(user.user_id))
+        .await?;
+    transaction.commit().await?;
+
+    ctx.statistics_cache.invalidate();
+
+    get_incident(user, State(ctx), Path(id)).await
+}
+
+/// Удалить аварию в корзину вместе с её ремонтами; маршрут есть только в v2.
+pub async fn delete_incident(
+    user: AuthUser,
+    State(ctx): State<ApiContext>,
+    Path(id): Path<Uuid>,
+    if_match: IfMatch,
+) -> Result<(), Error> {
+    let mut transaction = ctx.db.begin().await?;
+
+    let version = sqlx::query_scalar!(
+        r#"
+        SELECT version
+        FROM incident
+        WHERE id = $1 AND deleted_at IS NULL
+        FOR UPDATE
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(Error::NotFound)?;
+    if_match.check(version)?;
+
+    let before = soft_delete(&mut transaction, TrashEntity::Incident, id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    AuditRecord::deleted(AuditEntity::Incident, id, before)
+        .save(&mut transaction, Some(user.user_id))
+        .await?;
+    transaction.commit().await?;
+
+    ctx.statistics_cache.invalidate();
+
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/incidents/types",
+    tag = "incidents",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Все типы аварий", body = IncidentTypeList),
+        (status = 401, description = "Нет действующего токена"),
+    )
+)]
 pub async fn get_all_incident_types(
     _: AuthUser,
     State(ctx): State<ApiContext>,
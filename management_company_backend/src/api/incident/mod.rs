@@ -1,13 +1,36 @@
 use axum::{routing::get, Router};
-use controllers::{add_incident, get_all_incident_types, get_all_incidents};
+use controllers::{add_incident, export_incidents, get_all_incident_types, get_all_incidents};
+use utoipa::OpenApi;
 
 use super::ApiContext;
 
 mod controllers;
 pub mod models;
+pub(crate) mod v2;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::get_all_incidents,
+        controllers::add_incident,
+        controllers::get_all_incident_types,
+        controllers::export_incidents,
+        v2::get_all_incidents,
+        v2::add_incident,
+        v2::get_all_incident_types,
+        v2::export_incidents,
+        v2::get_incident,
+        v2::update_incident,
+        v2::delete_incident,
+    ),
+    components(schemas(models::IncidentSortField)),
+    tags((name = "incidents", description = "Аварии и их типы"))
+)]
+pub(crate) struct ApiDoc;
 
 pub(crate) fn router() -> Router<ApiContext> {
     Router::new()
         .route("/api/incidents", get(get_all_incidents).post(add_incident))
         .route("/api/incidents/types", get(get_all_incident_types))
+        .route("/api/incidents/export", get(export_incidents))
 }
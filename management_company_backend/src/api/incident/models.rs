@@ -1,16 +1,24 @@
-use chrono::{DateTime, Utc};
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::prelude::Type;
+use sqlx::{prelude::Type, FromRow, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Serialize)]
+use crate::api::{
+    export::{Cell, ExportRow},
+    pagination::{ListFilter, PageInfo, SortField, SortOrder},
+};
+
+#[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IncidentType {
     pub id: Uuid,
     pub name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IncidentDetails {
     pub id: Uuid,
@@ -20,14 +28,150 @@ pub struct IncidentDetails {
     pub status: String,
     pub description: Option<String>,
     pub incident_type_name: String,
+    /// Версия записи; та же, что в `ETag`.
+    pub version: i32,
+}
+
+impl From<IncidentDetailsRow> for IncidentDetails {
+    fn from(incident: IncidentDetailsRow) -> Self {
+        Self {
+            id: incident.id,
+            reported_at: incident.reported_at.unwrap_or_default(),
+            resolved_at: incident.resolved_at,
+            status: incident.status.to_string(),
+            description: incident.description,
+            incident_type_name: incident.incident_type_name,
+            building_address: format!(
+                "{}, {}, {}, дом {}",
+                incident.address_region.unwrap_or_default(),
+                incident.address_city.unwrap_or_default(),
+                incident.address_street.unwrap_or_default(),
+                incident.building_number
+            ),
+            version: incident.version,
+        }
+    }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct IncidentList {
     pub incidents: Vec<IncidentDetails>,
+    #[serde(flatten)]
+    pub page_info: PageInfo,
+}
+
+#[derive(FromRow)]
+pub struct IncidentDetailsRow {
+    pub id: Uuid,
+    pub reported_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub status: IncidentStatus,
+    pub description: Option<String>,
+    pub incident_type_name: String,
+    pub building_number: i32,
+    pub address_region: Option<String>,
+    pub address_city: Option<String>,
+    pub address_street: Option<String>,
+    pub version: i32,
+}
+
+impl ExportRow for IncidentDetailsRow {
+    const SHEET_TITLE: &'static str = "Аварии";
+    const HEADERS: &'static [&'static str] = &[
+        "Дата регистрации",
+        "Дата устранения",
+        "Статус",
+        "Тип аварии",
+        "Адрес",
+        "Описание",
+    ];
+
+    fn cells(self) -> Vec<Cell> {
+        vec![
+            self.reported_at.map_or(Cell::Empty, Cell::DateTime),
+            self.resolved_at.map_or(Cell::Empty, Cell::DateTime),
+            Cell::text(self.status.to_string()),
+            Cell::Text(self.incident_type_name),
+            Cell::Text(format!(
+                "{}, {}, {}, дом {}",
+                self.address_region.unwrap_or_default(),
+                self.address_city.unwrap_or_default(),
+                self.address_street.unwrap_or_default(),
+                self.building_number
+            )),
+            self.description.map_or(Cell::Empty, Cell::Text),
+        ]
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct IncidentFilter {
+    pub status: Option<IncidentStatus>,
+    pub incident_type_id: Option<Uuid>,
+    pub building_id: Option<Uuid>,
+    pub reported_from: Option<NaiveDate>,
+    pub reported_to: Option<NaiveDate>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Type)]
+impl ListFilter for IncidentFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(status) = self.status {
+            builder.push(" AND i.status = ").push_bind(status);
+        }
+        if let Some(incident_type_id) = self.incident_type_id {
+            builder
+                .push(" AND i.incident_type_id = ")
+                .push_bind(incident_type_id);
+        }
+        if let Some(building_id) = self.building_id {
+            builder.push(" AND i.building_id = ").push_bind(building_id);
+        }
+        if let Some(reported_from) = self.reported_from {
+            builder
+                .push(" AND i.reported_at >= ")
+                .push_bind(reported_from)
+                .push("::date");
+        }
+        if let Some(reported_to) = self.reported_to {
+            builder
+                .push(" AND i.reported_at < ")
+                .push_bind(reported_to)
+                .push("::date + 1");
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum IncidentSortField {
+    #[default]
+    ReportedAt,
+    ResolvedAt,
+    Status,
+    IncidentType,
+}
+
+impl SortField for IncidentSortField {
+    fn expression(self) -> &'static str {
+        match self {
+            Self::ReportedAt => "COALESCE(i.reported_at, '-infinity')",
+            Self::ResolvedAt => "COALESCE(i.resolved_at, 'infinity')",
+            Self::Status => "i.status",
+            Self::IncidentType => "it.name",
+        }
+    }
+
+    fn default_order(self) -> SortOrder {
+        match self {
+            Self::ReportedAt | Self::ResolvedAt => SortOrder::Desc,
+            Self::Status | Self::IncidentType => SortOrder::Asc,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Type, ToSchema)]
 #[sqlx(type_name = "incident_status", rename_all = "camelCase")]
 pub enum IncidentStatus {
     Reported,
@@ -38,19 +182,19 @@ pub enum IncidentStatus {
     Cancelled,
 }
 
-impl IncidentStatus {
-    pub fn to_string(&self) -> String {
-        match self {
-            Self::Closed => "Закрыто".to_string(),
-            Self::Cancelled => "Отменено".to_string(),
-            Self::InProgress => "В процессе ремонта".to_string(),
-            Self::Reported => "Обработка заявки".to_string(),
-            Self::Resolved => "Исправлено".to_string(),
-        }
+impl fmt::Display for IncidentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Closed => "Закрыто",
+            Self::Cancelled => "Отменено",
+            Self::InProgress => "В процессе ремонта",
+            Self::Reported => "Обработка заявки",
+            Self::Resolved => "Исправлено",
+        })
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NewIncident {
     pub building_id: Uuid,
@@ -60,7 +204,7 @@ pub struct NewIncident {
     pub incident_type_id: Uuid,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Incident {
     pub id: Uuid,
@@ -70,9 +214,20 @@ pub struct Incident {
     pub status: IncidentStatus,
     pub description: Option<String>,
     pub incident_type_id: Uuid,
+    /// Версия записи; та же, что в `ETag`.
+    pub version: i32,
+}
+
+/// Изменение аварии диспетчером; поля, которых нет в запросе, не меняются.
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateIncident {
+    pub status: Option<IncidentStatus>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub description: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IncidentTypeList {
     pub incident_types: Vec<IncidentType>,
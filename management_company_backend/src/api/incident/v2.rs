@@ -0,0 +1,219 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::api::{
+    conditional::{IfMatch, Tagged},
+    envelope::{created, Envelope, ErrorEnvelope},
+    export::ExportParams,
+    extractor::AuthUser,
+    pagination::{ListQuery, PageParams, SortOrder},
+    ApiContext, Error,
+};
+
+use super::{
+    controllers,
+    models::{
+        Incident, IncidentDetails, IncidentFilter, IncidentSortField, IncidentType, NewIncident,
+        UpdateIncident,
+    },
+};
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route(
+            "/api/v2/incidents",
+            get(get_all_incidents).post(add_incident),
+        )
+        .route("/api/v2/incidents/export", get(export_incidents))
+        .route(
+            "/api/v2/incidents/:id",
+            get(get_incident)
+                .patch(update_incident)
+                .delete(delete_incident),
+        )
+        .route("/api/v2/incident_types", get(get_all_incident_types))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/incidents",
+    tag = "incidents",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<IncidentSortField>, Query, description = "Поле сортировки"),
+        IncidentFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка аварий", body = Envelope<Vec<IncidentDetails>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_all_incidents(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    query: ListQuery<IncidentFilter, IncidentSortField>,
+) -> Result<Json<Envelope<Vec<IncidentDetails>>>, Error> {
+    let Json(list) = controllers::get_all_incidents(user, ctx, query.paged()).await?;
+    Ok(Envelope::page(list.incidents, list.page_info))
+}
+
+/// Выгрузить все аварии с фильтрами и сортировкой списка в CSV или XLSX.
+#[utoipa::path(
+    get,
+    path = "/api/v2/incidents/export",
+    tag = "incidents",
+    security(("bearer" = [])),
+    params(
+        ExportParams,
+        ("sortBy" = Option<IncidentSortField>, Query, description = "Поле сортировки"),
+        ("sortOrder" = Option<SortOrder>, Query, description = "Порядок сортировки"),
+        IncidentFilter,
+    ),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn export_incidents(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<ExportParams>,
+    query: ListQuery<IncidentFilter, IncidentSortField>,
+) -> Result<Response, Error> {
+    controllers::export_incidents(user, ctx, params, query).await
+}
+
+/// Зарегистрировать аварию в доме.
+#[utoipa::path(
+    post,
+    path = "/api/v2/incidents",
+    tag = "incidents",
+    security(("bearer" = [])),
+    request_body = NewIncident,
+    responses(
+        (status = 201, description = "Авария зарегистрирована", body = Envelope<Incident>,
+            headers(("ETag" = String, description = "Версия аварии"))),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+    )
+)]
+pub async fn add_incident(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    payload: Json<NewIncident>,
+) -> Result<Response, Error> {
+    let Json(incident) = controllers::add_incident(user, ctx, payload).await?;
+    Ok(Tagged::new(incident.version, created(incident)).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/incidents/{id}",
+    tag = "incidents",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "Идентификатор аварии")),
+    responses(
+        (status = 200, description = "Авария", body = Envelope<IncidentDetails>,
+            headers(("ETag" = String, description = "Версия аварии"))),
+        (status = 304, description = "Авария не изменилась с версии из `If-None-Match`"),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Авария не найдена", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_incident(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    id: Path<Uuid>,
+) -> Result<Tagged<Json<Envelope<IncidentDetails>>>, Error> {
+    let tagged = controllers::get_incident(user, ctx, id).await?;
+    Ok(tagged.map(|Json(incident)| Envelope::new(incident)))
+}
+
+/// Изменить статус, дату устранения или описание аварии; поля, которых нет в запросе,
+/// не меняются.
+#[utoipa::path(
+    patch,
+    path = "/api/v2/incidents/{id}",
+    tag = "incidents",
+    security(("bearer" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Идентификатор аварии"),
+        ("If-Match" = String, Header, description = "`ETag` изменяемой версии"),
+    ),
+    request_body = UpdateIncident,
+    responses(
+        (status = 200, description = "Авария после изменения", body = Envelope<IncidentDetails>,
+            headers(("ETag" = String, description = "Новая версия аварии"))),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Авария не найдена", body = ErrorEnvelope),
+        (status = 412, description = "Аварию уже изменили", body = ErrorEnvelope),
+        (status = 428, description = "Нет заголовка `If-Match`", body = ErrorEnvelope),
+    )
+)]
+pub async fn update_incident(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    id: Path<Uuid>,
+    if_match: IfMatch,
+    payload: Json<UpdateIncident>,
+) -> Result<Tagged<Json<Envelope<IncidentDetails>>>, Error> {
+    let tagged = controllers::update_incident(user, ctx, id, if_match, payload).await?;
+    Ok(tagged.map(|Json(incident)| Envelope::new(incident)))
+}
+
+/// Удалить аварию в корзину вместе с её ремонтами.
+#[utoipa::path(
+    delete,
+    path = "/api/v2/incidents/{id}",
+    tag = "incidents",
+    security(("bearer" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Идентификатор аварии"),
+        ("If-Match" = String, Header, description = "`ETag` удаляемой версии"),
+    ),
+    responses(
+        (status = 204, description = "Авария удалена"),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Авария не найдена", body = ErrorEnvelope),
+        (status = 412, description = "Аварию уже изменили", body = ErrorEnvelope),
+        (status = 428, description = "Нет заголовка `If-Match`", body = ErrorEnvelope),
+    )
+)]
+pub async fn delete_incident(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    id: Path<Uuid>,
+    if_match: IfMatch,
+) -> Result<StatusCode, Error> {
+    controllers::delete_incident(user, ctx, id, if_match).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/incident_types",
+    tag = "incidents",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Все типы аварий", body = Envelope<Vec<IncidentType>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_all_incident_types(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+) -> Result<Json<Envelope<Vec<IncidentType>>>, Error> {
+    let Json(list) = controllers::get_all_incident_types(user, ctx).await?;
+    Ok(Envelope::new(list.incident_types))
+}
@@ -0,0 +1,360 @@
+use std::{future::Future, time::Instant};
+
+use anyhow::Context;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{
+        header::{AUTHORIZATION, CONTENT_TYPE},
+        HeaderMap,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::api::{ApiContext, Error};
+
+/// Метка маршрута для запросов, не попавших ни в один маршрут: путь как есть раздул бы число рядов.
+const UNMATCHED_ROUTE: &str = "<unmatched>";
+
+/// Границы корзин гистограмм в секундах: от быстрых запросов списков до тяжёлой статистики.
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Метрики сервера в формате Prometheus.
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    statistics_query_duration: HistogramVec,
+    db_pool_connections: IntGaugeVec,
+    active_incidents: IntGauge,
+    open_repairs: IntGauge,
+    overdue_incidents: IntGauge,
+}
+
+impl Metrics {
+    pub fn new(max_connections: u32) -> anyhow::Result<Self> {
+        let registry = Registry::new_custom(Some("management_company".to_string()), None)?;
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "Обработанные HTTP-запросы"),
+            &["method", "route", "status"],
+        )?;
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Время обработки HTTP-запроса",
+            )
+            .buckets(DURATION_BUCKETS.to_vec()),
+            &["method", "route", "status"],
+        )?;
+        let statistics_query_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "statistics_query_duration_seconds",
+                "Время расчёта статистики без учёта ответов из кэша",
+            )
+            .buckets(DURATION_BUCKETS.to_vec()),
+            &["query"],
+        )?;
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Соединения в пуле базы данных"),
+            &["state"],
+        )?;
+        let db_pool_max_connections = IntGauge::new(
+            "db_pool_max_connections",
+            "Наибольшее число соединений в пуле",
+        )?;
+        let active_incidents = IntGauge::new(
+            "active_incidents",
+            "Аварии в статусах «зарегистрирована» и «в работе»",
+        )?;
+        let open_repairs = IntGauge::new("open_repairs", "Ремонты без даты окончания")?;
+        let overdue_incidents = IntGauge::new(
+            "overdue_incidents",
+            "Активные аварии, не устранённые в срок по SLA",
+        )?;
+
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_request_duration.clone()))?;
+        registry.register(Box::new(statistics_query_duration.clone()))?;
+        registry.register(Box::new(db_pool_connections.clone()))?;
+        registry.register(Box::new(db_pool_max_connections.clone()))?;
+        registry.register(Box::new(active_incidents.clone()))?;
+        registry.register(Box::new(open_repairs.clone()))?;
+        registry.register(Box::new(overdue_incidents.clone()))?;
+        db_pool_max_connections.set(i64::from(max_connections));
+
+        Ok(Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            statistics_query_duration,
+            db_pool_connections,
+            active_incidents,
+            open_repairs,
+            overdue_incidents,
+        })
+    }
+
+    /// Выполнить расчёт статистики `query`, записав его длительность.
+    pub async fn time_statistics<T>(&self, query: &'static str, f: impl Future<Output = T>) -> T {
+        let timer = self
+            .statistics_query_duration
+            .with_label_values(&[query])
+            .start_timer();
+        let output = f.await;
+        timer.observe_duration();
+        output
+    }
+
+    fn observe_request(&self, method: &str, route: &str, status: &str, seconds: f64) {
+        let labels = [method, route, status];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_request_duration
+            .with_label_values(&labels)
+            .observe(seconds);
+    }
+
+    /// Обновить показатели пула и бизнес-показатели; они считаются в момент опроса.
+    async fn refresh(&self, pool: &PgPool, sla_hours: u32) -> Result<(), Error> {
+        let idle = pool.num_idle() as i64;
+        self.db_pool_connections
+            .with_label_values(&["idle"])
+            .set(idle);
+        self.db_pool_connections
+            .with_label_values(&["in_use"])
+            .set(i64::from(pool.size()) - idle);
+
+        let counts = sqlx::query!(
+            r#"
+            SELECT
+                (
+                    SELECT COUNT(*)
+                    FROM incident
+                    WHERE deleted_at IS NULL AND status IN ('reported', 'in_progress')
+                ) AS "active_incidents!",
+                (
+                    SELECT COUNT(*)
+                    FROM repair
+                    WHERE deleted_at IS NULL AND ended_at IS NULL
+                ) AS "open_repairs!",
+                (
+                    SELECT COUNT(*)
+                    FROM incident
+                    WHERE
+                        deleted_at IS NULL
+                        AND status IN ('reported', 'in_progress')
+                        AND reported_at < NOW() - make_interval(hours => $1)
+                ) AS "overdue_incidents!"
+            "#,
+            sla_hours as i32
+        )
+        .fetch_one(pool)
+        .await?;
+
+        self.active_incidents.set(counts.active_incidents);
+        self.open_repairs.set(counts.open_repairs);
+        self.overdue_incidents.set(counts.overdue_incidents);
+        Ok(())
+    }
+
+    fn render(&self) -> Result<Vec<u8>, Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .context("failed to encode metrics")?;
+        Ok(buffer)
+    }
+}
+
+/// Считать запросы и их длительность по шаблону маршрута, а не по фактическому пути.
+pub async fn track_requests(
+    State(metrics): State<std::sync::Arc<Metrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let started_at = Instant::now();
+
+    let response = next.run(request).await;
+
+    metrics.observe_request(
+        &method,
+        &route,
+        response.status().as_str(),
+        started_at.elapsed().as_secs_f64(),
+    );
+    response
+}
+
+/// Метрики в текстовом формате Prometheus; бизнес-показатели считаются в момент запроса.
+///
+/// Доступны только с токеном из `--metrics-token`; пока он не задан, не отдаются никому.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "metrics",
+    params(
+        ("Authorization" = String, Header, description = "`Bearer` и токен из `--metrics-token`"),
+    ),
+    responses(
+        (status = 200, description = "Метрики", body = String, content_type = "text/plain; version=0.0.4"),
+        (status = 401, description = "Нет токена или токен неверный"),
+        (status = 403, description = "`--metrics-token` не задан"),
+    )
+)]
+pub async fn get_metrics(
+    State(ctx): State<ApiContext>,
+    headers: HeaderMap,
+) -> Result<Response, Error> {
+    let expected = ctx
+        .config
+        .metrics_token
+        .as_deref()
+        .ok_or(Error::Forbidden)?;
+    let presented = headers
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .ok_or(Error::Unauthorized)?;
+    // Сравниваются хэши, чтобы время сравнения не подсказывало, сколько символов угадано.
+    if Sha256::digest(presented) != Sha256::digest(expected) {
+        return Err(Error::Unauthorized);
+    }
+
+    ctx.metrics
+        .refresh(&ctx.db, ctx.config.incident_resolution_sla_hours)
+        .await?;
+    Ok((
+        [(CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        ctx.metrics.render()?,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        middleware,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        api::{api_router, test_context},
+        config::Config,
+    };
+
+    fn rendered(metrics: &Metrics) -> String {
+        String::from_utf8(metrics.render().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn requests_are_counted_by_route_template() {
+        let metrics = Arc::new(Metrics::new(10).unwrap());
+        let app = Router::new()
+            .route("/api/buildings/:id", get(|| async { "{}" }))
+            .layer(middleware::from_fn_with_state(
+                metrics.clone(),
+                track_requests,
+            ));
+
+        for uri in ["/api/buildings/1", "/api/buildings/2", "/missing"] {
+            app.clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+        }
+
+        let output = rendered(&metrics);
+        assert!(output.contains(
+            r#"management_company_http_requests_total{method="GET",route="/api/buildings/:id",status="200"} 2"#
+        ));
+        assert!(output.contains(
+            r#"management_company_http_requests_total{method="GET",route="<unmatched>",status="404"} 1"#
+        ));
+        assert!(output.contains("management_company_http_request_duration_seconds_bucket"));
+    }
+
+    #[tokio::test]
+    async fn statistics_durations_are_recorded() {
+        let metrics = Metrics::new(10).unwrap();
+
+        let value = metrics
+            .time_statistics("summary", async { StatusCode::OK })
+            .await;
+
+        assert_eq!(value, StatusCode::OK);
+        assert!(rendered(&metrics).contains(
+            r#"management_company_statistics_query_duration_seconds_count{query="summary"} 1"#
+        ));
+    }
+
+    #[sqlx::test]
+    async fn metrics_require_the_configured_token(pool: PgPool) {
+        use clap::Parser;
+
+        let scrape = |app: Router, token: Option<&'static str>| async move {
+            let mut request = Request::get("/metrics");
+            if let Some(token) = token {
+                request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+            }
+            app.oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap()
+                .status()
+        };
+
+        let mut ctx = test_context(pool);
+        let disabled = api_router(ctx.clone());
+        assert_eq!(scrape(disabled, Some("")).await, StatusCode::FORBIDDEN);
+
+        ctx.config = Arc::new(Config::parse_from([
+            "management_company_backend",
+            "--database-url",
+            "postgres://localhost/mc",
+            "--hmac-key",
+            "0123456789abcdef0123456789abcdef",
+            "--metrics-token",
+            "scrape-token",
+        ]));
+        let app = api_router(ctx);
+        assert_eq!(scrape(app.clone(), None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            scrape(app.clone(), Some("scrape")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(scrape(app, Some("scrape-token")).await, StatusCode::OK);
+    }
+
+    #[sqlx::test(fixtures("statistics/fixtures/statistics.sql"))]
+    async fn business_gauges_are_refreshed(pool: PgPool) {
+        let metrics = Metrics::new(10).unwrap();
+
+        metrics.refresh(&pool, 72).await.unwrap();
+
+        let output = rendered(&metrics);
+        assert!(output.contains("management_company_active_incidents 2"));
+        assert!(output.contains("management_company_open_repairs 1"));
+        assert!(output.contains("management_company_overdue_incidents 2"));
+        assert!(output.contains("management_company_db_pool_max_connections 10"));
+    }
+}
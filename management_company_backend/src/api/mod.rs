@@ -1,86 +1,456 @@
-use std::{
-    net::{Ipv4Addr, SocketAddr},
-    sync::Arc,
-    time::Duration,
-};
+use std::{future::IntoFuture, sync::Arc, time::Duration};
 
 use anyhow::Context;
-use axum::{http::header::AUTHORIZATION, Router};
+use axum::{
+    http::{
+        header::{
+            ACCEPT, AUTHORIZATION, CONTENT_DISPOSITION, CONTENT_TYPE, ETAG, IF_MATCH,
+            IF_NONE_MATCH, LINK,
+        },
+        HeaderName, HeaderValue, Method,
+    },
+    middleware, Router,
+};
 use sqlx::PgPool;
-use tokio::net::TcpListener;
+use tokio::{net::TcpListener, signal, sync::oneshot};
 use tower_http::{
     catch_panic::CatchPanicLayer,
     compression::CompressionLayer,
-    cors::{Any, CorsLayer},
+    cors::{AllowOrigin, CorsLayer},
     sensitive_headers::SetSensitiveHeadersLayer,
     timeout::TimeoutLayer,
-    trace::{DefaultMakeSpan, DefaultOnFailure, DefaultOnRequest, DefaultOnResponse, TraceLayer},
+    trace::{DefaultOnFailure, DefaultOnRequest, DefaultOnResponse, TraceLayer},
 };
 
 pub use error::Error;
 
+mod audit;
 mod building;
+mod conditional;
+mod deprecation;
 mod employee;
+mod envelope;
 mod error;
+mod events;
+mod export;
 mod extractor;
 mod financial_operation;
+mod health;
 mod incident;
+mod metrics;
+mod money;
+mod notification;
+mod openapi;
+mod pagination;
 mod repair;
+mod request_id;
+mod search;
 mod statistics;
+mod trash;
 mod user;
+mod webhook;
 
 use crate::config::Config;
+use deprecation::{V1Deprecation, DEPRECATION, SUNSET};
+use events::{feed::EventFeed, outbox::OutboxWorker};
+use metrics::Metrics;
+use notification::{mailer::Mailer, worker::NotificationWorker};
+use request_id::REQUEST_ID;
+use statistics::cache::StatisticsCache;
+use webhook::delivery::WebhookWorker;
 
 #[derive(Clone)]
 pub(crate) struct ApiContext {
     config: Arc<Config>,
     db: PgPool,
+    statistics_cache: Arc<StatisticsCache>,
+    metrics: Arc<Metrics>,
+    events: Arc<EventFeed>,
+}
+
+/// Контекст с настройками по умолчанию для тестов, которые собирают весь роутер.
+#[cfg(test)]
+pub(crate) fn test_context(db: PgPool) -> ApiContext {
+    use clap::Parser;
+
+    let config = Config::parse_from([
+        "management_company_backend",
+        "--database-url",
+        "postgres://localhost/mc",
+        "--hmac-key",
+        "0123456789abcdef0123456789abcdef",
+    ]);
+    ApiContext {
+        statistics_cache: Arc::new(StatisticsCache::new(config.statistics_cache_ttl())),
+        metrics: Arc::new(Metrics::new(config.database_max_connections).unwrap()),
+        config: Arc::new(config),
+        db,
+        events: Arc::new(EventFeed::new()),
+    }
 }
 
 pub async fn serve(config: Config, db: PgPool) -> anyhow::Result<()> {
+    let statistics_cache = StatisticsCache::new(config.statistics_cache_ttl());
+    let metrics = Metrics::new(config.database_max_connections)?;
+    let addr = config.bind_address;
+    let shutdown_timeout = config.shutdown_timeout();
+    let events = Arc::new(EventFeed::new());
+    events
+        .listen(&db)
+        .await
+        .context("could not subscribe to incident events")?;
+    // Триггер ставит уведомления в очередь и при `--mail-transport none`; устаревшие
+    // снимаются при каждом запуске, а рассыльщик писем снимает их и дальше.
+    let expired = notification::worker::expire_stale(&db, config.notification_max_age_hours)
+        .await
+        .context("could not expire stale notifications")?;
+    if expired > 0 {
+        tracing::warn!(
+            expired,
+            "skipped notifications older than {} hours",
+            config.notification_max_age_hours
+        );
+    }
+    let mut workers = vec![WebhookWorker::new(db.clone(), &config)?.spawn(&events)];
+    if let Some(mailer) = Mailer::from_config(&config)? {
+        workers.push(NotificationWorker::new(db.clone(), &config, mailer).spawn(&events));
+    }
     let api_context = ApiContext {
         config: Arc::new(config),
-        db,
+        db: db.clone(),
+        statistics_cache: Arc::new(statistics_cache),
+        metrics: Arc::new(metrics),
+        events: events.clone(),
     };
 
     let app = api_router(api_context);
 
-    let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, 8080));
-    let listener = TcpListener::bind(addr).await?;
-    axum::serve(listener, app)
+    let listener = TcpListener::bind(addr)
         .await
-        .context("error running HTTP server")
+        .with_context(|| format!("could not bind to {addr}"))?;
+    tracing::info!("listening on {addr}");
+
+    let (signalled, signal_received) = oneshot::channel();
+    let feed = events.clone();
+    let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+        shutdown_signal().await;
+        // Потоки SSE бесконечны: без этого сервер ждал бы их до `shutdown_timeout`.
+        feed.close();
+        let _ = signalled.send(());
+    });
+    let drain_deadline = async {
+        if signal_received.await.is_ok() {
+            tokio::time::sleep(shutdown_timeout).await;
+        } else {
+            std::future::pending::<()>().await;
+        }
+    };
+
+    tokio::select! {
+        result = server.into_future() => result.context("error running HTTP server")?,
+        _ = drain_deadline => tracing::warn!(
+            "in-flight requests did not finish within {shutdown_timeout:?}, shutting down anyway"
+        ),
+    }
+
+    // Рассыльщики доделывают начатую отправку и выходят, как только лента закрыта; без этого
+    // `db.close()` оборвал бы запись результата отправки.
+    events.close();
+    for worker in workers {
+        if let Err(e) = worker.await {
+            tracing::error!(error = ?e, "background worker failed");
+        }
+    }
+
+    db.close().await;
+    tracing::info!("server stopped");
+    Ok(())
+}
+
+/// Дождаться SIGINT (Ctrl+C) или SIGTERM; после него сервер перестаёт принимать соединения
+/// и дожидается уже начатых запросов, включая потоковые выгрузки.
+async fn shutdown_signal() {
+    let interrupt = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install SIGINT handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => {},
+        _ = terminate => {},
+    }
+    tracing::info!("shutdown signal received, draining in-flight requests");
 }
 
+/// Методы и заголовки, которые фронтенд отправляет в кросс-доменных запросах.
+const CORS_ALLOWED_METHODS: [Method; 5] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+];
+fn cors_allowed_headers() -> [HeaderName; 6] {
+    [
+        ACCEPT,
+        AUTHORIZATION,
+        CONTENT_TYPE,
+        IF_MATCH,
+        IF_NONE_MATCH,
+        REQUEST_ID.clone(),
+    ]
+}
+/// Имя файла выгрузки фронтенд берёт из `Content-Disposition`, версию ресурса — из `ETag`,
+/// о закрытии v1 узнаёт из `Deprecation`, `Sunset` и `Link`, а `X-Request-Id` показывает
+/// в сообщении об ошибке.
+fn cors_exposed_headers() -> [HeaderName; 6] {
+    [
+        CONTENT_DISPOSITION,
+        ETAG,
+        DEPRECATION.clone(),
+        SUNSET.clone(),
+        LINK,
+        REQUEST_ID.clone(),
+    ]
+}
+/// Сколько браузер может не повторять предварительный запрос.
+const CORS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 fn api_router(api_context: ApiContext) -> Router {
-    Router::new()
+    let request_timeout = api_context.config.request_timeout();
+    let cors = cors_layer(&api_context.config.cors_allowed_origins);
+    let metrics = api_context.metrics.clone();
+    let v1_deprecation = V1Deprecation::new(
+        api_context.config.api_v1_deprecated_at,
+        api_context.config.api_v1_sunset,
+    );
+
+    // Маршруты без версии в пути — v1; они работают до даты `Sunset`.
+    let v1 = Router::new()
         .merge(user::router())
         .merge(employee::router())
         .merge(building::router())
         .merge(incident::router())
         .merge(repair::router())
+        .merge(financial_operation::router())
         .merge(statistics::router())
-        .route("/health", axum::routing::get(|| async { "healthy" }))
+        .merge(search::router())
+        .layer(middleware::from_fn_with_state(
+            v1_deprecation,
+            deprecation::deprecate_v1,
+        ));
+
+    let v2 = Router::new()
+        .merge(user::v2::router())
+        .merge(employee::v2::router())
+        .merge(building::v2::router())
+        .merge(incident::v2::router())
+        .merge(repair::v2::router())
+        .merge(financial_operation::v2::router())
+        .merge(statistics::v2::router())
+        .merge(search::v2::router())
+        .merge(audit::router())
+        .merge(trash::router())
+        .merge(events::router())
+        .merge(webhook::router())
+        .layer(middleware::map_response(envelope::json_errors));
+
+    Router::new()
+        .merge(v1)
+        .merge(v2)
+        .merge(health::router())
+        .merge(openapi::router())
+        .route("/metrics", axum::routing::get(metrics::get_metrics))
+        .layer(middleware::from_fn(conditional::not_modified))
+        .layer(middleware::from_fn_with_state(
+            metrics,
+            metrics::track_requests,
+        ))
         .layer((
             SetSensitiveHeadersLayer::new([AUTHORIZATION]),
             CompressionLayer::new(),
-            TimeoutLayer::new(Duration::from_secs(30)),
+            TimeoutLayer::new(request_timeout),
             CatchPanicLayer::new(),
         ))
         .layer(
             TraceLayer::new_for_http()
-                .make_span_with(DefaultMakeSpan::new().include_headers(true))
+                .make_span_with(request_id::make_span)
                 .on_request(DefaultOnRequest::new().level(tracing::Level::INFO))
                 .on_response(DefaultOnResponse::new().level(tracing::Level::INFO))
                 .on_failure(DefaultOnFailure::new().level(tracing::Level::ERROR)),
         )
-        .layer(
-            CorsLayer::new()
-                .allow_origin(Any)
-                .allow_methods(Any)
-                .allow_headers(Any),
-        )
+        .layer(request_id::layers())
+        .layer(cors)
         .with_state(api_context)
 }
+
+/// Кросс-доменные запросы разрешены только с перечисленных источников и с учётными данными.
+fn cors_layer(allowed_origins: &[HeaderValue]) -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(allowed_origins.iter().cloned()))
+        .allow_methods(CORS_ALLOWED_METHODS)
+        .allow_headers(cors_allowed_headers())
+        .expose_headers(cors_exposed_headers())
+        .allow_credentials(true)
+        .max_age(CORS_MAX_AGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{
+            header::{
+                ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+                ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+                ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_REQUEST_HEADERS,
+                ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+            },
+            Request, Response, StatusCode,
+        },
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const FRONTEND: &str = "https://uk.example.com";
+
+    async fn send(request: Request<Body>) -> Response<Body> {
+        Router::new()
+            .route("/api/buildings", get(|| async { "[]" }))
+            .layer(cors_layer(&[HeaderValue::from_static(FRONTEND)]))
+            .oneshot(request)
+            .await
+            .unwrap()
+    }
+
+    fn preflight(origin: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/buildings")
+            .header(ORIGIN, origin)
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "DELETE")
+            .header(ACCESS_CONTROL_REQUEST_HEADERS, "authorization,content-type")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn header<'a>(response: &'a Response<Body>, name: &HeaderName) -> Option<&'a str> {
+        response
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap())
+    }
+
+    #[tokio::test]
+    async fn preflight_from_allowed_origin_is_accepted() {
+        let response = send(preflight(FRONTEND)).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            header(&response, &ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(FRONTEND)
+        );
+        assert_eq!(
+            header(&response, &ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
+        assert_eq!(
+            header(&response, &ACCESS_CONTROL_ALLOW_METHODS),
+            Some("GET,POST,PUT,PATCH,DELETE")
+        );
+        assert_eq!(
+            header(&response, &ACCESS_CONTROL_ALLOW_HEADERS),
+            Some("accept,authorization,content-type,if-match,if-none-match,x-request-id")
+        );
+    }
+
+    #[tokio::test]
+    async fn preflight_from_other_origin_is_not_allowed() {
+        let response = send(preflight("https://evil.example.com")).await;
+
+        // Без `Access-Control-Allow-Origin` браузер не отправит сам запрос.
+        assert_eq!(header(&response, &ACCESS_CONTROL_ALLOW_ORIGIN), None);
+    }
+
+    #[sqlx::test]
+    async fn v1_is_deprecated_and_v2_wraps_errors(pool: PgPool) {
+        let app = api_router(test_context(pool));
+
+        let v1 = app
+            .clone()
+            .oneshot(Request::get("/api/buildings").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(v1.status(), StatusCode::OK);
+        assert!(v1.headers().contains_key(&DEPRECATION));
+        assert!(v1.headers().contains_key(&SUNSET));
+
+        let v2 = app
+            .clone()
+            .oneshot(
+                Request::get("/api/v2/buildings")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(v2.status(), StatusCode::OK);
+        assert!(!v2.headers().contains_key(&DEPRECATION));
+        let body = axum::body::to_bytes(v2.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list["data"], serde_json::json!([]));
+        assert_eq!(list["meta"]["total"], 0);
+
+        let unauthorized = app
+            .oneshot(
+                Request::get("/api/v2/users/me")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+        let body = axum::body::to_bytes(unauthorized.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error"]["status"], 401);
+    }
+
+    #[tokio::test]
+    async fn actual_request_exposes_download_headers() {
+        let request = Request::builder()
+            .uri("/api/buildings")
+            .header(ORIGIN, FRONTEND)
+            .body(Body::empty())
+            .unwrap();
+
+        let response = send(request).await;
+
+        assert_eq!(
+            header(&response, &ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(FRONTEND)
+        );
+        assert_eq!(
+            header(&response, &ACCESS_CONTROL_EXPOSE_HEADERS),
+            Some("content-disposition,etag,deprecation,sunset,link,x-request-id")
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use std::{fmt, str::FromStr};
+
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use utoipa::{
+    openapi::{schema::SchemaType, ObjectBuilder, RefOr, Schema, SchemaFormat, Type},
+    PartialSchema, ToSchema,
+};
+
+/// Денежная сумма, хранимая в базе как `numeric(14, 2)`.
+///
+/// Сериализуется точной десятичной строкой с двумя знаками после запятой, например `"1500.50"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct Money(pub Decimal);
+
+/// Сколько знаков после запятой хранит столбец `numeric(14, 2)`.
+const SCALE: u32 = 2;
+
+impl Money {
+    pub fn to_f64(self) -> f64 {
+        self.0.to_f64().unwrap_or_default()
+    }
+
+    /// Сумма из клиента: больше двух значащих знаков после запятой база бы молча округлила.
+    fn exact<E: de::Error>(amount: Decimal) -> Result<Self, E> {
+        if amount.normalize().scale() > SCALE {
+            return Err(E::custom(format!(
+                "amount {amount} has more than {SCALE} decimal places"
+            )));
+        }
+        Ok(Self(amount))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}", self.0)
+    }
+}
+
+impl FromStr for Money {
+    type Err = rust_decimal::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Decimal::from_str_exact(s).map(Self)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MoneyVisitor;
+
+        impl de::Visitor<'_> for MoneyVisitor {
+            type Value = Money;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a decimal amount as a string or a number")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Money, E> {
+                Money::exact(Decimal::from_str_exact(v).map_err(E::custom)?)
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Money, E> {
+                Ok(Money(v.into()))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Money, E> {
+                Ok(Money(v.into()))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Money, E> {
+                Money::exact(Decimal::try_from(v).map_err(E::custom)?)
+            }
+        }
+
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+/// В спецификации сумма — строка, а не число: клиент не должен терять копейки на `f64`.
+impl PartialSchema for Money {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(SchemaType::Type(Type::String))
+            .format(Some(SchemaFormat::Custom("decimal".to_string())))
+            .description(Some("Денежная сумма с двумя знаками после запятой"))
+            .examples([serde_json::json!("1500.50")])
+            .into()
+    }
+}
+
+impl ToSchema for Money {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialises_as_exact_decimal_string() {
+        let amount: Money = "1500.5".parse().unwrap();
+
+        assert_eq!(serde_json::to_string(&amount).unwrap(), r#""1500.50""#);
+    }
+
+    #[test]
+    fn deserialises_strings_and_numbers() {
+        let from_string: Money = serde_json::from_str(r#""0.10""#).unwrap();
+        let from_number: Money = serde_json::from_str("0.1").unwrap();
+
+        assert_eq!(from_string, from_number);
+        assert_eq!(from_string.to_string(), "0.10");
+    }
+
+    #[test]
+    fn rejects_fractions_of_a_kopeck() {
+        for json in [r#""0.105""#, "0.105", r#""1e-3""#] {
+            assert!(serde_json::from_str::<Money>(json).is_err(), "{json}");
+        }
+
+        let trailing_zeros: Money = serde_json::from_str(r#""1.500""#).unwrap();
+        assert_eq!(trailing_zeros.to_string(), "1.50");
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Context;
+use lettre::{AsyncFileTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::{Config, MailTransport};
+
+/// Способ отправки писем, выбранный в `--mail-transport`.
+pub enum Mailer {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    /// Каждое письмо сохраняется файлом `.eml` в каталог.
+    File(AsyncFileTransport<Tokio1Executor>),
+}
+
+impl Mailer {
+    /// `None`, если уведомления отключены.
+    pub fn from_config(config: &Config) -> anyhow::Result<Option<Self>> {
+        match config.mail_transport {
+            MailTransport::None => Ok(None),
+            MailTransport::Smtp => {
+                let url = config
+                    .smtp_url
+                    .as_deref()
+                    .context("--smtp-url is required for the smtp mail transport")?;
+                let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(url)
+                    .context("invalid --smtp-url")?
+                    .timeout(Some(config.mail_timeout()))
+                    .build();
+                Ok(Some(Self::Smtp(transport)))
+            }
+            MailTransport::File => {
+                let dir = config
+                    .mail_drop_dir
+                    .as_deref()
+                    .context("--mail-drop-dir is required for the file mail transport")?;
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("could not create {}", dir.display()))?;
+                Ok(Some(Self::File(AsyncFileTransport::new(dir))))
+            }
+        }
+    }
+
+    pub async fn send(&self, message: Message) -> anyhow::Result<()> {
+        match self {
+            Self::Smtp(transport) => {
+                transport.send(message).await?;
+            }
+            Self::File(transport) => {
+                transport.send(message).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+//! Письма членам комитетов о новых авариях, смене их статуса и нарушении срока устранения.
+
+pub mod mailer;
+pub mod templates;
+pub mod worker;
@@ -0,0 +1,138 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use uuid::Uuid;
+
+use crate::api::incident::models::IncidentStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Type)]
+#[sqlx(type_name = "notification_kind", rename_all = "snake_case")]
+pub enum NotificationKind {
+    IncidentCreated,
+    IncidentStatusChanged,
+    SlaBreached,
+}
+
+/// Всё, что нужно для письма об аварии.
+pub struct IncidentNotice {
+    pub kind: NotificationKind,
+    pub incident_id: Uuid,
+    pub incident_type: Option<String>,
+    pub description: Option<String>,
+    pub address: Option<String>,
+    pub reported_at: Option<DateTime<Utc>>,
+    pub status: IncidentStatus,
+    pub previous_status: Option<IncidentStatus>,
+}
+
+/// Тема и текст письма.
+pub struct Email {
+    pub subject: String,
+    pub body: String,
+}
+
+impl IncidentNotice {
+    pub fn render(&self, sla_hours: u32, timezone: Tz) -> Email {
+        let incident_type = self.incident_type.as_deref().unwrap_or("без типа");
+        let address = self.address.as_deref().unwrap_or("не указан");
+
+        let (subject, summary) = match self.kind {
+            NotificationKind::IncidentCreated => (
+                format!("Новая авария: {incident_type}, {address}"),
+                format!("Зарегистрирована авария «{incident_type}»."),
+            ),
+            NotificationKind::IncidentStatusChanged => (
+                format!("Авария «{incident_type}»: {}", self.status),
+                match self.previous_status {
+                    Some(previous) => format!(
+                        "Статус аварии «{incident_type}» изменён: {previous} → {}.",
+                        self.status
+                    ),
+                    None => format!(
+                        "Статус аварии «{incident_type}» изменён на «{}».",
+                        self.status
+                    ),
+                },
+            ),
+            NotificationKind::SlaBreached => (
+                format!("Просрочено устранение аварии: {incident_type}, {address}"),
+                format!(
+                    "Авария «{incident_type}» не устранена за {sla_hours} ч с момента регистрации."
+                ),
+            ),
+        };
+
+        let mut body = format!("Здравствуйте!\n\n{summary}\n\n");
+        // Запись в String не может завершиться ошибкой.
+        let _ = writeln!(body, "Адрес: {address}");
+        if let Some(description) = &self.description {
+            let _ = writeln!(body, "Описание: {description}");
+        }
+        if let Some(reported_at) = self.reported_at {
+            let _ = writeln!(
+                body,
+                "Зарегистрирована: {}",
+                reported_at
+                    .with_timezone(&timezone)
+                    .format("%d.%m.%Y %H:%M")
+            );
+        }
+        let _ = writeln!(body, "Статус: {}", self.status);
+        let _ = writeln!(body, "Номер аварии: {}", self.incident_id);
+        body.push_str(
+            "\nВы получили это письмо как член комитета, обслуживающего дом. \
+             Отвечать на него не нужно.\n",
+        );
+
+        Email { subject, body }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn notice(kind: NotificationKind) -> IncidentNotice {
+        IncidentNotice {
+            kind,
+            incident_id: Uuid::from_u128(0x202),
+            incident_type: Some("Протечка".to_string()),
+            description: Some("Течёт крыша".to_string()),
+            address: Some("Московская область, Москва, улица Ленина, дом 5".to_string()),
+            reported_at: Some(Utc.with_ymd_and_hms(2024, 1, 15, 10, 0, 0).unwrap()),
+            status: IncidentStatus::InProgress,
+            previous_status: Some(IncidentStatus::Reported),
+        }
+    }
+
+    #[test]
+    fn messages_describe_the_incident_in_local_time() {
+        let email = notice(NotificationKind::IncidentCreated).render(72, chrono_tz::Europe::Moscow);
+
+        assert_eq!(
+            email.subject,
+            "Новая авария: Протечка, Московская область, Москва, улица Ленина, дом 5"
+        );
+        assert!(email.body.contains("Описание: Течёт крыша\n"));
+        assert!(email.body.contains("Зарегистрирована: 15.01.2024 13:00\n"));
+        assert!(email.body.contains("Статус: В процессе ремонта\n"));
+    }
+
+    #[test]
+    fn each_kind_has_its_own_message() {
+        let changed = notice(NotificationKind::IncidentStatusChanged).render(72, Tz::UTC);
+        assert_eq!(changed.subject, "Авария «Протечка»: В процессе ремонта");
+        assert!(changed
+            .body
+            .contains("изменён: Обработка заявки → В процессе ремонта."));
+
+        let breached = notice(NotificationKind::SlaBreached).render(72, Tz::UTC);
+        assert!(breached.subject.starts_with("Просрочено устранение аварии"));
+        assert!(breached.body.contains("не устранена за 72 ч"));
+    }
+}
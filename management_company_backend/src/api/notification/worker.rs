@@ -0,0 +1,463 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use chrono_tz::Tz;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    Message,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        events::outbox::{retry_delay, OutboxWorker},
+        incident::models::IncidentStatus,
+    },
+    config::Config,
+};
+
+use super::{
+    mailer::Mailer,
+    templates::{IncidentNotice, NotificationKind},
+};
+
+/// Рассылает членам комитета, обслуживающего дом, письма из `notification_outbox`.
+///
+/// Уведомления берутся из очереди пачками в аренду, как у `WebhookWorker`: письма
+/// отправляются без открытой транзакции, а результаты записываются отдельной короткой.
+pub struct NotificationWorker {
+    db: PgPool,
+    mailer: Mailer,
+    from: Mailbox,
+    poll_interval: Duration,
+    batch_size: i64,
+    lease: Duration,
+    max_attempts: i32,
+    max_age_hours: u32,
+    sla_hours: u32,
+    sla_breach_lookback_hours: u32,
+    timezone: Tz,
+}
+
+/// Уведомление, которое пора отправить, вместе с данными аварии и адресатами.
+struct Pending {
+    id: Uuid,
+    attempts: i32,
+    recipients: Vec<String>,
+    notice: IncidentNotice,
+}
+
+impl NotificationWorker {
+    pub fn new(db: PgPool, config: &Config, mailer: Mailer) -> Self {
+        Self {
+            db,
+            mailer,
+            from: config.mail_from.clone(),
+            poll_interval: config.notification_poll_interval(),
+            batch_size: i64::from(config.notification_batch_size),
+            lease: config.notification_lease(),
+            max_attempts: config.notification_max_attempts,
+            max_age_hours: config.notification_max_age_hours,
+            sla_hours: config.incident_resolution_sla_hours,
+            sla_breach_lookback_hours: config.sla_breach_lookback_hours,
+            timezone: config.timezone,
+        }
+    }
+
+    /// Поставить в очередь уведомления об авариях, которые только что вышли за срок устранения.
+    pub async fn queue_sla_breaches(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO notification_outbox (kind, incident_id, status)
+            SELECT 'sla_breached', id, status
+            FROM incident
+            WHERE
+                deleted_at IS NULL
+                AND status IN ('reported', 'in_progress')
+                AND reported_at < NOW() - make_interval(hours => $1)
+                AND reported_at >= NOW() - make_interval(hours => $1 + $2)
+            ON CONFLICT (incident_id) WHERE kind = 'sla_breached' DO NOTHING
+            "#,
+            self.sla_hours as i32,
+            self.sla_breach_lookback_hours as i32
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Отправить все уведомления, которым подошёл срок, и вернуть число отправленных писем.
+    pub async fn send_due(&self) -> Result<usize, sqlx::Error> {
+        let mut sent = 0;
+        loop {
+            let batch = self.claim_due().await?;
+            if batch.is_empty() {
+                return Ok(sent);
+            }
+            let mut results = Vec::with_capacity(batch.len());
+            for pending in batch {
+                let result = self.send(&pending).await;
+                if matches!(result, Ok(true)) {
+                    sent += 1;
+                }
+                results.push((pending, result));
+            }
+            self.record(results).await?;
+        }
+    }
+
+    /// Взять в аренду до `batch_size` уведомлений, которым подошёл срок, вместе с данными
+    /// аварий и адресатами.
+    async fn claim_due(&self) -> Result<Vec<Pending>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            WITH claimed AS (
+                UPDATE notification_outbox
+                SET next_attempt_at = NOW() + make_interval(secs => $1)
+                WHERE id IN (
+                    SELECT id
+                    FROM notification_outbox
+                    WHERE next_attempt_at <= NOW()
+                    ORDER BY next_attempt_at
+                    LIMIT $2
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, kind, status, previous_status, attempts, incident_id, created_at
+            )
+            SELECT
+                n.id,
+                n.kind AS "kind!: NotificationKind",
+                n.status AS "status!: IncidentStatus",
+                n.previous_status AS "previous_status: IncidentStatus",
+                n.attempts AS "attempts!",
+                i.id AS incident_id,
+                i.reported_at,
+                i.description,
+                t.name AS "incident_type?",
+                b.number AS "building_number?",
+                a.region AS "region?",
+                a.city AS "city?",
+                a.street AS "street?",
+                ARRAY(
+                    SELECT e.email::text
+                    FROM committee_employee ce
+                    JOIN employee e ON e.id = ce.employee_id
+                    WHERE
+                        ce.committee_id = b.committee_id
+                        AND e.deleted_at IS NULL
+                        AND e.email IS NOT NULL
+                        AND i.deleted_at IS NULL
+                    ORDER BY e.email
+                ) AS "recipients!"
+            FROM
+                claimed n
+                JOIN incident i ON i.id = n.incident_id
+                LEFT JOIN incident_type t ON t.id = i.incident_type_id
+                LEFT JOIN building b ON b.id = i.building_id
+                LEFT JOIN address a ON a.id = b.address_id
+            ORDER BY
+                n.created_at
+            "#,
+            self.lease.as_secs_f64(),
+            self.batch_size
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let address = row.building_number.map(|number| {
+                    format!(
+                        "{}, {}, {}, дом {}",
+                        row.region.as_deref().unwrap_or_default(),
+                        row.city.as_deref().unwrap_or_default(),
+                        row.street.as_deref().unwrap_or_default(),
+                        number
+                    )
+                });
+                Pending {
+                    id: row.id,
+                    attempts: row.attempts,
+                    recipients: row.recipients,
+                    notice: IncidentNotice {
+                        kind: row.kind,
+                        incident_id: row.incident_id,
+                        incident_type: row.incident_type,
+                        description: row.description,
+                        address,
+                        reported_at: row.reported_at,
+                        status: row.status,
+                        previous_status: row.previous_status,
+                    },
+                }
+            })
+            .collect())
+    }
+
+    /// Записать результаты отправки и назначить повторы одной короткой транзакцией.
+    async fn record(
+        &self,
+        results: Vec<(Pending, anyhow::Result<bool>)>,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = self.db.begin().await?;
+        for (pending, result) in results {
+            let attempt = pending.attempts + 1;
+            let now = Utc::now();
+            let (next_attempt_at, sent_at, failed_at) = match result {
+                // `false`: адресатов нет, у дома нет комитета или у его членов не указана почта.
+                Ok(_) => (None, Some(now), None),
+                Err(e) if attempt >= self.max_attempts => {
+                    tracing::error!(
+                        notification_id = %pending.id,
+                        error = ?e,
+                        "giving up on notification"
+                    );
+                    (None, None, Some(now))
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        notification_id = %pending.id,
+                        error = ?e,
+                        "failed to send notification, will retry"
+                    );
+                    (Some(now + retry_delay(attempt)), None, None)
+                }
+            };
+            // Если аренда истекла и письмо уже отправил другой экземпляр, его результат главнее.
+            sqlx::query!(
+                r#"
+                UPDATE notification_outbox
+                SET attempts = $2, next_attempt_at = $3, sent_at = $4, failed_at = $5
+                WHERE id = $1 AND attempts = $6
+                "#,
+                pending.id,
+                attempt,
+                next_attempt_at,
+                sent_at,
+                failed_at,
+                pending.attempts
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        transaction.commit().await
+    }
+
+    /// Отправить одно письмо всем адресатам; `false`, если отправлять некому.
+    async fn send(&self, pending: &Pending) -> anyhow::Result<bool> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .header(ContentType::TEXT_PLAIN);
+        let mut has_recipients = false;
+        for email in &pending.recipients {
+            match email.parse::<Mailbox>() {
+                Ok(mailbox) => {
+                    builder = builder.to(mailbox);
+                    has_recipients = true;
+                }
+                Err(e) => tracing::warn!(email, error = %e, "skipping invalid recipient"),
+            }
+        }
+        if !has_recipients {
+            return Ok(false);
+        }
+
+        let email = pending.notice.render(self.sla_hours, self.timezone);
+        let message = builder.subject(email.subject).body(email.body)?;
+        self.mailer.send(message).await?;
+        Ok(true)
+    }
+}
+
+/// Снять с очереди уведомления, которые не удалось отправить за `max_age_hours`: они
+/// помечаются неотправленными (`failed_at`). Возвращает их число.
+pub async fn expire_stale(db: &PgPool, max_age_hours: u32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE notification_outbox
+        SET next_attempt_at = NULL, failed_at = NOW()
+        WHERE
+            next_attempt_at IS NOT NULL
+            AND created_at < NOW() - make_interval(hours => $1)
+        "#,
+        max_age_hours as i32
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+impl OutboxWorker for NotificationWorker {
+    fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    async fn poll(&self) {
+        if let Err(e) = expire_stale(&self.db, self.max_age_hours).await {
+            tracing::error!(error = ?e, "failed to expire stale notifications");
+        }
+        if let Err(e) = self.queue_sla_breaches().await {
+            tracing::error!(error = ?e, "failed to check incident deadlines");
+        }
+        if let Err(e) = self.send_due().await {
+            tracing::error!(error = ?e, "failed to send notifications");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lettre::AsyncFileTransport;
+
+    use super::*;
+    use crate::api::test_context;
+
+    const SIDOROV: Uuid = Uuid::from_u128(0xe3);
+    const COMMITTEE: Uuid = Uuid::from_u128(0xc1);
+    const LENINA_5: Uuid = Uuid::from_u128(0xb1);
+    const KHIMKI_12: Uuid = Uuid::from_u128(0xb2);
+    const LEAK: Uuid = Uuid::from_u128(0x101);
+    const ROOF: Uuid = Uuid::from_u128(0x202);
+
+    async fn report(pool: &PgPool, building_id: Uuid, hours_ago: i32) {
+        sqlx::query!(
+            r#"
+            INSERT INTO incident (building_id, reported_at, status, description, incident_type_id)
+            VALUES ($1, NOW() - make_interval(hours => $2), 'reported', 'Прорвало трубу', $3)
+            "#,
+            building_id,
+            hours_ago,
+            LEAK
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn stale_notifications_are_not_sent(pool: PgPool) {
+        sqlx::query!("DELETE FROM notification_outbox")
+            .execute(&pool)
+            .await
+            .unwrap();
+        report(&pool, LENINA_5, 0).await;
+        report(&pool, LENINA_5, 0).await;
+        sqlx::query!(
+            r#"
+            UPDATE notification_outbox
+            SET created_at = NOW() - interval '25 hours'
+            WHERE id = (SELECT id FROM notification_outbox LIMIT 1)
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(expire_stale(&pool, 24).await.unwrap(), 1);
+        assert_eq!(expire_stale(&pool, 24).await.unwrap(), 0);
+        let pending = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM notification_outbox
+            WHERE next_attempt_at IS NOT NULL
+            "#
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(pending, 1);
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn claimed_notifications_are_leased_to_one_worker(pool: PgPool) {
+        let ctx = test_context(pool.clone());
+        let mailer = || Mailer::File(AsyncFileTransport::new(std::env::temp_dir()));
+        let worker = NotificationWorker::new(pool.clone(), &ctx.config, mailer());
+        let other = NotificationWorker::new(pool.clone(), &ctx.config, mailer());
+
+        // Аварии фикстуры попали в очередь.
+        let queued = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM notification_outbox"#)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(queued > 0);
+
+        assert_eq!(worker.claim_due().await.unwrap().len() as i64, queued);
+        // Аренда уже зафиксирована: другой экземпляр эти уведомления не возьмёт.
+        assert!(other.claim_due().await.unwrap().is_empty());
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn committee_members_are_emailed_about_their_buildings(pool: PgPool) {
+        let ctx = test_context(pool.clone());
+        let dir = std::env::temp_dir().join(format!("mc-mail-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mailer = Mailer::File(AsyncFileTransport::new(&dir));
+        let worker = NotificationWorker::new(pool.clone(), &ctx.config, mailer);
+
+        // Аварии фикстуры тоже попали в очередь.
+        sqlx::query!("DELETE FROM notification_outbox")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Удалённый сотрудник остаётся в комитете, но писем не получает.
+        sqlx::query!(
+            "INSERT INTO committee_employee VALUES ($1, $2)",
+            COMMITTEE,
+            SIDOROV
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "UPDATE employee SET deleted_at = NOW() WHERE id = $1",
+            SIDOROV
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        report(&pool, LENINA_5, 0).await;
+        report(&pool, LENINA_5, 73).await;
+        // У дома в Химках нет комитета.
+        report(&pool, KHIMKI_12, 0).await;
+        sqlx::query!(
+            "UPDATE incident SET status = 'resolved' WHERE id = $1",
+            ROOF
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Аварии в Химках из фикстуры просрочены слишком давно.
+        assert_eq!(worker.queue_sla_breaches().await.unwrap(), 1);
+        assert_eq!(worker.queue_sla_breaches().await.unwrap(), 0);
+
+        // Две новые аварии на Ленина, смена статуса и нарушение срока.
+        assert_eq!(worker.send_due().await.unwrap(), 4);
+        assert_eq!(worker.send_due().await.unwrap(), 0);
+
+        let emails: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(emails.len(), 4);
+        for email in &emails {
+            assert!(email.contains("To: petrova@example.com\r\n"), "{email}");
+            assert!(!email.contains("sidorov@example.com"));
+        }
+
+        let unsettled = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM notification_outbox WHERE sent_at IS NULL"#
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(unsettled, 0);
+    }
+}
@@ -0,0 +1,322 @@
+use axum::Router;
+use utoipa::{
+    openapi::{
+        path::Operation,
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        Deprecated, OpenApi as OpenApiSpec, PathItem,
+    },
+    Modify, OpenApi,
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use super::{
+    audit, building, employee, events, export::ExportFormat, financial_operation, health, incident,
+    metrics, pagination::SortOrder, repair, search, statistics, trash, user, webhook, ApiContext,
+};
+
+/// Спецификация отдаётся рядом с API, страница Swagger UI её загружает.
+const SPEC_PATH: &str = "/api/openapi.json";
+const DOCS_PATH: &str = "/api/docs";
+
+/// Схема авторизации, на которую ссылаются методы с `security(("bearer" = []))`.
+const BEARER_SCHEME: &str = "bearer";
+
+/// Маршруты актуальной версии; остальные под `/api/` относятся к устаревшей v1.
+const V2_PREFIX: &str = "/api/v2/";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Management company API",
+        description = "API управляющей компании: сотрудники, дома, аварии, ремонты, финансы и статистика."
+    ),
+    paths(metrics::get_metrics),
+    components(schemas(SortOrder, ExportFormat)),
+    tags((name = "metrics", description = "Метрики для Prometheus")),
+    modifiers(&BearerAuth)
+)]
+struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut OpenApiSpec) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                BEARER_SCHEME,
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .description(Some("Токен из ответа `/api/users/login`"))
+                        .build(),
+                ),
+            );
+    }
+}
+
+/// Спецификация всего API; каждый модуль описывает свои маршруты так же, как регистрирует их.
+pub fn api_doc() -> OpenApiSpec {
+    let mut doc = ApiDoc::openapi();
+    for module in [
+        user::ApiDoc::openapi(),
+        employee::ApiDoc::openapi(),
+        building::ApiDoc::openapi(),
+        incident::ApiDoc::openapi(),
+        repair::ApiDoc::openapi(),
+        financial_operation::ApiDoc::openapi(),
+        statistics::ApiDoc::openapi(),
+        search::ApiDoc::openapi(),
+        health::ApiDoc::openapi(),
+        audit::ApiDoc::openapi(),
+        trash::ApiDoc::openapi(),
+        events::ApiDoc::openapi(),
+        webhook::ApiDoc::openapi(),
+    ] {
+        doc.merge(module);
+    }
+    // Лицензия не указана в Cargo.toml, пустое имя в спецификации недопустимо.
+    doc.info.license = None;
+
+    for (path, item) in doc.paths.paths.iter_mut() {
+        let is_v2 = path.starts_with(V2_PREFIX);
+        let is_v1 = !is_v2 && path.starts_with("/api/");
+        for operation in operations(item) {
+            if is_v1 {
+                operation.deprecated = Some(Deprecated::True);
+            }
+            // Обработчики v2 называются так же, как в v1, а идентификаторы должны различаться.
+            if let (true, Some(id)) = (is_v2, operation.operation_id.as_mut()) {
+                id.insert_str(0, "v2_");
+            }
+        }
+    }
+    doc
+}
+
+fn operations(item: &mut PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        item.get.as_mut(),
+        item.post.as_mut(),
+        item.put.as_mut(),
+        item.patch.as_mut(),
+        item.delete.as_mut(),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+pub(crate) fn router() -> Router<ApiContext> {
+    SwaggerUi::new(DOCS_PATH).url(SPEC_PATH, api_doc()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, fs, path::Path};
+
+    use axum::{
+        body::Body,
+        http::{Method, Request, StatusCode},
+    };
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::api::{api_router, test_context};
+
+    const METHODS: [Method; 5] = [
+        Method::GET,
+        Method::POST,
+        Method::PUT,
+        Method::PATCH,
+        Method::DELETE,
+    ];
+
+    fn documented_methods(item: &PathItem) -> Vec<Method> {
+        [
+            (Method::GET, item.get.is_some()),
+            (Method::POST, item.post.is_some()),
+            (Method::PUT, item.put.is_some()),
+            (Method::PATCH, item.patch.is_some()),
+            (Method::DELETE, item.delete.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(method, documented)| documented.then_some(method))
+        .collect()
+    }
+
+    /// Пути из вызовов `.route("...")` во всех модулях `src/api`, в записи OpenAPI.
+    ///
+    /// Код тестов не просматривается: там маршруты собираются для проверки слоёв.
+    fn registered_paths(dir: &Path, paths: &mut BTreeSet<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                registered_paths(&path, paths);
+                continue;
+            }
+            let source = fs::read_to_string(&path).unwrap();
+            let source = source.split("#[cfg(test)]").next().unwrap();
+            for call in source.split(".route(").skip(1) {
+                let literal = call.trim_start().strip_prefix('"').unwrap();
+                let route = &literal[..literal.find('"').unwrap()];
+                let route: Vec<String> = route
+                    .split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(param) => format!("{{{param}}}"),
+                        None => segment.to_string(),
+                    })
+                    .collect();
+                paths.insert(route.join("/"));
+            }
+        }
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        let spec = api_doc();
+        let mut registered = BTreeSet::new();
+        registered_paths(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src/api"),
+            &mut registered,
+        );
+        let documented: BTreeSet<String> = spec.paths.paths.keys().cloned().collect();
+
+        let undocumented: Vec<_> = registered.difference(&documented).collect();
+        assert!(
+            undocumented.is_empty(),
+            "routes missing from the spec: {undocumented:?}"
+        );
+    }
+
+    fn collect_references<'a>(value: &'a serde_json::Value, references: &mut Vec<&'a str>) {
+        match value {
+            serde_json::Value::Object(object) => {
+                if let Some(serde_json::Value::String(reference)) = object.get("$ref") {
+                    references.push(reference);
+                }
+                object
+                    .values()
+                    .for_each(|value| collect_references(value, references));
+            }
+            serde_json::Value::Array(items) => items
+                .iter()
+                .for_each(|value| collect_references(value, references)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn operation_ids_are_unique_and_only_v1_is_deprecated() {
+        let mut spec = api_doc();
+        let mut ids = BTreeSet::new();
+
+        for (path, item) in spec.paths.paths.iter_mut() {
+            for operation in operations(item) {
+                let id = operation.operation_id.clone().unwrap();
+                assert!(ids.insert(id.clone()), "duplicate operation id {id}");
+
+                let deprecated = matches!(operation.deprecated, Some(Deprecated::True));
+                let v1 = path.starts_with("/api/") && !path.starts_with(V2_PREFIX);
+                assert_eq!(deprecated, v1, "{path} ({id})");
+            }
+        }
+    }
+
+    #[test]
+    fn every_schema_reference_resolves() {
+        let spec = serde_json::to_value(api_doc()).unwrap();
+        let mut references = Vec::new();
+        collect_references(&spec, &mut references);
+
+        let dangling: BTreeSet<_> = references
+            .into_iter()
+            .filter(|reference| {
+                let name = reference.trim_start_matches("#/components/schemas/");
+                spec["components"]["schemas"].get(name).is_none()
+            })
+            .collect();
+        assert!(
+            dangling.is_empty(),
+            "unresolved schema references: {dangling:?}"
+        );
+    }
+
+    #[sqlx::test]
+    async fn spec_matches_router(pool: PgPool) {
+        let app = api_router(test_context(pool));
+        let spec = api_doc();
+
+        for (path, item) in &spec.paths.paths {
+            let uri = path
+                .split('/')
+                .map(|segment| {
+                    if segment.starts_with('{') {
+                        "00000000-0000-0000-0000-000000000000"
+                    } else {
+                        segment
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let documented = documented_methods(item);
+
+            for method in METHODS {
+                let response = app
+                    .clone()
+                    .oneshot(
+                        Request::builder()
+                            .method(method.clone())
+                            .uri(&uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = response.status();
+
+                if documented.contains(&method) {
+                    assert!(
+                        status != StatusCode::NOT_FOUND && status != StatusCode::METHOD_NOT_ALLOWED,
+                        "{method} {path} is documented but the router answers {status}"
+                    );
+                } else {
+                    assert_eq!(
+                        status,
+                        StatusCode::METHOD_NOT_ALLOWED,
+                        "{method} {path} is routed but not documented"
+                    );
+                }
+            }
+        }
+    }
+
+    #[sqlx::test]
+    async fn spec_and_docs_are_served(pool: PgPool) {
+        let app = api_router(test_context(pool));
+
+        let spec = app
+            .clone()
+            .oneshot(Request::get(SPEC_PATH).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(spec.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(spec.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let served: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(served, serde_json::to_value(api_doc()).unwrap());
+
+        let docs = app
+            .oneshot(
+                Request::get(format!("{DOCS_PATH}/"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(docs.status(), StatusCode::OK);
+    }
+}
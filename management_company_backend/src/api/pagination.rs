@@ -0,0 +1,577 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::{postgres::PgRow, FromRow, PgPool, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::Error;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+pub const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    fn as_sql(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+
+    fn comparison(self) -> &'static str {
+        match self {
+            Self::Asc => ">",
+            Self::Desc => "<",
+        }
+    }
+}
+
+/// Поле, по которому можно сортировать список ресурса.
+pub trait SortField: Copy + Default + DeserializeOwned + Send {
+    /// SQL-выражение для `ORDER BY`. Не должно возвращать NULL, иначе курсор перестанет работать.
+    fn expression(self) -> &'static str;
+
+    fn default_order(self) -> SortOrder {
+        SortOrder::Asc
+    }
+}
+
+/// Фильтры конкретного ресурса, добавляемые к запросу после условия источника.
+pub trait ListFilter: DeserializeOwned + Send {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>);
+}
+
+/// Описание таблицы, из которой строится постраничный список.
+pub struct ListSource {
+    pub select: &'static str,
+    pub from: &'static str,
+    /// Условие, которое действует всегда, например исключение удалённых записей.
+    pub condition: &'static str,
+    pub id_column: &'static str,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Page {
+    Offset {
+        page: i64,
+        page_size: i64,
+    },
+    Cursor {
+        after: Uuid,
+        page_size: i64,
+    },
+    /// Весь список одним ответом: так отвечают маршруты v1, если страница не запрошена.
+    All,
+}
+
+impl Page {
+    fn page_size(&self) -> Option<i64> {
+        match *self {
+            Self::Offset { page_size, .. } | Self::Cursor { page_size, .. } => Some(page_size),
+            Self::All => None,
+        }
+    }
+
+    /// Сколько записей пропустить до начала страницы.
+    fn offset(&self) -> Result<Option<i64>, Error> {
+        match *self {
+            Self::Offset { page, page_size } => (page - 1)
+                .checked_mul(page_size)
+                .map(Some)
+                .ok_or_else(|| Error::UnprocessableEntity("page is too large".to_string())),
+            Self::Cursor { .. } | Self::All => Ok(None),
+        }
+    }
+}
+
+/// Параметры списка: `page`/`pageSize` или `cursor`, `sortBy`, `sortOrder` и фильтры ресурса.
+pub struct ListQuery<F, S> {
+    pub filter: F,
+    pub sort_by: S,
+    pub sort_order: SortOrder,
+    pub page: Page,
+    /// Передан ли хоть один из `page`, `pageSize` и `cursor`.
+    page_requested: bool,
+}
+
+impl<F, S> ListQuery<F, S> {
+    /// Маршруты v1 до появления страниц отдавали списки целиком, и клиенты на это рассчитывают:
+    /// без параметров страницы список возвращается полностью.
+    pub fn whole_list_unless_paged(mut self) -> Self {
+        if !self.page_requested {
+            self.page = Page::All;
+        }
+        self
+    }
+
+    /// Маршруты v2 всегда отвечают страницей: без параметров — первой.
+    pub fn paged(mut self) -> Self {
+        self.page_requested = true;
+        self
+    }
+}
+
+/// Параметры страницы, общие для всех списков; `sortBy` и фильтры у каждого ресурса свои.
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// Номер страницы, начиная с 1; нельзя передавать вместе с `cursor`.
+    page: Option<i64>,
+    /// Размер страницы, от 1 до 200; по умолчанию 50. Маршруты v1 без `page`, `pageSize`
+    /// и `cursor` возвращают весь список.
+    page_size: Option<i64>,
+    /// `nextCursor` предыдущей страницы.
+    cursor: Option<Uuid>,
+    /// По умолчанию — свой для каждого поля сортировки.
+    sort_order: Option<SortOrder>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", bound = "S: DeserializeOwned")]
+struct SortParams<S> {
+    sort_by: Option<S>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub total: i64,
+    pub page: Option<i64>,
+    pub page_size: i64,
+    pub next_cursor: Option<Uuid>,
+}
+
+#[async_trait]
+impl<F, S, St> FromRequestParts<St> for ListQuery<F, S>
+where
+    F: ListFilter,
+    S: SortField,
+    St: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &St) -> Result<Self, Self::Rejection> {
+        let Query(params) = Query::<PageParams>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| Error::UnprocessableEntity(e.body_text()))?;
+        let Query(sort) = Query::<SortParams<S>>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| Error::UnprocessableEntity(e.body_text()))?;
+        let Query(filter) = Query::<F>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| Error::UnprocessableEntity(e.body_text()))?;
+
+        let page_requested =
+            params.page.is_some() || params.page_size.is_some() || params.cursor.is_some();
+        let page_size = params.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+        if !(1..=MAX_PAGE_SIZE).contains(&page_size) {
+            return Err(Error::UnprocessableEntity(format!(
+                "pageSize must be between 1 and {MAX_PAGE_SIZE}"
+            )));
+        }
+
+        let page = match (params.cursor, params.page) {
+            (Some(_), Some(_)) => {
+                return Err(Error::UnprocessableEntity(
+                    "page and cursor cannot be used together".to_string(),
+                ))
+            }
+            (Some(after), None) => Page::Cursor { after, page_size },
+            (None, Some(page)) if page < 1 => {
+                return Err(Error::UnprocessableEntity(
+                    "page must be greater than 0".to_string(),
+                ))
+            }
+            (None, page) => Page::Offset {
+                page: page.unwrap_or(1),
+                page_size,
+            },
+        };
+        page.offset()?;
+
+        let sort_by = sort.sort_by.unwrap_or_default();
+
+        Ok(Self {
+            filter,
+            sort_by,
+            sort_order: params.sort_order.unwrap_or(sort_by.default_order()),
+            page,
+            page_requested,
+        })
+    }
+}
+
+/// Запрос всех записей списка, подходящих под фильтры, в порядке сортировки и без разбиения на страницы.
+pub fn list_all_query<F, S>(
+    source: &ListSource,
+    query: &ListQuery<F, S>,
+) -> QueryBuilder<'static, Postgres>
+where
+    F: ListFilter,
+    S: SortField,
+{
+    let sort_expression = query.sort_by.expression();
+    let order = query.sort_order.as_sql();
+
+    let mut builder = QueryBuilder::new(source.select);
+    builder
+        .push(" ")
+        .push(source.from)
+        .push(" WHERE ")
+        .push(source.condition);
+    query.filter.push_conditions(&mut builder);
+    builder.push(format_args!(
+        " ORDER BY {sort_expression} {order}, {} {order}",
+        source.id_column
+    ));
+
+    builder
+}
+
+/// Загрузить одну страницу списка вместе с общим количеством записей, подходящих под фильтры.
+pub async fn fetch_page<F, S, T>(
+    pool: &PgPool,
+    source: &ListSource,
+    query: &ListQuery<F, S>,
+    id_of: impl Fn(&T) -> Uuid,
+) -> Result<(Vec<T>, PageInfo), Error>
+where
+    F: ListFilter,
+    S: SortField,
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    let Some(page_size) = query.page.page_size() else {
+        let rows: Vec<T> = list_all_query(source, query)
+            .build_query_as()
+            .fetch_all(pool)
+            .await?;
+        let total = rows.len() as i64;
+        return Ok((
+            rows,
+            PageInfo {
+                total,
+                page: None,
+                page_size: total,
+                next_cursor: None,
+            },
+        ));
+    };
+
+    // Курсор на запись, которой нет, иначе молча вернул бы пустую страницу. Удалённые записи
+    // остаются в таблице, поэтому курсор на запись, удалённую после запроса страницы, работает.
+    if let Page::Cursor { after, .. } = query.page {
+        let mut cursor_builder = QueryBuilder::new("SELECT EXISTS (SELECT 1 ");
+        cursor_builder
+            .push(source.from)
+            .push(format_args!(" WHERE {} = ", source.id_column))
+            .push_bind(after)
+            .push(")");
+        let known: bool = cursor_builder.build_query_scalar().fetch_one(pool).await?;
+        if !known {
+            return Err(Error::UnprocessableEntity(
+                "cursor does not point to an item of this list".to_string(),
+            ));
+        }
+    }
+
+    let mut count_builder = QueryBuilder::new("SELECT COUNT(*) ");
+    count_builder
+        .push(source.from)
+        .push(" WHERE ")
+        .push(source.condition);
+    query.filter.push_conditions(&mut count_builder);
+    let total: i64 = count_builder.build_query_scalar().fetch_one(pool).await?;
+
+    let sort_expression = query.sort_by.expression();
+    let order = query.sort_order.as_sql();
+
+    let mut builder = QueryBuilder::new(source.select);
+    builder
+        .push(" ")
+        .push(source.from)
+        .push(" WHERE ")
+        .push(source.condition);
+    query.filter.push_conditions(&mut builder);
+
+    if let Page::Cursor { after, .. } = query.page {
+        builder
+            .push(format_args!(
+                " AND ({sort_expression}, {}) {} ((SELECT {sort_expression} {} WHERE {} = ",
+                source.id_column,
+                query.sort_order.comparison(),
+                source.from,
+                source.id_column,
+            ))
+            .push_bind(after)
+            .push("), ")
+            .push_bind(after)
+            .push(")");
+    }
+
+    builder.push(format_args!(
+        " ORDER BY {sort_expression} {order}, {} {order} LIMIT ",
+        source.id_column
+    ));
+    builder.push_bind(page_size + 1);
+
+    if let Some(offset) = query.page.offset()? {
+        builder.push(" OFFSET ").push_bind(offset);
+    }
+
+    let mut rows: Vec<T> = builder.build_query_as().fetch_all(pool).await?;
+
+    let has_more = rows.len() as i64 > page_size;
+    rows.truncate(page_size as usize);
+
+    let next_cursor = if has_more {
+        rows.last().map(&id_of)
+    } else {
+        None
+    };
+
+    let page = match query.page {
+        Page::Offset { page, .. } => Some(page),
+        Page::Cursor { .. } | Page::All => None,
+    };
+
+    Ok((
+        rows,
+        PageInfo {
+            total,
+            page,
+            page_size,
+            next_cursor,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header::AUTHORIZATION, Request, StatusCode},
+        Router,
+    };
+    use serde_json::Value;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::api::{api_router, extractor::AuthUser, test_context};
+
+    const PETROVA: Uuid = Uuid::from_u128(0xe2);
+
+    async fn app(pool: PgPool) -> (Router, String) {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO user_account (email, password_hash, employee_id)
+            VALUES ('dispatcher@example.com', '', $1)
+            RETURNING id
+            "#,
+            PETROVA
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let ctx = test_context(pool);
+        let token = AuthUser { user_id }.to_jwt(&ctx);
+        (api_router(ctx), token)
+    }
+
+    async fn list(app: &Router, token: &str, query: &str) -> (StatusCode, Value) {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get(format!("/api/incidents?{query}"))
+                    .header(AUTHORIZATION, format!("Bearer {token}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    /// Последние байты идентификаторов аварий на странице.
+    fn ids(body: &Value) -> Vec<u128> {
+        body["incidents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|incident| {
+                Uuid::parse_str(incident["id"].as_str().unwrap())
+                    .unwrap()
+                    .as_u128()
+            })
+            .collect()
+    }
+
+    #[sqlx::test(fixtures("statistics/fixtures/statistics.sql"))]
+    async fn offset_pages_cover_the_list(pool: PgPool) {
+        let (app, token) = app(pool).await;
+
+        let (status, first) = list(&app, &token, "pageSize=3").await;
+        assert_eq!(status, StatusCode::OK);
+        // По умолчанию сначала новые.
+        assert_eq!(ids(&first), [0x204, 0x203, 0x202]);
+        assert_eq!(first["total"], 4);
+        assert_eq!(first["page"], 1);
+        assert_eq!(first["pageSize"], 3);
+        assert_eq!(first["nextCursor"], "00000000-0000-0000-0000-000000000202");
+
+        let (_, second) = list(&app, &token, "pageSize=3&page=2").await;
+        assert_eq!(ids(&second), [0x201]);
+        assert_eq!(second["nextCursor"], Value::Null);
+
+        let (_, past_the_end) = list(&app, &token, "pageSize=3&page=3").await;
+        assert_eq!(ids(&past_the_end), Vec::<u128>::new());
+        assert_eq!(past_the_end["total"], 4);
+
+        for query in [
+            "page=0",
+            "pageSize=0",
+            "pageSize=201",
+            "page=9223372036854775807",
+            "page=-9223372036854775808",
+        ] {
+            let (status, _) = list(&app, &token, query).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{query}");
+        }
+        let (status, _) = list(&app, &token, "pageSize=200&page=46116860184273879").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[sqlx::test(fixtures("statistics/fixtures/statistics.sql"))]
+    async fn v1_returns_the_whole_list_unless_a_page_is_requested(pool: PgPool) {
+        let (app, token) = app(pool).await;
+
+        let (status, whole) = list(&app, &token, "sortOrder=asc").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ids(&whole), [0x201, 0x202, 0x203, 0x204]);
+        assert_eq!(whole["total"], 4);
+        assert_eq!(whole["page"], Value::Null);
+        assert_eq!(whole["pageSize"], 4);
+        assert_eq!(whole["nextCursor"], Value::Null);
+
+        let (_, first) = list(&app, &token, "page=1").await;
+        assert_eq!(first["page"], 1);
+        assert_eq!(first["pageSize"], DEFAULT_PAGE_SIZE);
+
+        let response = app
+            .oneshot(
+                Request::get("/api/v2/incidents")
+                    .header(AUTHORIZATION, format!("Bearer {token}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let v2: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(v2["meta"]["page"], 1);
+        assert_eq!(v2["meta"]["pageSize"], DEFAULT_PAGE_SIZE);
+    }
+
+    #[sqlx::test(fixtures("statistics/fixtures/statistics.sql"))]
+    async fn cursor_pages_continue_after_ties(pool: PgPool) {
+        let (app, token) = app(pool).await;
+
+        // У двух аварий один тип, порядок внутри типа задаёт идентификатор.
+        let (status, first) = list(&app, &token, "pageSize=2&sortBy=incidentType").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ids(&first), [0x203, 0x204]);
+        let cursor = first["nextCursor"].as_str().unwrap();
+
+        let (_, second) = list(
+            &app,
+            &token,
+            &format!("pageSize=2&sortBy=incidentType&cursor={cursor}"),
+        )
+        .await;
+        assert_eq!(ids(&second), [0x201, 0x202]);
+        assert_eq!(second["page"], Value::Null);
+        assert_eq!(second["nextCursor"], Value::Null);
+
+        let (_, descending) = list(
+            &app,
+            &token,
+            "pageSize=2&sortBy=incidentType&sortOrder=desc&cursor=00000000-0000-0000-0000-000000000201",
+        )
+        .await;
+        assert_eq!(ids(&descending), [0x204, 0x203]);
+
+        for query in [
+            "cursor=00000000-0000-0000-0000-000000000999",
+            "cursor=not-a-cursor",
+            "cursor=00000000-0000-0000-0000-000000000202&page=2",
+        ] {
+            let (status, _) = list(&app, &token, query).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{query}");
+        }
+    }
+
+    #[sqlx::test(fixtures("statistics/fixtures/statistics.sql"))]
+    async fn filters_and_sort_fields_are_whitelisted(pool: PgPool) {
+        let (app, token) = app(pool).await;
+
+        let (status, lenina) = list(
+            &app,
+            &token,
+            "buildingId=00000000-0000-0000-0000-0000000000b1",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "lenina");
+        assert_eq!(ids(&lenina), [0x202, 0x201]);
+        assert_eq!(lenina["total"], 2);
+
+        let (status, reported) = list(&app, &token, "status=Reported").await;
+        assert_eq!(status, StatusCode::OK, "reported");
+        assert_eq!(ids(&reported), [0x204]);
+
+        // Обе границы периода входят в него.
+        let (status, march) = list(
+            &app,
+            &token,
+            "reportedFrom=2024-03-20&reportedTo=2024-03-31",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "march");
+        assert_eq!(ids(&march), [0x204, 0x203]);
+
+        let (status, oldest_first) = list(&app, &token, "sortBy=reportedAt&sortOrder=asc").await;
+        assert_eq!(status, StatusCode::OK, "oldest_first");
+        assert_eq!(ids(&oldest_first), [0x201, 0x202, 0x203, 0x204]);
+
+        for query in [
+            "sortBy=description",
+            "sortBy=i.id;DROP%20TABLE%20incident",
+            "sortOrder=sideways",
+            "status=lost",
+            "buildingId=5",
+        ] {
+            let (status, _) = list(&app, &token, query).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{query}");
+        }
+    }
+}
@@ -1,30 +1,38 @@
-use axum::{extract::State, Json};
-use sqlx::query;
+use axum::{
+    extract::{Path, Query, State},
+    response::Response,
+    Json,
+};
+use uuid::Uuid;
 
-use crate::api::{extractor::AuthUser, incident::models::IncidentStatus, ApiContext, Error};
+use crate::api::{
+    audit::{models::AuditEntity, utils::AuditRecord},
+    conditional::IfMatch,
+    export::{list_response, ExportParams},
+    extractor::AuthUser,
+    pagination::{fetch_page, ListQuery, ListSource, PageParams, SortOrder},
+    trash::{models::TrashEntity, utils::soft_delete},
+    ApiContext, Error,
+};
 
-use super::models::{Repair, RepairList, RepairType};
+use super::models::{Repair, RepairFilter, RepairList, RepairRow, RepairSortField};
 
-pub async fn get_all_repairs(
-    _: AuthUser,
-    State(ctx): State<ApiContext>,
-) -> Result<Json<RepairList>, Error> {
-    let db_repairs = query!(
-        r#"
+const REPAIR_LIST: ListSource = ListSource {
+    select: r#"
         SELECT
             r.id,
             r.started_at,
             r.ended_at,
-            r.type::text AS "repair_type:RepairType",
-            i.id AS incident_id,
-            i.reported_at,
-            i.resolved_at,
-            i.status as "status: IncidentStatus",
+            r.type AS repair_type,
+            i.status,
             i.description,
-            b.number as building_number,
+            b.number AS building_number,
             a.region,
             a.city,
-            a.street
+            a.street,
+            r.version
+        "#,
+    from: r#"
         FROM
             repair r
         LEFT JOIN
@@ -33,29 +41,132 @@ pub async fn get_all_repairs(
             building b ON r.building_id = b.id
         INNER JOIN
             address a ON b.address_id = a.id
-        "#
+        "#,
+    condition: "r.deleted_at IS NULL",
+    id_column: "r.id",
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/repairs",
+    tag = "repairs",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<RepairSortField>, Query, description = "Поле сортировки"),
+        RepairFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка ремонтов", body = RepairList),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверные параметры списка"),
     )
-    .fetch_all(&ctx.db)
-    .await?;
+)]
+pub async fn get_all_repairs(
+    _: AuthUser,
+    State(ctx): State<ApiContext>,
+    query: ListQuery<RepairFilter, RepairSortField>,
+) -> Result<Json<RepairList>, Error> {
+    let query = query.whole_list_unless_paged();
+    let (db_repairs, page_info) =
+        fetch_page(&ctx.db, &REPAIR_LIST, &query, |row: &RepairRow| row.id).await?;
 
     let repairs = db_repairs
         .into_iter()
         .map(|row| Repair {
             id: row.id,
-            started_at: row.started_at.unwrap(),
+            started_at: row.started_at,
             ended_at: row.ended_at,
-            repair_type: row.repair_type.unwrap().to_string(),
-            status: row.status.to_string(),
-            description: row.description.unwrap(),
+            repair_type: row.repair_type.to_string(),
+            status: row
+                .status
+                .map(|status| status.to_string())
+                .unwrap_or_default(),
+            description: row.description.unwrap_or_default(),
             building_address: format!(
                 "{}, {}, {}, дом {}",
-                row.region.unwrap(),
-                row.city.unwrap(),
-                row.street.unwrap(),
+                row.region.unwrap_or_default(),
+                row.city.unwrap_or_default(),
+                row.street.unwrap_or_default(),
                 row.building_number
             ),
+            version: row.version,
         })
         .collect();
 
-    Ok(Json(RepairList { repairs }))
+    Ok(Json(RepairList { repairs, page_info }))
+}
+
+/// Выгрузить все ремонты с фильтрами и сортировкой списка в CSV или XLSX.
+#[utoipa::path(
+    get,
+    path = "/api/repairs/export",
+    tag = "repairs",
+    security(("bearer" = [])),
+    params(
+        ExportParams,
+        ("sortBy" = Option<RepairSortField>, Query, description = "Поле сортировки"),
+        ("sortOrder" = Option<SortOrder>, Query, description = "Порядок сортировки"),
+        RepairFilter,
+    ),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверные параметры списка"),
+    )
+)]
+pub async fn export_repairs(
+    _: AuthUser,
+    State(ctx): State<ApiContext>,
+    Query(params): Query<ExportParams>,
+    query: ListQuery<RepairFilter, RepairSortField>,
+) -> Result<Response, Error> {
+    list_response::<_, _, RepairRow>(
+        ctx.db,
+        &REPAIR_LIST,
+        query,
+        params.format,
+        "repairs",
+        ctx.config.timezone,
+    )
+    .await
+}
+
+/// Удалить ремонт в корзину; маршрут есть только в v2.
+pub async fn delete_repair(
+    user: AuthUser,
+    State(ctx): State<ApiContext>,
+    Path(id): Path<Uuid>,
+    if_match: IfMatch,
+) -> Result<(), Error> {
+    let mut transaction = ctx.db.begin().await?;
+
+    let version = sqlx::query_scalar!(
+        r#"
+        SELECT version
+        FROM repair
+        WHERE id = $1 AND deleted_at IS NULL
+        FOR UPDATE
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(Error::NotFound)?;
+    if_match.check(version)?;
+
+    let before = soft_delete(&mut transaction, TrashEntity::Repair, id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    AuditRecord::deleted(AuditEntity::Repair, id, before)
+        .save(&mut transaction, Some(user.user_id))
+        .await?;
+    transaction.commit().await?;
+
+    ctx.statistics_cache.invalidate();
+
+    Ok(())
 }
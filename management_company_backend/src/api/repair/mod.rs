@@ -1,11 +1,29 @@
 use axum::{routing::get, Router};
-use controllers::get_all_repairs;
+use controllers::{export_repairs, get_all_repairs};
+use utoipa::OpenApi;
 
 use super::ApiContext;
 
 mod controllers;
 mod models;
+pub(crate) mod v2;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::get_all_repairs,
+        controllers::export_repairs,
+        v2::get_all_repairs,
+        v2::export_repairs,
+        v2::delete_repair,
+    ),
+    components(schemas(models::RepairSortField, models::RepairType)),
+    tags((name = "repairs", description = "Ремонты"))
+)]
+pub(crate) struct ApiDoc;
 
 pub(crate) fn router() -> Router<ApiContext> {
-    Router::new().route("/api/repairs", get(get_all_repairs))
+    Router::new()
+        .route("/api/repairs", get(get_all_repairs))
+        .route("/api/repairs/export", get(export_repairs))
 }
@@ -1,38 +1,152 @@
+use std::fmt;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::prelude::Type;
+use sqlx::{prelude::Type, FromRow, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize, Serialize, Type)]
+use crate::api::{
+    export::{Cell, ExportRow},
+    incident::models::IncidentStatus,
+    pagination::{ListFilter, PageInfo, SortField, SortOrder},
+};
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Type, ToSchema)]
 #[sqlx(type_name = "repair_type", rename_all = "lowercase")]
 pub enum RepairType {
     Scheduled,
     Emergency,
 }
 
-impl RepairType {
-    pub fn to_string(&self) -> String {
-        match self {
-            RepairType::Emergency => "Аварийный".to_string(),
-            RepairType::Scheduled => "Плановый".to_string(),
-        }
+impl fmt::Display for RepairType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RepairType::Emergency => "Аварийный",
+            RepairType::Scheduled => "Плановый",
+        })
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Repair {
     pub id: Uuid,
-    pub started_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
     pub ended_at: Option<DateTime<Utc>>,
     pub repair_type: String,
     pub status: String,
     pub description: String,
     pub building_address: String,
+    /// Версия записи; её же сервер ждёт в `If-Match` при удалении.
+    pub version: i32,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RepairList {
     pub repairs: Vec<Repair>,
+    #[serde(flatten)]
+    pub page_info: PageInfo,
+}
+
+#[derive(FromRow)]
+pub struct RepairRow {
+    pub id: Uuid,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub repair_type: RepairType,
+    pub status: Option<IncidentStatus>,
+    pub description: Option<String>,
+    pub building_number: i32,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub street: Option<String>,
+    pub version: i32,
+}
+
+impl ExportRow for RepairRow {
+    const SHEET_TITLE: &'static str = "Ремонты";
+    const HEADERS: &'static [&'static str] = &[
+        "Начало",
+        "Окончание",
+        "Вид ремонта",
+        "Статус аварии",
+        "Адрес",
+        "Описание",
+    ];
+
+    fn cells(self) -> Vec<Cell> {
+        vec![
+            self.started_at.map_or(Cell::Empty, Cell::DateTime),
+            self.ended_at.map_or(Cell::Empty, Cell::DateTime),
+            Cell::text(self.repair_type.to_string()),
+            self.status
+                .map_or(Cell::Empty, |status| Cell::text(status.to_string())),
+            Cell::Text(format!(
+                "{}, {}, {}, дом {}",
+                self.region.unwrap_or_default(),
+                self.city.unwrap_or_default(),
+                self.street.unwrap_or_default(),
+                self.building_number
+            )),
+            self.description.map_or(Cell::Empty, Cell::Text),
+        ]
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct RepairFilter {
+    pub repair_type: Option<RepairType>,
+    /// Только незаконченные (`true`) или только законченные (`false`) ремонты.
+    pub open: Option<bool>,
+    pub building_id: Option<Uuid>,
+}
+
+impl ListFilter for RepairFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(repair_type) = self.repair_type {
+            builder.push(" AND r.type = ").push_bind(repair_type);
+        }
+        match self.open {
+            Some(true) => {
+                builder.push(" AND r.ended_at IS NULL");
+            }
+            Some(false) => {
+                builder.push(" AND r.ended_at IS NOT NULL");
+            }
+            None => (),
+        }
+        if let Some(building_id) = self.building_id {
+            builder.push(" AND r.building_id = ").push_bind(building_id);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairSortField {
+    #[default]
+    StartedAt,
+    EndedAt,
+    RepairType,
+}
+
+impl SortField for RepairSortField {
+    fn expression(self) -> &'static str {
+        match self {
+            Self::StartedAt => "COALESCE(r.started_at, '-infinity')",
+            Self::EndedAt => "COALESCE(r.ended_at, 'infinity')",
+            Self::RepairType => "r.type",
+        }
+    }
+
+    fn default_order(self) -> SortOrder {
+        match self {
+            Self::StartedAt | Self::EndedAt => SortOrder::Desc,
+            Self::RepairType => SortOrder::Asc,
+        }
+    }
 }
@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Response,
+    routing::{delete, get},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::api::{
+    conditional::IfMatch,
+    envelope::{Envelope, ErrorEnvelope},
+    export::ExportParams,
+    extractor::AuthUser,
+    pagination::{ListQuery, PageParams, SortOrder},
+    ApiContext, Error,
+};
+
+use super::{
+    controllers,
+    models::{Repair, RepairFilter, RepairSortField},
+};
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/api/v2/repairs", get(get_all_repairs))
+        .route("/api/v2/repairs/export", get(export_repairs))
+        .route("/api/v2/repairs/:id", delete(delete_repair))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/repairs",
+    tag = "repairs",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<RepairSortField>, Query, description = "Поле сортировки"),
+        RepairFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница списка ремонтов", body = Envelope<Vec<Repair>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_all_repairs(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    query: ListQuery<RepairFilter, RepairSortField>,
+) -> Result<Json<Envelope<Vec<Repair>>>, Error> {
+    let Json(list) = controllers::get_all_repairs(user, ctx, query.paged()).await?;
+    Ok(Envelope::page(list.repairs, list.page_info))
+}
+
+/// Выгрузить все ремонты с фильтрами и сортировкой списка в CSV или XLSX.
+#[utoipa::path(
+    get,
+    path = "/api/v2/repairs/export",
+    tag = "repairs",
+    security(("bearer" = [])),
+    params(
+        ExportParams,
+        ("sortBy" = Option<RepairSortField>, Query, description = "Поле сортировки"),
+        ("sortOrder" = Option<SortOrder>, Query, description = "Порядок сортировки"),
+        RepairFilter,
+    ),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn export_repairs(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<ExportParams>,
+    query: ListQuery<RepairFilter, RepairSortField>,
+) -> Result<Response, Error> {
+    controllers::export_repairs(user, ctx, params, query).await
+}
+
+/// Удалить ремонт в корзину.
+#[utoipa::path(
+    delete,
+    path = "/api/v2/repairs/{id}",
+    tag = "repairs",
+    security(("bearer" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Идентификатор ремонта"),
+        ("If-Match" = String, Header, description = "`ETag` удаляемой версии"),
+    ),
+    responses(
+        (status = 204, description = "Ремонт удалён"),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 404, description = "Ремонт не найден", body = ErrorEnvelope),
+        (status = 412, description = "Ремонт уже изменили", body = ErrorEnvelope),
+        (status = 428, description = "Нет заголовка `If-Match`", body = ErrorEnvelope),
+    )
+)]
+pub async fn delete_repair(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    id: Path<Uuid>,
+    if_match: IfMatch,
+) -> Result<StatusCode, Error> {
+    controllers::delete_repair(user, ctx, id, if_match).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
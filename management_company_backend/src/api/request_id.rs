@@ -0,0 +1,118 @@
+use axum::{
+    body::Body,
+    http::{HeaderName, Request},
+};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tracing::Span;
+
+/// Идентификатор запроса: его передаёт клиент или прокси, иначе сервер создаёт UUID.
+/// Он возвращается в ответе и записывается в спан запроса, поэтому по нему находятся
+/// все строки журнала, относящиеся к жалобе пользователя.
+pub static REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Слои, которые присваивают запросу идентификатор и копируют его в ответ.
+pub fn layers() -> (SetRequestIdLayer<MakeRequestUuid>, PropagateRequestIdLayer) {
+    (
+        SetRequestIdLayer::new(REQUEST_ID.clone(), MakeRequestUuid),
+        PropagateRequestIdLayer::new(REQUEST_ID.clone()),
+    )
+}
+
+/// Спан запроса без заголовков и строки запроса: в них токены и персональные данные
+/// (поиск, фильтры по ФИО), поэтому в журнал попадает только путь.
+pub fn make_span(request: &Request<Body>) -> Span {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        version = ?request.version(),
+        request_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::http::StatusCode;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use super::*;
+    use crate::api::{api_router, test_context};
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Captured {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[sqlx::test]
+    async fn incoming_id_is_returned_and_logged_with_errors(pool: PgPool) {
+        let logs = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(logs.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let response = api_router(test_context(pool))
+            .oneshot(
+                Request::get("/api/v2/users/me")
+                    .header(&REQUEST_ID, "support-1234")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[&REQUEST_ID], "support-1234");
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let error = logs
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .find(|line| line["fields"]["error"] == "Authentication required")
+            .expect("the error is logged");
+        assert_eq!(error["span"]["request_id"], "support-1234");
+        assert!(!logs.contains("authorization"));
+    }
+
+    #[sqlx::test]
+    async fn missing_id_is_generated(pool: PgPool) {
+        let response = api_router(test_context(pool))
+            .oneshot(Request::get("/health").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let id = response.headers()[&REQUEST_ID].to_str().unwrap();
+        assert!(uuid::Uuid::parse_str(id).is_ok());
+    }
+}
@@ -0,0 +1,394 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use sqlx::{query, PgPool};
+
+use crate::api::{extractor::AuthUser, incident::models::IncidentStatus, ApiContext, Error};
+
+use super::models::{
+    BuildingSearchHit, EmployeeSearchHit, IncidentSearchHit, SearchParams, SearchResults,
+};
+
+const DEFAULT_LIMIT: i64 = 10;
+const MAX_LIMIT: i64 = 50;
+
+/// Границы совпадений, которые расставляет `ts_headline`. Это символы из области для частного
+/// использования: сам текст экранируется уже после выделения, и теги в нём отличать от
+/// выделения иначе нельзя.
+const MATCH_START: char = '\u{E000}';
+const MATCH_END: char = '\u{E001}';
+const HEADLINE_OPTIONS: &str = "StartSel=\u{E000}, StopSel=\u{E001}, MaxFragments=2";
+
+/// Полнотекстовый поиск по авариям, домам и сотрудникам; `headline` — экранированный HTML,
+/// в котором совпадения выделены `<mark>`.
+#[utoipa::path(
+    get,
+    path = "/api/search",
+    tag = "search",
+    security(("bearer" = [])),
+    params(SearchParams),
+    responses(
+        (status = 200, description = "Найденные аварии, дома и сотрудники", body = SearchResults),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Пустой запрос или неверный лимит"),
+    )
+)]
+pub async fn search(
+    _: AuthUser,
+    State(ctx): State<ApiContext>,
+    Query(params): Query<SearchParams>,
+) -> Result<Json<SearchResults>, Error> {
+    let q = params.q.trim();
+    if q.is_empty() {
+        return Err(Error::UnprocessableEntity(
+            "search query must not be empty".to_string(),
+        ));
+    }
+
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(Error::UnprocessableEntity(format!(
+            "limit must be between 1 and {MAX_LIMIT}"
+        )));
+    }
+
+    let (incidents, buildings, employees) = tokio::try_join!(
+        search_incidents(&ctx.db, q, limit),
+        search_buildings(&ctx.db, q, limit),
+        search_employees(&ctx.db, q, limit),
+    )?;
+
+    Ok(Json(SearchResults {
+        incidents,
+        buildings,
+        employees,
+    }))
+}
+
+/// Экранировать HTML во фрагменте из `ts_headline` и выделить совпадения тегом `<mark>`.
+fn highlight(headline: &str) -> String {
+    let mut html = String::with_capacity(headline.len());
+    for c in headline.chars() {
+        match c {
+            MATCH_START => html.push_str("<mark>"),
+            MATCH_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+/// Поиск аварий по описанию, названию типа аварии и адресу дома
+async fn search_incidents(
+    pool: &PgPool,
+    q: &str,
+    limit: i64,
+) -> Result<Vec<IncidentSearchHit>, Error> {
+    let rows = query!(
+        r#"
+        SELECT
+            i.id,
+            i.reported_at,
+            i.status AS "status: IncidentStatus",
+            it.name AS incident_type_name,
+            b.number AS building_number,
+            a.region,
+            a.city,
+            a.street,
+            ts_rank(i.search_vector || it.search_vector || a.search_vector, q) AS "rank!",
+            ts_headline(
+                'russian',
+                it.name || '. ' || coalesce(i.description, ''),
+                q,
+                $3
+            ) AS "headline!"
+        FROM
+            incident i
+        JOIN
+            incident_type it ON i.incident_type_id = it.id
+        JOIN
+            building b ON i.building_id = b.id
+        JOIN
+            address a ON b.address_id = a.id,
+            websearch_to_tsquery('russian', $1) q
+        WHERE
+            i.deleted_at IS NULL
+            -- Каждый вектор сопоставляется отдельно, чтобы работали их GIN-индексы.
+            AND (i.search_vector @@ q OR it.search_vector @@ q OR a.search_vector @@ q)
+        ORDER BY
+            "rank!" DESC, i.reported_at DESC
+        LIMIT $2
+        "#,
+        q,
+        limit,
+        HEADLINE_OPTIONS
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| IncidentSearchHit {
+            id: row.id,
+            incident_type_name: row.incident_type_name,
+            status: row.status.to_string(),
+            reported_at: row.reported_at,
+            building_address: format!(
+                "{}, {}, {}, дом {}",
+                row.region.unwrap_or_default(),
+                row.city.unwrap_or_default(),
+                row.street.unwrap_or_default(),
+                row.building_number
+            ),
+            headline: highlight(&row.headline),
+            rank: row.rank,
+        })
+        .collect())
+}
+
+/// Поиск домов по адресу и номеру дома
+async fn search_buildings(
+    pool: &PgPool,
+    q: &str,
+    limit: i64,
+) -> Result<Vec<BuildingSearchHit>, Error> {
+    let rows = query!(
+        r#"
+        SELECT
+            b.id,
+            b.number,
+            a.region,
+            a.city,
+            a.street,
+            ts_rank(a.search_vector || to_tsvector('simple', b.number::text), q) AS "rank!",
+            ts_headline(
+                'russian',
+                coalesce(a.city, '') || ', ' || coalesce(a.street, '') || ', дом ' || b.number,
+                q,
+                $3
+            ) AS "headline!"
+        FROM
+            building b
+        JOIN
+            address a ON b.address_id = a.id,
+            websearch_to_tsquery('russian', $1) q
+        WHERE
+            b.deleted_at IS NULL
+            AND (a.search_vector @@ q OR to_tsvector('simple', b.number::text) @@ q)
+        ORDER BY
+            "rank!" DESC, a.city, a.street, b.number
+        LIMIT $2
+        "#,
+        q,
+        limit,
+        HEADLINE_OPTIONS
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| BuildingSearchHit {
+            id: row.id,
+            address: format!(
+                "{}, {}, {}, дом {}",
+                row.region.unwrap_or_default(),
+                row.city.unwrap_or_default(),
+                row.street.unwrap_or_default(),
+                row.number
+            ),
+            headline: highlight(&row.headline),
+            rank: row.rank,
+        })
+        .collect())
+}
+
+/// Поиск сотрудников по ФИО
+async fn search_employees(
+    pool: &PgPool,
+    q: &str,
+    limit: i64,
+) -> Result<Vec<EmployeeSearchHit>, Error> {
+    let rows = query!(
+        r#"
+        SELECT
+            e.id,
+            e.last_name,
+            e.first_name,
+            e.middle_name,
+            p.name AS "position_name?",
+            ts_rank(e.search_vector, q) AS "rank!",
+            ts_headline(
+                'russian',
+                e.last_name || ' ' || e.first_name || ' ' || coalesce(e.middle_name, ''),
+                q,
+                $3
+            ) AS "headline!"
+        FROM
+            employee e
+        LEFT JOIN
+            position_at_work p ON e.position_id = p.id,
+            websearch_to_tsquery('russian', $1) q
+        WHERE
+            e.deleted_at IS NULL
+            AND e.search_vector @@ q
+        ORDER BY
+            "rank!" DESC, e.last_name, e.first_name
+        LIMIT $2
+        "#,
+        q,
+        limit,
+        HEADLINE_OPTIONS
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| EmployeeSearchHit {
+            id: row.id,
+            full_name: [Some(row.last_name), Some(row.first_name), row.middle_name]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" "),
+            position_name: row.position_name,
+            headline: highlight(&row.headline),
+            rank: row.rank,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{header::AUTHORIZATION, Request, StatusCode},
+        Router,
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::api::{api_router, test_context};
+
+    const PETROVA: Uuid = Uuid::from_u128(0xe2);
+    const LENINA_5: Uuid = Uuid::from_u128(0xb1);
+    const LEAK: Uuid = Uuid::from_u128(0x101);
+
+    async fn app(pool: PgPool) -> (Router, String) {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO user_account (email, password_hash, employee_id)
+            VALUES ('dispatcher@example.com', '', $1)
+            RETURNING id
+            "#,
+            PETROVA
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let ctx = test_context(pool);
+        let token = AuthUser { user_id }.to_jwt(&ctx);
+        (api_router(ctx), token)
+    }
+
+    async fn search(app: &Router, token: &str, q: &str) -> Value {
+        let url =
+            reqwest::Url::parse_with_params("http://localhost/api/search", [("q", q)]).unwrap();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get(format!("{}?{}", url.path(), url.query().unwrap()))
+                    .header(AUTHORIZATION, format!("Bearer {token}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn ids(hits: &Value) -> Vec<u128> {
+        hits.as_array()
+            .unwrap()
+            .iter()
+            .map(|hit| {
+                Uuid::parse_str(hit["id"].as_str().unwrap())
+                    .unwrap()
+                    .as_u128()
+            })
+            .collect()
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn other_word_forms_match(pool: PgPool) {
+        let (app, token) = app(pool).await;
+
+        // Тип аварии — «Протечка».
+        let results = search(&app, &token, "протечки").await;
+        assert_eq!(ids(&results["incidents"]), [0x201, 0x202]);
+
+        let results = search(&app, &token, "Петровой").await;
+        assert_eq!(ids(&results["employees"]), [0xe2]);
+
+        let results = search(&app, &token, "Ленина").await;
+        assert_eq!(ids(&results["buildings"]), [0xb1]);
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn more_relevant_hits_come_first(pool: PgPool) {
+        let (app, token) = app(pool).await;
+
+        // В аварии 0x201 протечка упомянута и в типе, и в описании.
+        let results = search(&app, &token, "протечка").await;
+        let incidents = &results["incidents"];
+        assert_eq!(ids(incidents), [0x201, 0x202]);
+        assert!(incidents[0]["rank"].as_f64() > incidents[1]["rank"].as_f64());
+        assert_eq!(
+            incidents[0]["headline"],
+            "<mark>Протечка</mark>. <mark>Протечка</mark> в подвале на Ленина"
+        );
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn headlines_escape_html(pool: PgPool) {
+        sqlx::query!(
+            r#"
+            INSERT INTO incident (building_id, status, description, incident_type_id)
+            VALUES ($1, 'reported', '<img src=x onerror=alert(1)> "давление" < 2 & протечка', $2)
+            "#,
+            LENINA_5,
+            LEAK
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let (app, token) = app(pool).await;
+
+        let results = search(&app, &token, "давление").await;
+        assert_eq!(
+            results["incidents"][0]["headline"],
+            "Протечка. &lt;img src=x onerror=alert(1)&gt; &quot;<mark>давление</mark>&quot; &lt; 2 &amp; протечка"
+        );
+    }
+
+    #[test]
+    fn only_match_markers_become_tags() {
+        assert_eq!(
+            highlight("\u{E000}a\u{E001} <b>'x'</b>"),
+            "<mark>a</mark> &lt;b&gt;&#39;x&#39;&lt;/b&gt;"
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use axum::{routing::get, Router};
+use controllers::search;
+use utoipa::OpenApi;
+
+use super::ApiContext;
+
+mod controllers;
+mod models;
+pub(crate) mod v2;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(controllers::search, v2::search),
+    tags((name = "search", description = "Поиск"))
+)]
+pub(crate) struct ApiDoc;
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new().route("/api/search", get(search))
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct SearchParams {
+    /// Поисковая строка.
+    pub q: String,
+    /// Сколько результатов каждого вида вернуть, от 1 до 50; по умолчанию 10.
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IncidentSearchHit {
+    pub id: Uuid,
+    pub incident_type_name: String,
+    pub status: String,
+    pub reported_at: Option<DateTime<Utc>>,
+    pub building_address: String,
+    pub headline: String,
+    pub rank: f32,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildingSearchHit {
+    pub id: Uuid,
+    pub address: String,
+    pub headline: String,
+    pub rank: f32,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmployeeSearchHit {
+    pub id: Uuid,
+    pub full_name: String,
+    pub position_name: Option<String>,
+    pub headline: String,
+    pub rank: f32,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    pub incidents: Vec<IncidentSearchHit>,
+    pub buildings: Vec<BuildingSearchHit>,
+    pub employees: Vec<EmployeeSearchHit>,
+}
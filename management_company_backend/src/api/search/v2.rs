@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+
+use crate::api::{
+    envelope::{Envelope, ErrorEnvelope},
+    extractor::AuthUser,
+    ApiContext, Error,
+};
+
+use super::{
+    controllers,
+    models::{SearchParams, SearchResults},
+};
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new().route("/api/v2/search", get(search))
+}
+
+/// Полнотекстовый поиск по авариям, домам и сотрудникам; `headline` — экранированный HTML,
+/// в котором совпадения выделены `<mark>`.
+#[utoipa::path(
+    get,
+    path = "/api/v2/search",
+    tag = "search",
+    security(("bearer" = [])),
+    params(SearchParams),
+    responses(
+        (status = 200, description = "Найденные аварии, дома и сотрудники", body = Envelope<SearchResults>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Пустой запрос или неверный лимит", body = ErrorEnvelope),
+    )
+)]
+pub async fn search(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<SearchParams>,
+) -> Result<Json<Envelope<SearchResults>>, Error> {
+    let Json(results) = controllers::search(user, ctx, params).await?;
+    Ok(Envelope::new(results))
+}
@@ -0,0 +1,195 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::api::Error;
+
+use super::models::{
+    BuildingStatistics, CostAttribution, ExpenseBreakdown, Period, RiskRanking,
+    SeasonalDecomposition, SummaryStatistics, TrendBreakdown, TrendForecast, TrendGranularity,
+    TrendMetric, TrendStatistics, YearOverYearTrends, YearOverviewStatistics,
+};
+
+/// Параметры запроса обзорной статистики. Период по умолчанию скользящий,
+/// поэтому ключом служат сами параметры, а не вычисленный по ним период.
+pub type YearOverviewKey = (
+    Option<i32>,
+    Option<NaiveDate>,
+    Option<NaiveDate>,
+    Option<Uuid>,
+    ExpenseBreakdown,
+);
+
+pub type SummaryKey = (Period, CostAttribution);
+
+pub type BuildingKey = (Uuid, Period, CostAttribution);
+
+pub type RiskKey = (Period, CostAttribution);
+
+pub type TrendKey = (Period, TrendGranularity, Option<Uuid>, TrendBreakdown);
+
+pub type YearOverYearKey = (i32, Option<Uuid>);
+
+pub type SeasonalityKey = (Period, TrendMetric, Option<Uuid>);
+
+/// Прогноз зависит от текущего месяца, поэтому он входит в ключ вместе с периодом истории.
+pub type ForecastKey = (NaiveDate, Period, Option<Uuid>);
+
+/// Кэш ответов статистики, сбрасываемый каждым обработчиком, который меняет данные статистики:
+/// аварии, ремонты, дома и сотрудников.
+///
+/// Кэш свой у каждого процесса. Запись, сделанная через другой экземпляр сервера или прямо в
+/// базе, становится видна здесь только по истечении `--statistics-cache-ttl-secs`.
+pub struct StatisticsCache {
+    pub year_overview: TtlCache<YearOverviewKey, YearOverviewStatistics>,
+    pub summary: TtlCache<SummaryKey, SummaryStatistics>,
+    pub building: TtlCache<BuildingKey, BuildingStatistics>,
+    pub risk: TtlCache<RiskKey, RiskRanking>,
+    pub trends: TtlCache<TrendKey, TrendStatistics>,
+    pub year_over_year: TtlCache<YearOverYearKey, YearOverYearTrends>,
+    pub seasonality: TtlCache<SeasonalityKey, SeasonalDecomposition>,
+    pub forecast: TtlCache<ForecastKey, TrendForecast>,
+}
+
+impl StatisticsCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            year_overview: TtlCache::new(ttl),
+            summary: TtlCache::new(ttl),
+            building: TtlCache::new(ttl),
+            risk: TtlCache::new(ttl),
+            trends: TtlCache::new(ttl),
+            year_over_year: TtlCache::new(ttl),
+            seasonality: TtlCache::new(ttl),
+            forecast: TtlCache::new(ttl),
+        }
+    }
+
+    pub fn invalidate(&self) {
+        self.year_overview.clear();
+        self.summary.clear();
+        self.building.clear();
+        self.risk.clear();
+        self.trends.clear();
+        self.year_over_year.clear();
+        self.seasonality.clear();
+        self.forecast.clear();
+    }
+}
+
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    generation: AtomicU64,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            generation: AtomicU64::new(0),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().expect("statistics cache lock poisoned");
+        match entries.get(key) {
+            Some((stored_at, value)) if stored_at.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Вернуть значение из кэша или вычислить его.
+    ///
+    /// Если кэш был сброшен, пока значение вычислялось, результат не сохраняется:
+    /// он мог быть посчитан по данным до записи.
+    pub async fn get_or_try_insert_with<F, Fut>(&self, key: K, compute: F) -> Result<V, Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, Error>>,
+    {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let value = compute().await?;
+
+        let mut entries = self.entries.lock().expect("statistics cache lock poisoned");
+        if self.generation.load(Ordering::Acquire) == generation {
+            entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+            entries.insert(key, (Instant::now(), value.clone()));
+        }
+
+        Ok(value)
+    }
+
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock().expect("statistics cache lock poisoned");
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn values_are_cached_until_ttl_expires() {
+        let cache = TtlCache::new(Duration::from_millis(50));
+
+        let first = cache.get_or_try_insert_with(1, || async { Ok(1) }).await;
+        let cached = cache.get_or_try_insert_with(1, || async { Ok(2) }).await;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let expired = cache.get_or_try_insert_with(1, || async { Ok(3) }).await;
+
+        assert_eq!(first.unwrap(), 1);
+        assert_eq!(cached.unwrap(), 1);
+        assert_eq!(expired.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn clear_drops_cached_values() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+
+        cache
+            .get_or_try_insert_with(1, || async { Ok(1) })
+            .await
+            .unwrap();
+        cache.clear();
+
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[tokio::test]
+    async fn value_computed_across_clear_is_not_stored() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+
+        let value = cache
+            .get_or_try_insert_with(1, || async {
+                cache.clear();
+                Ok(1)
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(value, 1);
+        assert_eq!(cache.get(&1), None);
+    }
+}
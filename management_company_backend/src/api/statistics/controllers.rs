@@ -1,35 +1,486 @@
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::{IntoResponse, Response},
     Json,
 };
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{Datelike, Utc};
+use uuid::Uuid;
 
-use crate::api::{extractor::AuthUser, ApiContext, Error};
+use crate::api::{
+    export::{sheets_response, ExportParams},
+    extractor::AuthUser,
+    ApiContext, Error,
+};
 
 use super::{
-    models::{QueryTimeDiapasonParams, SummaryStatistics, YearOverviewStatistics},
-    utils::{build_statistics_for_building, build_year_overview_statistics},
+    export::{summary_sheets, year_overview_sheets},
+    models::{
+        month_start, BuildingStatistics, ForecastParams, QueryTimeDiapasonParams, RiskRanking,
+        SeasonalDecomposition, SeasonalityParams, SummaryStatistics, TrendForecast, TrendParams,
+        TrendStatistics, YearOverYearParams, YearOverYearTrends, YearOverviewParams,
+        YearOverviewStatistics,
+    },
+    report::render_period_report,
+    utils::{
+        build_period_report, build_risk_ranking, build_seasonal_decomposition,
+        build_single_building_statistics, build_statistics_for_building, build_trend_forecast,
+        build_trend_statistics, build_year_over_year_trends, build_year_overview_statistics,
+    },
 };
 
+/// Сводка за год, за период или за последние двенадцать месяцев в сравнении с предыдущими.
+#[utoipa::path(
+    get,
+    path = "/api/statistics/year_overview",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(YearOverviewParams),
+    responses(
+        (status = 200, description = "Сводка за год или период", body = YearOverviewStatistics),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
 pub async fn get_year_overview_statistics(
     _: AuthUser,
     ctx: State<ApiContext>,
+    Query(params): Query<YearOverviewParams>,
 ) -> Result<Json<YearOverviewStatistics>, Error> {
-    let statistics = build_year_overview_statistics(&ctx.db).await?;
-    Ok(Json(statistics))
+    Ok(Json(year_overview(&ctx, &params).await?))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/statistics/year_overview/export",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(YearOverviewParams, ExportParams),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
+pub async fn export_year_overview_statistics(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    Query(params): Query<YearOverviewParams>,
+    Query(export): Query<ExportParams>,
+) -> Result<Response, Error> {
+    let statistics = year_overview(&ctx, &params).await?;
+    sheets_response(
+        &year_overview_sheets(&statistics),
+        export.format,
+        "year_overview",
+        ctx.config.timezone,
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/statistics/building",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(QueryTimeDiapasonParams),
+    responses(
+        (status = 200, description = "Статистика по всем домам за период", body = SummaryStatistics),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
 pub async fn get_building_statistics(
     _: AuthUser,
     ctx: State<ApiContext>,
     Query(params): Query<QueryTimeDiapasonParams>,
 ) -> Result<Json<SummaryStatistics>, Error> {
-    let start_date_time = params.start_date.and_hms(0, 0, 0);
-    let end_date_time = params.end_date.and_hms(0, 0, 0);
+    Ok(Json(summary(&ctx, &params).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/statistics/building/export",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(QueryTimeDiapasonParams, ExportParams),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
+pub async fn export_building_statistics(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    Query(params): Query<QueryTimeDiapasonParams>,
+    Query(export): Query<ExportParams>,
+) -> Result<Response, Error> {
+    let statistics = summary(&ctx, &params).await?;
+    sheets_response(
+        &summary_sheets(&statistics),
+        export.format,
+        &format!("statistics_{}_{}", params.start_date, params.end_date),
+        ctx.config.timezone,
+    )
+}
+
+/// Печатный отчёт за период в PDF.
+#[utoipa::path(
+    get,
+    path = "/api/statistics/building/report",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(QueryTimeDiapasonParams),
+    responses(
+        (status = 200, description = "Печатный отчёт", body = Vec<u8>, content_type = "application/pdf"),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
+pub async fn get_period_report(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    Query(params): Query<QueryTimeDiapasonParams>,
+) -> Result<Response, Error> {
+    let period = params.period(ctx.config.timezone)?;
+    let report = ctx
+        .metrics
+        .time_statistics(
+            "period_report",
+            build_period_report(
+                &ctx.db,
+                period,
+                params.cost_attribution,
+                ctx.config.timezone,
+                &ctx.config.currency,
+            ),
+        )
+        .await?;
+
+    let pdf = render_period_report(
+        &report,
+        params.start_date,
+        params.end_date,
+        params.cost_attribution,
+        ctx.config.timezone,
+        Utc::now(),
+    )?;
+    Ok((
+        [
+            (CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"report_{}_{}.pdf\"",
+                    params.start_date, params.end_date
+                ),
+            ),
+        ],
+        pdf,
+    )
+        .into_response())
+}
+
+async fn year_overview(
+    ctx: &ApiContext,
+    params: &YearOverviewParams,
+) -> Result<YearOverviewStatistics, Error> {
+    let (current, previous) = params.periods(Utc::now(), ctx.config.timezone)?;
+    let key = (
+        params.year,
+        params.start_date,
+        params.end_date,
+        params.building_id,
+        params.expense_breakdown(),
+    );
+
+    ctx.statistics_cache
+        .year_overview
+        .get_or_try_insert_with(key, || {
+            ctx.metrics.time_statistics(
+                "year_overview",
+                build_year_overview_statistics(
+                    &ctx.db,
+                    current,
+                    previous,
+                    params.building_id,
+                    &ctx.config.currency,
+                    ctx.config.timezone,
+                    params.expense_breakdown(),
+                ),
+            )
+        })
+        .await
+}
 
-    let start_date_utc: DateTime<Utc> = Utc.from_utc_datetime(&start_date_time);
-    let end_date_utc: DateTime<Utc> = Utc.from_utc_datetime(&end_date_time);
+async fn summary(
+    ctx: &ApiContext,
+    params: &QueryTimeDiapasonParams,
+) -> Result<SummaryStatistics, Error> {
+    let period = params.period(ctx.config.timezone)?;
+
+    ctx.statistics_cache
+        .summary
+        .get_or_try_insert_with((period, params.cost_attribution), || {
+            ctx.metrics.time_statistics(
+                "summary",
+                build_statistics_for_building(
+                    &ctx.db,
+                    period,
+                    params.cost_attribution,
+                    &ctx.config.currency,
+                ),
+            )
+        })
+        .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/statistics/building/{id}",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "Идентификатор дома"), QueryTimeDiapasonParams),
+    responses(
+        (status = 200, description = "Статистика дома за период", body = BuildingStatistics),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
+pub async fn get_single_building_statistics(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<QueryTimeDiapasonParams>,
+) -> Result<Json<BuildingStatistics>, Error> {
+    let period = params.period(ctx.config.timezone)?;
 
-    let report = build_statistics_for_building(&ctx.db, start_date_utc, end_date_utc).await?;
+    let report = ctx
+        .statistics_cache
+        .building
+        .get_or_try_insert_with((id, period, params.cost_attribution), || {
+            ctx.metrics.time_statistics(
+                "building",
+                build_single_building_statistics(
+                    &ctx.db,
+                    id,
+                    period,
+                    params.cost_attribution,
+                    ctx.config.timezone,
+                    &ctx.config.currency,
+                ),
+            )
+        })
+        .await?;
     Ok(Json(report))
 }
+
+/// Дома, упорядоченные по риску аварий, с вкладом каждого фактора в оценку.
+#[utoipa::path(
+    get,
+    path = "/api/statistics/risk",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(QueryTimeDiapasonParams),
+    responses(
+        (status = 200, description = "Дома по убыванию оценки риска", body = RiskRanking),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
+pub async fn get_risk_ranking(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    Query(params): Query<QueryTimeDiapasonParams>,
+) -> Result<Json<RiskRanking>, Error> {
+    let period = params.period(ctx.config.timezone)?;
+
+    let ranking = ctx
+        .statistics_cache
+        .risk
+        .get_or_try_insert_with((period, params.cost_attribution), || {
+            ctx.metrics.time_statistics(
+                "risk",
+                build_risk_ranking(
+                    &ctx.db,
+                    period,
+                    params.cost_attribution,
+                    &ctx.config.currency,
+                ),
+            )
+        })
+        .await?;
+    Ok(Json(ranking))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/statistics/trends",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(TrendParams),
+    responses(
+        (status = 200, description = "Ряды аварий и расходов", body = TrendStatistics),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
+pub async fn get_trend_statistics(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    Query(params): Query<TrendParams>,
+) -> Result<Json<TrendStatistics>, Error> {
+    let period = params.period(ctx.config.timezone)?;
+    let key = (
+        period,
+        params.granularity,
+        params.building_id,
+        params.breakdown(),
+    );
+
+    let trends = ctx
+        .statistics_cache
+        .trends
+        .get_or_try_insert_with(key, || {
+            ctx.metrics.time_statistics(
+                "trends",
+                build_trend_statistics(
+                    &ctx.db,
+                    period,
+                    params.granularity,
+                    params.building_id,
+                    params.breakdown(),
+                    &ctx.config.currency,
+                    ctx.config.timezone,
+                ),
+            )
+        })
+        .await?;
+    Ok(Json(trends))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/statistics/trends/year_over_year",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(YearOverYearParams),
+    responses(
+        (status = 200, description = "Помесячное сравнение года с предыдущим", body = YearOverYearTrends),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
+pub async fn get_year_over_year_trends(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    Query(params): Query<YearOverYearParams>,
+) -> Result<Json<YearOverYearTrends>, Error> {
+    let year = params
+        .year
+        .unwrap_or_else(|| Utc::now().with_timezone(&ctx.config.timezone).year());
+
+    let trends = ctx
+        .statistics_cache
+        .year_over_year
+        .get_or_try_insert_with((year, params.building_id), || {
+            ctx.metrics.time_statistics(
+                "year_over_year",
+                build_year_over_year_trends(
+                    &ctx.db,
+                    year,
+                    params.building_id,
+                    &ctx.config.currency,
+                    ctx.config.timezone,
+                ),
+            )
+        })
+        .await?;
+    Ok(Json(trends))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/statistics/trends/seasonality",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(SeasonalityParams),
+    responses(
+        (status = 200, description = "Тренд, сезонность и остаток по месяцам", body = SeasonalDecomposition),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
+pub async fn get_seasonal_decomposition(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    Query(params): Query<SeasonalityParams>,
+) -> Result<Json<SeasonalDecomposition>, Error> {
+    let month = month_start(Utc::now(), ctx.config.timezone);
+    let period = params.period(month, ctx.config.timezone)?;
+
+    let decomposition = ctx
+        .statistics_cache
+        .seasonality
+        .get_or_try_insert_with((period, params.metric, params.building_id), || {
+            ctx.metrics.time_statistics(
+                "seasonality",
+                build_seasonal_decomposition(
+                    &ctx.db,
+                    period,
+                    params.metric,
+                    params.building_id,
+                    &ctx.config.currency,
+                    ctx.config.timezone,
+                ),
+            )
+        })
+        .await?;
+    Ok(Json(decomposition))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/statistics/trends/forecast",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(ForecastParams),
+    responses(
+        (status = 200, description = "Прогноз на текущий и следующий квартал", body = TrendForecast),
+        (status = 401, description = "Нет действующего токена"),
+        (status = 422, description = "Неверный период или параметры"),
+    )
+)]
+pub async fn get_trend_forecast(
+    _: AuthUser,
+    ctx: State<ApiContext>,
+    Query(params): Query<ForecastParams>,
+) -> Result<Json<TrendForecast>, Error> {
+    let month = month_start(Utc::now(), ctx.config.timezone);
+    let history = params.period(month, ctx.config.timezone)?;
+
+    let forecast = ctx
+        .statistics_cache
+        .forecast
+        .get_or_try_insert_with((month, history, params.building_id), || {
+            ctx.metrics.time_statistics(
+                "forecast",
+                build_trend_forecast(
+                    &ctx.db,
+                    month,
+                    history,
+                    params.building_id,
+                    &ctx.config.currency,
+                    ctx.config.timezone,
+                ),
+            )
+        })
+        .await?;
+    Ok(Json(forecast))
+}
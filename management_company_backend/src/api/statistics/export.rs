@@ -0,0 +1,222 @@
+use crate::api::export::{Cell, Sheet};
+
+use super::models::{SummaryStatistics, YearOverviewStatistics};
+
+/// Разделы обзорной статистики для выгрузки; разбивки расходов попадают в файл, только если запрошены.
+pub fn year_overview_sheets(statistics: &YearOverviewStatistics) -> Vec<Sheet> {
+    let overview = Sheet {
+        title: "Обзор",
+        headers: &["Показатель", "Значение", "Изменение"],
+        rows: vec![
+            vec![
+                Cell::Text(format!("Расходы, {}", statistics.currency)),
+                Cell::Money(statistics.total_expenses_last_year),
+                Cell::percent(&statistics.percent_changes_in_expense_from_last_year),
+            ],
+            vec![
+                Cell::text("Ремонты"),
+                Cell::Integer(statistics.count_of_repairs_last_year),
+                Cell::percent(&statistics.percent_changes_in_count_repair_last_year),
+            ],
+            vec![
+                Cell::text("Активные заявки на ремонт"),
+                Cell::Integer(statistics.count_of_active_repair_requests),
+                Cell::percent(&statistics.percent_changes_in_active_repair_requests_last_year),
+            ],
+            vec![
+                Cell::text("Сотрудники"),
+                Cell::Integer(statistics.count_of_employees),
+            ],
+            vec![
+                Cell::text("Новые сотрудники"),
+                Cell::Integer(statistics.count_new_employee_last_year),
+            ],
+            vec![
+                Cell::text("Аварии"),
+                Cell::Integer(statistics.total_incidents_last_year),
+            ],
+        ],
+    };
+
+    let months = &statistics.expense_distribution_by_month_last_year;
+    let monthly = Sheet {
+        title: "Расходы по месяцам",
+        headers: &["Месяц", "Сумма"],
+        rows: months
+            .iter()
+            .map(|month| vec![Cell::text(month.month.as_str()), Cell::Money(month.total)])
+            .collect(),
+    };
+
+    let incident_types = Sheet {
+        title: "Типы аварий",
+        headers: &["Тип аварии", "Количество", "Доля"],
+        rows: statistics
+            .top_5_incident_types_last_year
+            .iter()
+            .map(|info| {
+                vec![
+                    Cell::text(info.name.as_str()),
+                    Cell::Integer(info.count),
+                    Cell::percent(&info.percentage),
+                ]
+            })
+            .collect(),
+    };
+
+    let mut sheets = vec![overview, monthly];
+    if months.iter().any(|month| month.by_operation_type.is_some()) {
+        sheets.push(Sheet {
+            title: "Расходы по типам операций",
+            headers: &["Месяц", "Тип операции", "Сумма"],
+            rows: months
+                .iter()
+                .flat_map(|month| {
+                    month.by_operation_type.iter().flatten().map(|item| {
+                        vec![
+                            Cell::text(month.month.as_str()),
+                            Cell::text(item.operation_type.to_string()),
+                            Cell::Money(item.total),
+                        ]
+                    })
+                })
+                .collect(),
+        });
+    }
+    if months.iter().any(|month| month.by_building.is_some()) {
+        sheets.push(Sheet {
+            title: "Расходы по домам",
+            headers: &["Месяц", "Дом", "Сумма"],
+            rows: months
+                .iter()
+                .flat_map(|month| {
+                    month.by_building.iter().flatten().map(|item| {
+                        vec![
+                            Cell::text(month.month.as_str()),
+                            item.building_number.map_or_else(
+                                || Cell::text("Без дома"),
+                                |number| Cell::Integer(number.into()),
+                            ),
+                            Cell::Money(item.total),
+                        ]
+                    })
+                })
+                .collect(),
+        });
+    }
+    sheets.push(incident_types);
+
+    sheets
+}
+
+pub fn summary_sheets(statistics: &SummaryStatistics) -> Vec<Sheet> {
+    vec![
+        Sheet {
+            title: "Сводка",
+            headers: &["Показатель", "Значение"],
+            rows: vec![
+                vec![
+                    Cell::text("Аварии"),
+                    Cell::Integer(statistics.total_incidents),
+                ],
+                vec![
+                    Cell::Text(format!("Затраты, {}", statistics.currency)),
+                    Cell::Money(statistics.total_cost),
+                ],
+                vec![
+                    Cell::text("Аварийные ремонты"),
+                    Cell::Integer(statistics.repair_counts.emergency_repairs),
+                ],
+                vec![
+                    Cell::text("Плановые ремонты"),
+                    Cell::Integer(statistics.repair_counts.scheduled_repairs),
+                ],
+                vec![
+                    Cell::text("Всего ремонтов"),
+                    Cell::Integer(statistics.repair_counts.total),
+                ],
+            ],
+        },
+        Sheet {
+            title: "Затраты по типам аварий",
+            headers: &["Тип аварии", "Затраты"],
+            rows: statistics
+                .incident_costs
+                .iter()
+                .map(|cost| {
+                    vec![
+                        Cell::text(cost.incident_type.as_str()),
+                        Cell::Money(cost.total_cost),
+                    ]
+                })
+                .collect(),
+        },
+        Sheet {
+            title: "Дома по затратам на ремонт",
+            headers: &["Дом", "Затраты", "Ремонтов"],
+            rows: statistics
+                .top_buildings_by_repair_cost
+                .iter()
+                .map(|building| {
+                    vec![
+                        Cell::Integer(building.building_number.into()),
+                        Cell::Money(building.total_cost),
+                        Cell::Integer(building.repair_count),
+                    ]
+                })
+                .collect(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::{financial_operation::models::FinancialOperationType, money::Money};
+
+    use super::{
+        super::models::{MonthlyExpenses, OperationTypeExpenses},
+        *,
+    };
+
+    #[test]
+    fn operation_types_are_named_in_russian() {
+        let statistics = YearOverviewStatistics {
+            currency: "RUB".to_string(),
+            total_expenses_last_year: Money::default(),
+            percent_changes_in_expense_from_last_year: "0.00%".to_string(),
+            count_of_repairs_last_year: 0,
+            percent_changes_in_count_repair_last_year: "0.00%".to_string(),
+            count_of_active_repair_requests: 0,
+            percent_changes_in_active_repair_requests_last_year: "0.00%".to_string(),
+            count_of_employees: 0,
+            count_new_employee_last_year: 0,
+            expense_distribution_by_month_last_year: vec![MonthlyExpenses {
+                month: "2024-06".to_string(),
+                name: "Июнь".to_string(),
+                total: Money::default(),
+                by_operation_type: Some(vec![
+                    OperationTypeExpenses {
+                        operation_type: FinancialOperationType::Withdrawal,
+                        total: Money::default(),
+                    },
+                    OperationTypeExpenses {
+                        operation_type: FinancialOperationType::Payment,
+                        total: Money::default(),
+                    },
+                ]),
+                by_building: None,
+            }],
+            total_incidents_last_year: 0,
+            top_5_incident_types_last_year: Vec::new(),
+        };
+
+        let sheets = year_overview_sheets(&statistics);
+
+        let by_type = sheets
+            .iter()
+            .find(|sheet| sheet.title == "Расходы по типам операций")
+            .unwrap();
+        let names: Vec<&Cell> = by_type.rows.iter().map(|row| &row[1]).collect();
+        assert_eq!(names, [&Cell::text("Списание"), &Cell::text("Оплата")]);
+    }
+}
@@ -0,0 +1,34 @@
+INSERT INTO position_at_work (id, name, salary) VALUES ('00000000-0000-0000-0000-000000000001','Диспетчер', 50000), ('00000000-0000-0000-0000-000000000002','Сантехник', 60000);
+INSERT INTO passport (id, series, number) VALUES ('00000000-0000-0000-0000-0000000000a1',1234,123456),('00000000-0000-0000-0000-0000000000a2',1234,123457),('00000000-0000-0000-0000-0000000000a3',1234,123458);
+INSERT INTO employee (id,last_name,first_name,passport_id,position_id,gender,email,started_at) VALUES
+ ('00000000-0000-0000-0000-0000000000e1','Иванов','Иван','00000000-0000-0000-0000-0000000000a1','00000000-0000-0000-0000-000000000001','male','ivanov@example.com','2023-03-01'),
+ ('00000000-0000-0000-0000-0000000000e2','Петрова','Анна','00000000-0000-0000-0000-0000000000a2','00000000-0000-0000-0000-000000000002','female','petrova@example.com','2024-02-01'),
+ ('00000000-0000-0000-0000-0000000000e3','Сидоров','Олег','00000000-0000-0000-0000-0000000000a3','00000000-0000-0000-0000-000000000002','male','sidorov@example.com','2024-05-01');
+INSERT INTO committee (id,name,start_date) VALUES ('00000000-0000-0000-0000-0000000000c1','Совет дома','2020-01-01');
+INSERT INTO committee_employee VALUES ('00000000-0000-0000-0000-0000000000c1','00000000-0000-0000-0000-0000000000e2');
+INSERT INTO address (id,country,region,city,street) VALUES ('00000000-0000-0000-0000-0000000000d1','Россия','Московская область','Москва','улица Ленина'),('00000000-0000-0000-0000-0000000000d2','Россия','Московская область','Химки','Московская улица');
+INSERT INTO building (id,committee_id,address_id,number,construction_date,number_of_floors) VALUES
+ ('00000000-0000-0000-0000-0000000000b1','00000000-0000-0000-0000-0000000000c1','00000000-0000-0000-0000-0000000000d1',5,'1975-06-01',9),
+ ('00000000-0000-0000-0000-0000000000b2',NULL,'00000000-0000-0000-0000-0000000000d2',12,'2005-09-01',16);
+INSERT INTO owner (id,last_name,first_name) VALUES ('00000000-0000-0000-0000-0000000000f1','Смирнов','Пётр');
+INSERT INTO apartment (building_id,owner_id,number,floor,square_metres) VALUES
+ ('00000000-0000-0000-0000-0000000000b1','00000000-0000-0000-0000-0000000000f1',1,1,50),('00000000-0000-0000-0000-0000000000b1',NULL,2,1,70),
+ ('00000000-0000-0000-0000-0000000000b2',NULL,1,1,100);
+INSERT INTO incident_type (id,name) VALUES ('00000000-0000-0000-0000-000000000101','Протечка'),('00000000-0000-0000-0000-000000000102','Отключение электричества');
+INSERT INTO incident (id,building_id,reported_at,resolved_at,status,description,incident_type_id) VALUES
+ ('00000000-0000-0000-0000-000000000201','00000000-0000-0000-0000-0000000000b1','2023-03-10 10:00+00','2023-03-12 10:00+00','closed','Протечка в подвале на Ленина','00000000-0000-0000-0000-000000000101'),
+ ('00000000-0000-0000-0000-000000000202','00000000-0000-0000-0000-0000000000b1','2024-01-15 10:00+00',NULL,'in_progress','Течёт крыша','00000000-0000-0000-0000-000000000101'),
+ ('00000000-0000-0000-0000-000000000203','00000000-0000-0000-0000-0000000000b2','2024-03-20 10:00+00','2024-03-21 10:00+00','resolved','Нет света в подъезде','00000000-0000-0000-0000-000000000102'),
+ ('00000000-0000-0000-0000-000000000204','00000000-0000-0000-0000-0000000000b2','2024-03-31 20:00+00',NULL,'reported','Искрит щиток','00000000-0000-0000-0000-000000000102');
+INSERT INTO repair (id,incident_id,building_id,started_at,ended_at,type) VALUES
+ ('00000000-0000-0000-0000-000000000301','00000000-0000-0000-0000-000000000201','00000000-0000-0000-0000-0000000000b1','2023-03-10 12:00+00','2023-03-12 10:00+00','emergency'),
+ ('00000000-0000-0000-0000-000000000302','00000000-0000-0000-0000-000000000202','00000000-0000-0000-0000-0000000000b1','2024-01-16 12:00+00',NULL,'emergency'),
+ ('00000000-0000-0000-0000-000000000303','00000000-0000-0000-0000-000000000203','00000000-0000-0000-0000-0000000000b2','2024-03-20 12:00+00','2024-03-21 10:00+00','emergency'),
+ ('00000000-0000-0000-0000-000000000304',NULL,'00000000-0000-0000-0000-0000000000b2','2024-06-01 09:00+00','2024-06-10 18:00+00','scheduled');
+INSERT INTO financial_operation (amount,happen_at,description,repair_id,type) VALUES
+ (15000.50,'2023-03-12 11:00+00','Материалы','00000000-0000-0000-0000-000000000301','payment'),
+ (20000,'2024-01-20 11:00+00','Кровля','00000000-0000-0000-0000-000000000302','payment'),
+ (5000.25,'2024-03-21 11:00+00','Электрик','00000000-0000-0000-0000-000000000303','payment'),
+ (3000,'2024-04-02 11:00+00','Доплата электрику','00000000-0000-0000-0000-000000000303','payment'),
+ (40000,'2024-06-10 18:00+00','Плановый ремонт','00000000-0000-0000-0000-000000000304','withdrawal'),
+ (100000,'2024-01-01 00:00+00','Взносы',NULL,'deposit');
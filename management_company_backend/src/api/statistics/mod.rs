@@ -1,11 +1,54 @@
 use axum::{routing::get, Router};
-use controllers::{get_building_statistics, get_year_overview_statistics};
+use controllers::{
+    export_building_statistics, export_year_overview_statistics, get_building_statistics,
+    get_period_report, get_risk_ranking, get_seasonal_decomposition,
+    get_single_building_statistics, get_trend_forecast, get_trend_statistics,
+    get_year_over_year_trends, get_year_overview_statistics,
+};
+use utoipa::OpenApi;
 
 use super::ApiContext;
 
+pub mod cache;
 mod controllers;
+mod export;
 mod models;
+mod report;
+mod risk;
+mod trends;
 mod utils;
+pub(crate) mod v2;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::get_year_overview_statistics,
+        controllers::export_year_overview_statistics,
+        controllers::get_building_statistics,
+        controllers::export_building_statistics,
+        controllers::get_period_report,
+        controllers::get_single_building_statistics,
+        controllers::get_risk_ranking,
+        controllers::get_trend_statistics,
+        controllers::get_year_over_year_trends,
+        controllers::get_seasonal_decomposition,
+        controllers::get_trend_forecast,
+        v2::get_year_overview_statistics,
+        v2::export_year_overview_statistics,
+        v2::get_building_statistics,
+        v2::export_building_statistics,
+        v2::get_period_report,
+        v2::get_single_building_statistics,
+        v2::get_risk_ranking,
+        v2::get_trend_statistics,
+        v2::get_year_over_year_trends,
+        v2::get_seasonal_decomposition,
+        v2::get_trend_forecast,
+    ),
+    components(schemas(models::CostAttribution)),
+    tags((name = "statistics", description = "Статистика, тренды, прогнозы и отчёты"))
+)]
+pub(crate) struct ApiDoc;
 
 pub(crate) fn router() -> Router<ApiContext> {
     Router::new()
@@ -13,5 +56,29 @@ pub(crate) fn router() -> Router<ApiContext> {
             "/api/statistics/year_overview",
             get(get_year_overview_statistics),
         )
+        .route(
+            "/api/statistics/year_overview/export",
+            get(export_year_overview_statistics),
+        )
         .route("/api/statistics/building", get(get_building_statistics))
+        .route(
+            "/api/statistics/building/export",
+            get(export_building_statistics),
+        )
+        .route("/api/statistics/building/report", get(get_period_report))
+        .route(
+            "/api/statistics/building/:id",
+            get(get_single_building_statistics),
+        )
+        .route("/api/statistics/risk", get(get_risk_ranking))
+        .route("/api/statistics/trends", get(get_trend_statistics))
+        .route(
+            "/api/statistics/trends/year_over_year",
+            get(get_year_over_year_trends),
+        )
+        .route(
+            "/api/statistics/trends/seasonality",
+            get(get_seasonal_decomposition),
+        )
+        .route("/api/statistics/trends/forecast", get(get_trend_forecast))
 }
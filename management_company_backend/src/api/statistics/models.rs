@@ -1,18 +1,46 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use serde::{de, Deserialize, Deserializer, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize)]
+use crate::api::{financial_operation::models::FinancialOperationType, money::Money, Error};
+
+/// Расходы за один календарный месяц; месяцы без расходов присутствуют с нулевой суммой.
+#[derive(Debug, Clone, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MonthlyExpenses {
+    /// Месяц в формате `YYYY-MM`.
+    pub month: String,
     pub name: String,
-    pub total: i32,
+    pub total: Money,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_operation_type: Option<Vec<OperationTypeExpenses>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_building: Option<Vec<BuildingExpenses>>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationTypeExpenses {
+    pub operation_type: FinancialOperationType,
+    pub total: Money,
+}
+
+/// Расходы по дому; операции, не привязанные к ремонту, попадают в строку без дома.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildingExpenses {
+    pub building_id: Option<Uuid>,
+    pub building_number: Option<i32>,
+    pub total: Money,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct YearOverviewStatistics {
-    pub total_expenses_last_year: String,
+    pub currency: String,
+    pub total_expenses_last_year: Money,
     pub percent_changes_in_expense_from_last_year: String,
     pub count_of_repairs_last_year: i64,
     pub percent_changes_in_count_repair_last_year: String,
@@ -25,7 +53,7 @@ pub struct YearOverviewStatistics {
     pub top_5_incident_types_last_year: Vec<IncidentTypeInfo>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IncidentTypeInfo {
     pub id: Uuid,
@@ -34,21 +62,21 @@ pub struct IncidentTypeInfo {
     pub percentage: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IncidentCost {
     pub incident_type: String,
-    pub total_cost: f64,
+    pub total_cost: Money,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BuildingSummary {
     pub total_incidents: i64,
-    pub total_cost: f64,
+    pub total_cost: Money,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RepairCount {
     pub emergency_repairs: i64,
@@ -56,16 +84,299 @@ pub struct RepairCount {
     pub total: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SummaryStatistics {
+    pub currency: String,
     pub total_incidents: i64,
-    pub total_cost: f64,
+    pub total_cost: Money,
     pub repair_counts: RepairCount,
     pub incident_costs: Vec<IncidentCost>,
     pub top_buildings_by_repair_cost: Vec<BuildingRepairCost>,
 }
 
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IncidentTypeCount {
+    pub incident_type: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IncidentStatusCount {
+    pub status: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildingMonthStatistics {
+    pub month: String,
+    pub incidents: i64,
+    pub repairs: i64,
+    pub total_cost: Money,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildingStatistics {
+    pub building_id: Uuid,
+    pub building_number: i32,
+    pub address: String,
+    pub currency: String,
+    pub total_incidents: i64,
+    pub total_cost: Money,
+    pub total_square_metres: f64,
+    pub cost_per_square_metre: Option<Money>,
+    pub mean_hours_to_resolve: Option<f64>,
+    pub repair_counts: RepairCount,
+    pub incident_costs: Vec<IncidentCost>,
+    pub incident_counts_by_type: Vec<IncidentTypeCount>,
+    pub incident_counts_by_status: Vec<IncidentStatusCount>,
+    pub monthly: Vec<BuildingMonthStatistics>,
+}
+
+/// Данные печатного отчёта за период.
+pub struct PeriodReport {
+    pub summary: SummaryStatistics,
+    pub incidents_by_type: Vec<IncidentTypeCount>,
+    /// Расходы относятся к месяцам по дате операции независимо от способа отнесения затрат.
+    pub monthly_expenses: Vec<MonthlyExpenses>,
+}
+
+/// Шаг временного ряда.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TrendGranularity {
+    /// Неделя по ISO, с понедельника.
+    Week,
+    #[default]
+    Month,
+}
+
+impl TrendGranularity {
+    /// Единица шага для `period_buckets`.
+    pub fn unit(self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    /// Подпись шага, начинающегося с `start`: `2024-W03` или `2024-03`.
+    pub fn label(self, start: NaiveDate) -> String {
+        match self {
+            Self::Week => start.format("%G-W%V").to_string(),
+            Self::Month => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// Показатель, по которому строится сезонная декомпозиция.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TrendMetric {
+    #[default]
+    Incidents,
+    RepairSpend,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ForecastMethod {
+    /// Сглаживание Хольта ряда без сезонности с возвратом сезонной поправки.
+    SeasonalExponentialSmoothing,
+    /// Сглаживание Хольта без учёта сезонности, когда истории меньше двух лет.
+    ExponentialSmoothing,
+}
+
+/// Аварии и расходы на ремонт за один шаг ряда; шаги без событий присутствуют с нулями.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    pub start: NaiveDate,
+    pub label: String,
+    pub incidents: i64,
+    /// Расходы по ремонтам, отнесённые к шагу по дате операции.
+    pub repair_spend: Money,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendCount {
+    pub start: NaiveDate,
+    pub label: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IncidentTypeTrend {
+    pub incident_type_id: Option<Uuid>,
+    pub incident_type: Option<String>,
+    pub points: Vec<TrendCount>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildingTrend {
+    pub building_id: Uuid,
+    pub building_number: i32,
+    pub points: Vec<TrendCount>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendStatistics {
+    pub granularity: TrendGranularity,
+    pub currency: String,
+    pub series: Vec<TrendPoint>,
+    /// Ряды аварий по типам, которые встречались за период.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_incident_type: Option<Vec<IncidentTypeTrend>>,
+    /// Ряды аварий по домам, в которых были аварии за период.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_building: Option<Vec<BuildingTrend>>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct YearOverYearValue<T> {
+    pub current: T,
+    pub previous: T,
+    pub change: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct YearOverYearMonth {
+    /// Номер месяца, от 1 до 12.
+    pub month: u32,
+    pub incidents: YearOverYearValue<i64>,
+    pub repair_spend: YearOverYearValue<Money>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct YearOverYearTrends {
+    pub currency: String,
+    pub year: i32,
+    pub previous_year: i32,
+    pub incidents: YearOverYearValue<i64>,
+    pub repair_spend: YearOverYearValue<Money>,
+    pub months: Vec<YearOverYearMonth>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DecomposedPoint {
+    pub start: NaiveDate,
+    pub label: String,
+    pub observed: f64,
+    /// Годовое скользящее среднее; по полгода с краёв ряда не определено.
+    pub trend: Option<f64>,
+    pub seasonal: f64,
+    pub residual: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonalIndex {
+    /// Номер месяца, от 1 до 12.
+    pub month: u32,
+    /// На сколько месяц в среднем выше (или ниже) тренда.
+    pub index: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonalDecomposition {
+    pub metric: TrendMetric,
+    pub currency: String,
+    pub months: Vec<DecomposedPoint>,
+    pub seasonal_indices: Vec<SeasonalIndex>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastPoint {
+    pub start: NaiveDate,
+    pub label: String,
+    pub value: f64,
+}
+
+/// Прогноз одного показателя. Значения — оценки, округлённые до двух знаков.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricForecast {
+    pub method: ForecastMethod,
+    /// Все месяцы от текущего до конца следующего квартала.
+    pub months: Vec<ForecastPoint>,
+    pub quarter_total: f64,
+    /// Среднее за последние три полных месяца, умноженное на три, — простейшая оценка для сравнения.
+    pub moving_average_total: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendForecast {
+    pub currency: String,
+    /// Сколько полных месяцев истории легло в основу прогноза.
+    pub history_months: u32,
+    /// Прогнозируемый квартал, например `2025-Q1`.
+    pub quarter: String,
+    pub quarter_start: NaiveDate,
+    pub incidents: MetricForecast,
+    pub repair_spend: MetricForecast,
+}
+
+/// Фактор, влияющий на оценку риска дома.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RiskFactorKind {
+    /// Аварий в год за период.
+    IncidentFrequency,
+    /// Доля аварийных ремонтов среди всех ремонтов за период.
+    EmergencyRepairRatio,
+    /// Возраст здания в годах на конец периода.
+    BuildingAge,
+    Floors,
+    /// Расходы на ремонт за период на квадратный метр площади квартир.
+    RepairCostPerSquareMetre,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskFactor {
+    pub factor: RiskFactorKind,
+    pub value: f64,
+    /// Значение, приведённое к `[0, 1]`.
+    pub normalized: f64,
+    pub weight: f64,
+    /// Сколько баллов фактор добавил к оценке.
+    pub contribution: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildingRisk {
+    pub rank: usize,
+    pub building_id: Uuid,
+    pub building_number: i32,
+    pub address: String,
+    /// Оценка от 0 до 100; чем выше, тем раньше дому нужен плановый ремонт.
+    pub score: f64,
+    pub factors: Vec<RiskFactor>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskRanking {
+    pub currency: String,
+    pub buildings: Vec<BuildingRisk>,
+}
+
 const DATE_FORMAT: &str = "%Y-%m-%d";
 
 fn parse_date<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
@@ -76,20 +387,316 @@ where
     NaiveDate::parse_from_str(&s, DATE_FORMAT).map_err(de::Error::custom)
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// По какой дате относить затраты к периоду: по дате аварии (ремонта) или по дате оплаты.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
+pub enum CostAttribution {
+    #[default]
+    IncidentDate,
+    PaymentDate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
 pub struct QueryTimeDiapasonParams {
     #[serde(deserialize_with = "parse_date")]
     pub start_date: NaiveDate,
     #[serde(deserialize_with = "parse_date")]
     pub end_date: NaiveDate,
+    #[serde(default)]
+    pub cost_attribution: CostAttribution,
+}
+
+impl QueryTimeDiapasonParams {
+    /// Период с `start_date` по `end_date` включительно в часовом поясе компании.
+    pub fn period(&self, timezone: Tz) -> Result<Period, Error> {
+        if self.start_date > self.end_date {
+            return Err(Error::UnprocessableEntity(
+                "startDate must not be after endDate".to_string(),
+            ));
+        }
+        Period::between_dates(self.start_date, self.end_date, timezone).ok_or_else(end_out_of_range)
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+fn end_out_of_range() -> Error {
+    Error::UnprocessableEntity("endDate is out of range".to_string())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BuildingRepairCost {
     pub building_id: Uuid,
     pub building_number: i32,
-    pub total_cost: f64,
+    pub total_cost: Money,
     pub repair_count: i64,
 }
+
+/// Какие разбивки добавить к помесячному ряду расходов.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ExpenseBreakdown {
+    pub by_operation_type: bool,
+    pub by_building: bool,
+}
+
+/// Полуоткрытый интервал времени `[start, end)`, за который считается статистика.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Period {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Period {
+    /// Календарный год целиком.
+    pub fn year(year: i32, timezone: Tz) -> Option<Self> {
+        Some(Self {
+            start: start_of_day(NaiveDate::from_ymd_opt(year, 1, 1)?, timezone),
+            end: start_of_day(NaiveDate::from_ymd_opt(year + 1, 1, 1)?, timezone),
+        })
+    }
+
+    /// Последние двенадцать месяцев, заканчивающиеся в `now`.
+    pub fn twelve_months_until(now: DateTime<Utc>) -> Self {
+        Self {
+            start: now - Months::new(12),
+            end: now,
+        }
+    }
+
+    /// Период между двумя датами, включая обе; `None`, если `end_date` — последний
+    /// представимый день.
+    pub fn between_dates(start_date: NaiveDate, end_date: NaiveDate, timezone: Tz) -> Option<Self> {
+        Some(Self {
+            start: start_of_day(start_date, timezone),
+            end: start_of_day(end_date.checked_add_days(Days::new(1))?, timezone),
+        })
+    }
+
+    /// Тот же период годом ранее; `None`, если он выходит за пределы представимых дат.
+    pub fn year_before(&self) -> Option<Self> {
+        Some(Self {
+            start: self.start.checked_sub_months(Months::new(12))?,
+            end: self.end.checked_sub_months(Months::new(12))?,
+        })
+    }
+
+    /// `months` полных месяцев, закончившихся к началу месяца `month`.
+    pub fn months_before(month: NaiveDate, months: u32, timezone: Tz) -> Self {
+        Self {
+            start: start_of_day(month - Months::new(months), timezone),
+            end: start_of_day(month, timezone),
+        }
+    }
+
+    /// Период такой же длины, непосредственно предшествующий текущему; `None`, если он выходит
+    /// за пределы представимых дат.
+    pub fn preceding(&self) -> Option<Self> {
+        Some(Self {
+            start: self.start.checked_sub_signed(self.end - self.start)?,
+            end: self.start,
+        })
+    }
+}
+
+/// Начало суток `date` в часовом поясе `timezone`.
+///
+/// Если полночь выпадает на перевод часов, берётся первый существующий момент после неё.
+fn start_of_day(date: NaiveDate, timezone: Tz) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    (0..=3)
+        .find_map(|hours| {
+            timezone
+                .from_local_datetime(&(midnight + chrono::Duration::hours(hours)))
+                .earliest()
+        })
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct YearOverviewParams {
+    pub year: Option<i32>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub building_id: Option<Uuid>,
+    /// Разбить помесячные расходы по типам операций.
+    #[serde(default)]
+    pub breakdown_by_operation_type: bool,
+    /// Разбить помесячные расходы по домам.
+    #[serde(default)]
+    pub breakdown_by_building: bool,
+}
+
+impl YearOverviewParams {
+    pub fn expense_breakdown(&self) -> ExpenseBreakdown {
+        ExpenseBreakdown {
+            by_operation_type: self.breakdown_by_operation_type,
+            by_building: self.breakdown_by_building,
+        }
+    }
+
+    /// Отчётный период и период, с которым он сравнивается.
+    ///
+    /// По умолчанию — последние двенадцать месяцев против двенадцати месяцев до них.
+    pub fn periods(&self, now: DateTime<Utc>, timezone: Tz) -> Result<(Period, Period), Error> {
+        let out_of_range =
+            || Error::UnprocessableEntity("the period to compare with is out of range".to_string());
+        match (self.year, self.start_date, self.end_date) {
+            (None, None, None) => {
+                let current = Period::twelve_months_until(now);
+                Ok((current, current.year_before().ok_or_else(out_of_range)?))
+            }
+            (Some(year), None, None) => {
+                let current = Period::year(year, timezone).ok_or_else(|| {
+                    Error::UnprocessableEntity(format!("year {year} is out of range"))
+                })?;
+                Ok((current, current.year_before().ok_or_else(out_of_range)?))
+            }
+            (None, Some(start_date), Some(end_date)) if start_date <= end_date => {
+                let current = Period::between_dates(start_date, end_date, timezone)
+                    .ok_or_else(end_out_of_range)?;
+                Ok((current, current.preceding().ok_or_else(out_of_range)?))
+            }
+            (None, Some(_), Some(_)) => Err(Error::UnprocessableEntity(
+                "startDate must not be after endDate".to_string(),
+            )),
+            _ => Err(Error::UnprocessableEntity(
+                "either year or both startDate and endDate must be given".to_string(),
+            )),
+        }
+    }
+}
+
+/// Первое число текущего месяца в часовом поясе компании.
+pub fn month_start(now: DateTime<Utc>, timezone: Tz) -> NaiveDate {
+    let today = now.with_timezone(&timezone).date_naive();
+    today - Days::new(u64::from(today.day0()))
+}
+
+/// Больше шагов ряда за один запрос не отдаётся: это десять лет по неделям.
+const MAX_TREND_POINTS: i64 = 520;
+
+/// Какие разбивки добавить к ряду аварий.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TrendBreakdown {
+    pub by_incident_type: bool,
+    pub by_building: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct TrendParams {
+    #[serde(deserialize_with = "parse_date")]
+    pub start_date: NaiveDate,
+    #[serde(deserialize_with = "parse_date")]
+    pub end_date: NaiveDate,
+    #[serde(default)]
+    pub granularity: TrendGranularity,
+    pub building_id: Option<Uuid>,
+    /// Добавить ряды по типам аварий.
+    #[serde(default)]
+    pub by_incident_type: bool,
+    /// Добавить ряды по домам.
+    #[serde(default)]
+    pub by_building: bool,
+}
+
+impl TrendParams {
+    pub fn breakdown(&self) -> TrendBreakdown {
+        TrendBreakdown {
+            by_incident_type: self.by_incident_type,
+            by_building: self.by_building,
+        }
+    }
+
+    /// Период с `start_date` по `end_date` включительно; слишком длинные ряды отклоняются.
+    pub fn period(&self, timezone: Tz) -> Result<Period, Error> {
+        if self.start_date > self.end_date {
+            return Err(Error::UnprocessableEntity(
+                "startDate must not be after endDate".to_string(),
+            ));
+        }
+
+        let points = match self.granularity {
+            TrendGranularity::Week => (self.end_date - self.start_date).num_days() / 7 + 1,
+            TrendGranularity::Month => {
+                i64::from(self.end_date.year() - self.start_date.year()) * 12
+                    + i64::from(self.end_date.month0())
+                    - i64::from(self.start_date.month0())
+                    + 1
+            }
+        };
+        if points > MAX_TREND_POINTS {
+            return Err(Error::UnprocessableEntity(format!(
+                "period is too long: at most {MAX_TREND_POINTS} points are allowed"
+            )));
+        }
+
+        Period::between_dates(self.start_date, self.end_date, timezone).ok_or_else(end_out_of_range)
+    }
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct YearOverYearParams {
+    /// По умолчанию — текущий год.
+    pub year: Option<i32>,
+    pub building_id: Option<Uuid>,
+}
+
+fn default_seasonality_years() -> u32 {
+    3
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct SeasonalityParams {
+    /// Сколько последних лет разложить; берутся полные месяцы до текущего.
+    #[serde(default = "default_seasonality_years")]
+    pub years: u32,
+    pub building_id: Option<Uuid>,
+    #[serde(default)]
+    pub metric: TrendMetric,
+}
+
+impl SeasonalityParams {
+    pub fn period(&self, month: NaiveDate, timezone: Tz) -> Result<Period, Error> {
+        if !(2..=10).contains(&self.years) {
+            return Err(Error::UnprocessableEntity(
+                "years must be between 2 and 10".to_string(),
+            ));
+        }
+        Ok(Period::months_before(month, self.years * 12, timezone))
+    }
+}
+
+fn default_history_months() -> u32 {
+    36
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ForecastParams {
+    /// Сколько полных месяцев истории использовать для прогноза.
+    #[serde(default = "default_history_months")]
+    pub history_months: u32,
+    pub building_id: Option<Uuid>,
+}
+
+impl ForecastParams {
+    pub fn period(&self, month: NaiveDate, timezone: Tz) -> Result<Period, Error> {
+        if !(6..=120).contains(&self.history_months) {
+            return Err(Error::UnprocessableEntity(
+                "historyMonths must be between 6 and 120".to_string(),
+            ));
+        }
+        Ok(Period::months_before(month, self.history_months, timezone))
+    }
+}
@@ -0,0 +1,596 @@
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use printpdf::{
+    Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point,
+    Rect, Rgb,
+};
+use ttf_parser::Face;
+
+use crate::api::{money::Money, Error};
+
+use super::models::{CostAttribution, PeriodReport};
+
+const REGULAR_FONT: &[u8] = include_bytes!("../../../assets/fonts/DejaVuSans.ttf");
+const BOLD_FONT: &[u8] = include_bytes!("../../../assets/fonts/DejaVuSans-Bold.ttf");
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+const PT_TO_MM: f32 = 25.4 / 72.0;
+const TEXT_SIZE: f32 = 10.0;
+const SMALL_TEXT_SIZE: f32 = 8.0;
+const HEADING_SIZE: f32 = 13.0;
+const TITLE_SIZE: f32 = 16.0;
+const ROW_HEIGHT: f32 = 6.5;
+
+const CHART_HEIGHT: f32 = 70.0;
+const CHART_AXIS_WIDTH: f32 = 22.0;
+const CHART_LABEL_SIZE: f32 = 6.5;
+/// Минимальная ширина столбца, при которой под ним помещается подпись месяца.
+const MIN_LABELLED_BAR_SLOT: f32 = 9.0;
+
+const DATE_FORMAT: &str = "%d.%m.%Y";
+
+/// Сформировать PDF-отчёт за период с `start_date` по `end_date` включительно.
+pub fn render_period_report(
+    report: &PeriodReport,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    cost_attribution: CostAttribution,
+    timezone: Tz,
+    generated_at: DateTime<Utc>,
+) -> Result<Vec<u8>, Error> {
+    let title = format!(
+        "Отчёт за период с {} по {}",
+        start_date.format(DATE_FORMAT),
+        end_date.format(DATE_FORMAT)
+    );
+    let mut writer = ReportWriter::new(&title)?;
+    let summary = &report.summary;
+    let currency = &summary.currency;
+
+    writer.title(&title);
+    writer.note(&format!(
+        "Сформирован {} ({}). Затраты отнесены к периоду {}.",
+        generated_at
+            .with_timezone(&timezone)
+            .format("%d.%m.%Y %H:%M"),
+        timezone.name(),
+        match cost_attribution {
+            CostAttribution::IncidentDate => "по дате аварии",
+            CostAttribution::PaymentDate => "по дате оплаты",
+        }
+    ));
+
+    writer.heading("Сводные показатели");
+    writer.table(
+        &[
+            Column::left("Показатель", 120.0),
+            Column::right("Значение", 50.0),
+        ],
+        vec![
+            vec![
+                "Зарегистрировано аварий".to_string(),
+                summary.total_incidents.to_string(),
+            ],
+            vec![
+                format!("Затраты на устранение аварий, {currency}"),
+                format_money(summary.total_cost),
+            ],
+            vec![
+                "Аварийные ремонты".to_string(),
+                summary.repair_counts.emergency_repairs.to_string(),
+            ],
+            vec![
+                "Плановые ремонты".to_string(),
+                summary.repair_counts.scheduled_repairs.to_string(),
+            ],
+            vec![
+                "Всего ремонтов".to_string(),
+                summary.repair_counts.total.to_string(),
+            ],
+        ],
+    );
+
+    writer.heading("Аварии по типам");
+    let mut incident_rows: Vec<Vec<String>> = report
+        .incidents_by_type
+        .iter()
+        .map(|count| {
+            let cost = summary
+                .incident_costs
+                .iter()
+                .find(|cost| cost.incident_type == count.incident_type)
+                .map_or(Money::default(), |cost| cost.total_cost);
+            vec![
+                count.incident_type.clone(),
+                count.count.to_string(),
+                format_money(cost),
+            ]
+        })
+        .collect();
+    // При отнесении по дате оплаты затраты могут относиться к авариям, зарегистрированным раньше периода.
+    incident_rows.extend(
+        summary
+            .incident_costs
+            .iter()
+            .filter(|cost| {
+                !report
+                    .incidents_by_type
+                    .iter()
+                    .any(|count| count.incident_type == cost.incident_type)
+            })
+            .map(|cost| {
+                vec![
+                    cost.incident_type.clone(),
+                    "0".to_string(),
+                    format_money(cost.total_cost),
+                ]
+            }),
+    );
+    writer.table(
+        &[
+            Column::left("Тип аварии", 100.0),
+            Column::right("Аварий", 30.0),
+            Column::right(&format!("Затраты, {currency}"), 40.0),
+        ],
+        incident_rows,
+    );
+
+    writer.heading("Дома с наибольшими затратами на ремонт");
+    writer.table(
+        &[
+            Column::left("Дом", 70.0),
+            Column::right("Ремонтов", 40.0),
+            Column::right(&format!("Затраты, {currency}"), 60.0),
+        ],
+        summary
+            .top_buildings_by_repair_cost
+            .iter()
+            .map(|building| {
+                vec![
+                    format!("Дом № {}", building.building_number),
+                    building.repair_count.to_string(),
+                    format_money(building.total_cost),
+                ]
+            })
+            .collect(),
+    );
+
+    writer.heading(&format!("Расходы по месяцам, {currency}"));
+    writer.bar_chart(
+        &report
+            .monthly_expenses
+            .iter()
+            .map(|month| (format_month(&month.month), month.total))
+            .collect::<Vec<_>>(),
+    );
+    writer.note("Расходы отнесены к месяцам по дате финансовой операции.");
+
+    writer.finish()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Right,
+}
+
+struct Column {
+    header: String,
+    width: f32,
+    align: Align,
+}
+
+impl Column {
+    fn left(header: &str, width: f32) -> Self {
+        Self {
+            header: header.to_string(),
+            width,
+            align: Align::Left,
+        }
+    }
+
+    fn right(header: &str, width: f32) -> Self {
+        Self {
+            header: header.to_string(),
+            width,
+            align: Align::Right,
+        }
+    }
+}
+
+struct Font {
+    reference: IndirectFontRef,
+    face: Face<'static>,
+}
+
+impl Font {
+    fn load(doc: &PdfDocumentReference, data: &'static [u8]) -> Result<Self, Error> {
+        let reference = doc
+            .add_external_font(data)
+            .map_err(|e| anyhow!("failed to embed report font: {e}"))?;
+        let face = Face::parse(data, 0).context("failed to parse report font")?;
+        Ok(Self { reference, face })
+    }
+
+    /// Ширина строки в миллиметрах.
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let units: u32 = text
+            .chars()
+            .filter_map(|c| self.face.glyph_index(c))
+            .filter_map(|glyph| self.face.glyph_hor_advance(glyph))
+            .map(u32::from)
+            .sum();
+        units as f32 / f32::from(self.face.units_per_em()) * size * PT_TO_MM
+    }
+
+    /// Обрезать строку с многоточием, чтобы она поместилась в `width` миллиметров.
+    fn fit(&self, text: &str, size: f32, width: f32) -> String {
+        if self.width(text, size) <= width {
+            return text.to_string();
+        }
+        let mut fitted: String = text.to_string();
+        while !fitted.is_empty() && self.width(&format!("{fitted}…"), size) > width {
+            fitted.pop();
+        }
+        format!("{}…", fitted.trim_end())
+    }
+}
+
+/// Постраничная вёрстка отчёта сверху вниз; `y` — текущая позиция от нижнего края страницы.
+struct ReportWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: Font,
+    bold: Font,
+    y: f32,
+}
+
+impl ReportWriter {
+    fn new(title: &str) -> Result<Self, Error> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Слой 1");
+        let layer = doc.get_page(page).get_layer(layer);
+        let regular = Font::load(&doc, REGULAR_FONT)?;
+        let bold = Font::load(&doc, BOLD_FONT)?;
+        Ok(Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    /// Начать новую страницу, если до нижнего поля осталось меньше `height` миллиметров.
+    fn ensure_space(&mut self, height: f32) -> bool {
+        if self.y - height >= MARGIN {
+            return false;
+        }
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Слой 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+        true
+    }
+
+    fn text(&self, text: &str, size: f32, bold: bool, x: f32, width: f32, align: Align) {
+        let font = if bold { &self.bold } else { &self.regular };
+        let text = font.fit(text, size, width);
+        let x = match align {
+            Align::Left => x,
+            Align::Right => x + width - font.width(&text, size),
+        };
+        self.layer
+            .use_text(text, size, Mm(x), Mm(self.y), &font.reference);
+    }
+
+    fn title(&mut self, text: &str) {
+        self.y -= TITLE_SIZE * PT_TO_MM;
+        self.text(text, TITLE_SIZE, true, MARGIN, CONTENT_WIDTH, Align::Left);
+        self.y -= 4.0;
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure_space(10.0 + 2.0 * ROW_HEIGHT);
+        self.y -= 10.0;
+        self.text(text, HEADING_SIZE, true, MARGIN, CONTENT_WIDTH, Align::Left);
+        self.y -= 3.0;
+    }
+
+    fn note(&mut self, text: &str) {
+        self.ensure_space(ROW_HEIGHT);
+        self.y -= ROW_HEIGHT;
+        self.text(
+            text,
+            SMALL_TEXT_SIZE,
+            false,
+            MARGIN,
+            CONTENT_WIDTH,
+            Align::Left,
+        );
+    }
+
+    /// Таблица с заголовком, повторяющимся на каждой новой странице.
+    fn table(&mut self, columns: &[Column], rows: Vec<Vec<String>>) {
+        self.table_header(columns);
+        if rows.is_empty() {
+            self.y -= ROW_HEIGHT;
+            self.text(
+                "Нет данных",
+                TEXT_SIZE,
+                false,
+                MARGIN,
+                CONTENT_WIDTH,
+                Align::Left,
+            );
+            return;
+        }
+        for row in rows {
+            if self.ensure_space(ROW_HEIGHT) {
+                self.table_header(columns);
+            }
+            self.y -= ROW_HEIGHT;
+            let mut x = MARGIN;
+            for (column, value) in columns.iter().zip(&row) {
+                self.text(value, TEXT_SIZE, false, x, column.width - 2.0, column.align);
+                x += column.width;
+            }
+        }
+    }
+
+    fn table_header(&mut self, columns: &[Column]) {
+        self.ensure_space(2.0 * ROW_HEIGHT);
+        self.y -= ROW_HEIGHT;
+        let mut x = MARGIN;
+        for column in columns {
+            self.text(
+                &column.header,
+                TEXT_SIZE,
+                true,
+                x,
+                column.width - 2.0,
+                column.align,
+            );
+            x += column.width;
+        }
+        self.horizontal_line(self.y - 1.8, MARGIN, MARGIN + CONTENT_WIDTH, 0.8);
+    }
+
+    fn horizontal_line(&self, y: f32, from: f32, to: f32, gray: f32) {
+        self.layer.set_outline_color(gray_color(gray));
+        self.layer.set_outline_thickness(0.5);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(from), Mm(y)), false),
+                (Point::new(Mm(to), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    /// Столбчатая диаграмма с сеткой на нуле, половине и максимуме.
+    fn bar_chart(&mut self, bars: &[(String, Money)]) {
+        let max = bars
+            .iter()
+            .map(|(_, total)| *total)
+            .max()
+            .unwrap_or_default();
+        if bars.is_empty() || max <= Money::default() {
+            self.note("Расходов за период не было.");
+            return;
+        }
+
+        self.ensure_space(CHART_HEIGHT + 12.0);
+        let top = self.y - 6.0;
+        let bottom = top - CHART_HEIGHT;
+        let left = MARGIN + CHART_AXIS_WIDTH;
+        let width = CONTENT_WIDTH - CHART_AXIS_WIDTH;
+        let max_value = max.to_f64() as f32;
+
+        for fraction in [0.0, 0.5, 1.0] {
+            let y = bottom + CHART_HEIGHT * fraction;
+            self.horizontal_line(y, left, left + width, 0.85);
+            self.y = y - 1.0;
+            self.text(
+                &format_money_short(max_value * fraction),
+                CHART_LABEL_SIZE,
+                false,
+                MARGIN,
+                CHART_AXIS_WIDTH - 2.0,
+                Align::Right,
+            );
+        }
+
+        let slot = width / bars.len() as f32;
+        let label_every = (MIN_LABELLED_BAR_SLOT / slot).ceil().max(1.0) as usize;
+        for (i, (label, total)) in bars.iter().enumerate() {
+            let x = left + slot * i as f32;
+            let height = CHART_HEIGHT * (total.to_f64() as f32 / max_value);
+            if height > 0.0 {
+                self.layer
+                    .set_fill_color(Color::Rgb(Rgb::new(0.25, 0.45, 0.7, None)));
+                self.layer.add_rect(Rect::new(
+                    Mm(x + slot * 0.15),
+                    Mm(bottom),
+                    Mm(x + slot * 0.85),
+                    Mm(bottom + height),
+                ));
+                self.layer.set_fill_color(gray_color(0.0));
+            }
+            if slot >= 2.0 * MIN_LABELLED_BAR_SLOT && height > 0.0 {
+                self.y = bottom + height + 1.0;
+                self.text(
+                    &format_money_short(total.to_f64() as f32),
+                    CHART_LABEL_SIZE,
+                    false,
+                    x,
+                    slot,
+                    Align::Left,
+                );
+            }
+            if i % label_every == 0 {
+                self.y = bottom - 3.5;
+                self.text(
+                    label,
+                    CHART_LABEL_SIZE,
+                    false,
+                    x + slot * 0.15,
+                    slot * label_every as f32,
+                    Align::Left,
+                );
+            }
+        }
+
+        self.y = bottom - 4.0;
+    }
+
+    fn finish(self) -> Result<Vec<u8>, Error> {
+        Ok(self
+            .doc
+            .save_to_bytes()
+            .map_err(|e| anyhow!("failed to save PDF report: {e}"))?)
+    }
+}
+
+fn gray_color(level: f32) -> Color {
+    Color::Rgb(Rgb::new(level, level, level, None))
+}
+
+/// Сумма с разделителями разрядов и запятой: `1 234 567,89`.
+fn format_money(amount: Money) -> String {
+    let formatted = amount.to_string();
+    let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, "00"));
+    let (sign, digits) = match integer.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", integer),
+    };
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push('\u{a0}');
+        }
+        grouped.push(digit);
+    }
+    format!("{sign}{grouped},{fraction}")
+}
+
+/// Округлённая сумма для подписей диаграммы: `950`, `12,5 тыс.`, `1,2 млн`.
+fn format_money_short(amount: f32) -> String {
+    if amount >= 1_000_000.0 {
+        format!("{:.1} млн", amount / 1_000_000.0).replacen('.', ",", 1)
+    } else if amount >= 1_000.0 {
+        format!("{:.1} тыс.", amount / 1_000.0).replacen('.', ",", 1)
+    } else {
+        format!("{amount:.0}")
+    }
+}
+
+/// `2024-03` → `03.24`.
+fn format_month(month: &str) -> String {
+    match month.split_once('-') {
+        Some((year, month)) => format!("{month}.{}", &year[year.len().saturating_sub(2)..]),
+        None => month.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::statistics::models::{
+        BuildingRepairCost, IncidentCost, IncidentTypeCount, MonthlyExpenses, RepairCount,
+        SummaryStatistics,
+    };
+    use uuid::Uuid;
+
+    fn money(amount: &str) -> Money {
+        amount.parse().unwrap()
+    }
+
+    fn report(buildings: usize) -> PeriodReport {
+        PeriodReport {
+            summary: SummaryStatistics {
+                currency: "RUB".to_string(),
+                total_incidents: 3,
+                total_cost: money("28000.25"),
+                repair_counts: RepairCount {
+                    emergency_repairs: 2,
+                    scheduled_repairs: 1,
+                    total: 3,
+                },
+                incident_costs: vec![IncidentCost {
+                    incident_type: "Протечка".to_string(),
+                    total_cost: money("20000"),
+                }],
+                top_buildings_by_repair_cost: (0..buildings)
+                    .map(|i| BuildingRepairCost {
+                        building_id: Uuid::nil(),
+                        building_number: i as i32,
+                        total_cost: money("1000"),
+                        repair_count: 1,
+                    })
+                    .collect(),
+            },
+            incidents_by_type: vec![IncidentTypeCount {
+                incident_type: "Отключение электричества".to_string(),
+                count: 2,
+            }],
+            monthly_expenses: ["2024-01", "2024-02", "2024-03"]
+                .into_iter()
+                .zip(["20000", "0", "5000.25"])
+                .map(|(month, total)| MonthlyExpenses {
+                    month: month.to_string(),
+                    name: String::new(),
+                    total: money(total),
+                    by_operation_type: None,
+                    by_building: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn render(report: &PeriodReport) -> Vec<u8> {
+        render_period_report(
+            report,
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            CostAttribution::PaymentDate,
+            Tz::Europe__Moscow,
+            Utc::now(),
+        )
+        .unwrap()
+    }
+
+    fn page_count(pdf: &[u8]) -> usize {
+        printpdf::lopdf::Document::load_mem(pdf)
+            .unwrap()
+            .get_pages()
+            .len()
+    }
+
+    #[test]
+    fn renders_pdf() {
+        let pdf = render(&report(2));
+
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn long_tables_continue_on_next_page() {
+        let short = render(&report(2));
+        let long = render(&report(80));
+
+        assert_eq!(page_count(&short), 1);
+        assert!(page_count(&long) > 1);
+    }
+
+    #[test]
+    fn money_is_grouped_by_thousands() {
+        assert_eq!(format_money(money("1234567.8")), "1\u{a0}234\u{a0}567,80");
+        assert_eq!(format_money(money("-950")), "-950,00");
+        assert_eq!(format_money_short(12_500.0), "12,5 тыс.");
+        assert_eq!(format_month("2024-03"), "03.24");
+    }
+}
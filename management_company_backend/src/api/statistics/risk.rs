@@ -0,0 +1,194 @@
+//! Оценка риска аварий по дому из истории аварий, ремонтов и характеристик здания.
+
+use super::models::{RiskFactor, RiskFactorKind};
+
+/// Факторы риска, их веса (в сумме единица) и нормировка.
+///
+/// Доля аварийных ремонтов уже лежит в `[0, 1]`; остальные факторы делятся на максимум
+/// среди домов, поэтому оценка сравнивает дома между собой, а не с внешней нормой.
+const FACTORS: [(RiskFactorKind, f64, Normalization); 5] = [
+    (
+        RiskFactorKind::IncidentFrequency,
+        0.30,
+        Normalization::RelativeToMax,
+    ),
+    (
+        RiskFactorKind::RepairCostPerSquareMetre,
+        0.25,
+        Normalization::RelativeToMax,
+    ),
+    (
+        RiskFactorKind::EmergencyRepairRatio,
+        0.20,
+        Normalization::Ratio,
+    ),
+    (
+        RiskFactorKind::BuildingAge,
+        0.15,
+        Normalization::RelativeToMax,
+    ),
+    (RiskFactorKind::Floors, 0.10, Normalization::RelativeToMax),
+];
+
+/// Максимальная оценка риска.
+const MAX_SCORE: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy)]
+enum Normalization {
+    Ratio,
+    RelativeToMax,
+}
+
+/// Показатели дома, из которых складывается оценка.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RiskInputs {
+    pub incidents_per_year: f64,
+    /// Доля аварийных ремонтов среди всех; ноль, если ремонтов не было.
+    pub emergency_repair_ratio: f64,
+    pub age_years: f64,
+    pub floors: f64,
+    /// Ноль, если площадь квартир дома неизвестна.
+    pub repair_cost_per_square_metre: f64,
+}
+
+impl RiskInputs {
+    fn value(&self, factor: RiskFactorKind) -> f64 {
+        match factor {
+            RiskFactorKind::IncidentFrequency => self.incidents_per_year,
+            RiskFactorKind::EmergencyRepairRatio => self.emergency_repair_ratio,
+            RiskFactorKind::BuildingAge => self.age_years,
+            RiskFactorKind::Floors => self.floors,
+            RiskFactorKind::RepairCostPerSquareMetre => self.repair_cost_per_square_metre,
+        }
+    }
+}
+
+/// Оценка риска дома от 0 до 100 и вклад каждого фактора, от самого весомого.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskScore {
+    pub score: f64,
+    pub factors: Vec<RiskFactor>,
+}
+
+/// Оценить риск каждого дома; результат идёт в том же порядке, что и `buildings`.
+pub fn score_buildings(buildings: &[RiskInputs]) -> Vec<RiskScore> {
+    let maxima = FACTORS.map(|(factor, _, _)| {
+        buildings
+            .iter()
+            .map(|inputs| inputs.value(factor))
+            .fold(0.0, f64::max)
+    });
+
+    buildings
+        .iter()
+        .map(|inputs| {
+            let mut factors: Vec<RiskFactor> = FACTORS
+                .iter()
+                .zip(maxima)
+                .map(|(&(factor, weight, normalization), max)| {
+                    let value = inputs.value(factor);
+                    let normalized = match normalization {
+                        Normalization::Ratio => value.clamp(0.0, 1.0),
+                        Normalization::RelativeToMax if max > 0.0 => value / max,
+                        Normalization::RelativeToMax => 0.0,
+                    };
+                    RiskFactor {
+                        factor,
+                        value: round(value, 2),
+                        normalized: round(normalized, 3),
+                        weight,
+                        contribution: round(weight * normalized * MAX_SCORE, 1),
+                    }
+                })
+                .collect();
+            factors.sort_by(|a, b| b.contribution.total_cmp(&a.contribution));
+
+            RiskScore {
+                score: round(factors.iter().map(|factor| factor.contribution).sum(), 1),
+                factors,
+            }
+        })
+        .collect()
+}
+
+fn round(value: f64, digits: i32) -> f64 {
+    let scale = 10f64.powi(digits);
+    (value * scale).round() / scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contribution(score: &RiskScore, factor: RiskFactorKind) -> f64 {
+        score
+            .factors
+            .iter()
+            .find(|item| item.factor == factor)
+            .unwrap()
+            .contribution
+    }
+
+    #[test]
+    fn weights_add_up_to_one() {
+        let total: f64 = FACTORS.iter().map(|(_, weight, _)| weight).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn worst_building_on_every_factor_scores_maximum() {
+        let worst = RiskInputs {
+            incidents_per_year: 6.0,
+            emergency_repair_ratio: 1.0,
+            age_years: 50.0,
+            floors: 16.0,
+            repair_cost_per_square_metre: 300.0,
+        };
+        let scores = score_buildings(&[worst, RiskInputs::default()]);
+
+        assert_eq!(scores[0].score, 100.0);
+        assert_eq!(scores[1].score, 0.0);
+    }
+
+    #[test]
+    fn factors_are_normalised_against_other_buildings() {
+        let old = RiskInputs {
+            age_years: 40.0,
+            floors: 5.0,
+            ..Default::default()
+        };
+        let new = RiskInputs {
+            age_years: 10.0,
+            floors: 10.0,
+            emergency_repair_ratio: 0.5,
+            ..Default::default()
+        };
+
+        let scores = score_buildings(&[old, new]);
+
+        assert_eq!(contribution(&scores[0], RiskFactorKind::BuildingAge), 15.0);
+        assert_eq!(contribution(&scores[1], RiskFactorKind::BuildingAge), 3.8);
+        assert_eq!(contribution(&scores[0], RiskFactorKind::Floors), 5.0);
+        assert_eq!(
+            contribution(&scores[1], RiskFactorKind::EmergencyRepairRatio),
+            10.0
+        );
+        assert_eq!(scores[0].score, 20.0);
+        assert_eq!(scores[1].score, 23.8);
+    }
+
+    #[test]
+    fn factors_are_ordered_by_contribution() {
+        let scores = score_buildings(&[RiskInputs {
+            floors: 9.0,
+            emergency_repair_ratio: 0.25,
+            ..Default::default()
+        }]);
+
+        let factors: Vec<_> = scores[0].factors.iter().map(|item| item.factor).collect();
+        assert_eq!(
+            &factors[..2],
+            [RiskFactorKind::Floors, RiskFactorKind::EmergencyRepairRatio]
+        );
+    }
+}
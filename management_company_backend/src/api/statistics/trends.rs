@@ -0,0 +1,269 @@
+//! Анализ помесячных рядов: сезонная декомпозиция и прогноз экспоненциальным сглаживанием.
+
+use super::models::ForecastMethod;
+
+/// Длина сезона помесячного ряда.
+pub const MONTHS_IN_YEAR: usize = 12;
+/// Минимальная длина ряда, при которой каждый месяц года хотя бы раз попадает под тренд.
+pub const MIN_SEASONAL_HISTORY: usize = 2 * MONTHS_IN_YEAR;
+
+/// Вес последнего наблюдения при сглаживании уровня.
+const LEVEL_SMOOTHING: f64 = 0.3;
+/// Вес последнего изменения уровня при сглаживании тренда.
+const TREND_SMOOTHING: f64 = 0.1;
+/// Затухание тренда: без него прогноз на полгода вперёд уводит случайный всплеск в бесконечность.
+const TREND_DAMPING: f64 = 0.9;
+/// Сколько последних месяцев усредняет базовый прогноз скользящим средним.
+const MOVING_AVERAGE_WINDOW: usize = 3;
+
+/// Аддитивная декомпозиция ряда: наблюдение = тренд + сезонность + остаток.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decomposition {
+    /// Центрированное скользящее среднее за год; по полгода с краёв ряда его нет.
+    pub trend: Vec<Option<f64>>,
+    pub seasonal: Vec<f64>,
+    pub residual: Vec<Option<f64>>,
+    /// Сезонная поправка для января, февраля и так далее; в сумме поправки дают ноль.
+    pub indices: [f64; MONTHS_IN_YEAR],
+}
+
+/// Прогноз ряда на `horizon` месяцев вперёд.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    pub method: ForecastMethod,
+    pub values: Vec<f64>,
+    /// Среднее за последние месяцы, повторённое на весь горизонт — для сравнения с прогнозом.
+    pub moving_average: f64,
+}
+
+/// Центрированное скользящее среднее 2×12: концы окна берутся с половинным весом,
+/// чтобы окно чётной длины было симметричным относительно месяца.
+pub fn centered_moving_average(values: &[f64]) -> Vec<Option<f64>> {
+    let half = MONTHS_IN_YEAR / 2;
+    (0..values.len())
+        .map(|i| {
+            if i < half || i + half >= values.len() {
+                return None;
+            }
+            let inner: f64 = values[i + 1 - half..i + half].iter().sum();
+            let edges = (values[i - half] + values[i + half]) / 2.0;
+            Some((inner + edges) / MONTHS_IN_YEAR as f64)
+        })
+        .collect()
+}
+
+/// Классическая аддитивная декомпозиция помесячного ряда.
+///
+/// `first_month` — номер календарного месяца первого значения, от 0 (январь) до 11.
+/// Возвращает `None`, если ряд короче двух лет.
+pub fn decompose(values: &[f64], first_month: usize) -> Option<Decomposition> {
+    if values.len() < MIN_SEASONAL_HISTORY {
+        return None;
+    }
+
+    let trend = centered_moving_average(values);
+    let month_of = |i: usize| (first_month + i) % MONTHS_IN_YEAR;
+
+    let mut sums = [0.0; MONTHS_IN_YEAR];
+    let mut counts = [0usize; MONTHS_IN_YEAR];
+    for (i, (value, trend)) in values.iter().zip(&trend).enumerate() {
+        if let Some(trend) = trend {
+            sums[month_of(i)] += value - trend;
+            counts[month_of(i)] += 1;
+        }
+    }
+    let mut indices = [0.0; MONTHS_IN_YEAR];
+    for month in 0..MONTHS_IN_YEAR {
+        indices[month] = sums[month] / counts[month].max(1) as f64;
+    }
+    let mean = indices.iter().sum::<f64>() / MONTHS_IN_YEAR as f64;
+    indices.iter_mut().for_each(|index| *index -= mean);
+
+    let seasonal: Vec<f64> = (0..values.len()).map(|i| indices[month_of(i)]).collect();
+    let residual = values
+        .iter()
+        .zip(&trend)
+        .zip(&seasonal)
+        .map(|((value, trend), seasonal)| trend.map(|trend| value - trend - seasonal))
+        .collect();
+
+    Some(Decomposition {
+        trend,
+        seasonal,
+        residual,
+        indices,
+    })
+}
+
+/// Прогноз на `horizon` месяцев после конца ряда.
+///
+/// Если истории хватает на декомпозицию, сглаживается ряд без сезонности, а поправка
+/// возвращается в прогноз; иначе сглаживается сам ряд. Отрицательные значения
+/// обрезаются до нуля: ни аварий, ни расходов меньше нуля не бывает.
+pub fn forecast(values: &[f64], first_month: usize, horizon: usize) -> Forecast {
+    let window = &values[values.len().saturating_sub(MOVING_AVERAGE_WINDOW)..];
+    let moving_average = if window.is_empty() {
+        0.0
+    } else {
+        window.iter().sum::<f64>() / window.len() as f64
+    };
+
+    let (method, values) = match decompose(values, first_month) {
+        Some(decomposition) => {
+            let adjusted: Vec<f64> = values
+                .iter()
+                .zip(&decomposition.seasonal)
+                .map(|(value, seasonal)| value - seasonal)
+                .collect();
+            let next_month = first_month + values.len();
+            let values = damped_holt(&adjusted, horizon)
+                .into_iter()
+                .enumerate()
+                .map(|(h, value)| value + decomposition.indices[(next_month + h) % MONTHS_IN_YEAR])
+                .collect();
+            (ForecastMethod::SeasonalExponentialSmoothing, values)
+        }
+        None => (
+            ForecastMethod::ExponentialSmoothing,
+            damped_holt(values, horizon),
+        ),
+    };
+
+    Forecast {
+        method,
+        values: values.into_iter().map(|value| value.max(0.0)).collect(),
+        moving_average,
+    }
+}
+
+/// Двойное экспоненциальное сглаживание Хольта с затухающим трендом.
+fn damped_holt(values: &[f64], horizon: usize) -> Vec<f64> {
+    let Some(&first) = values.first() else {
+        return vec![0.0; horizon];
+    };
+
+    let mut level = first;
+    let mut trend = values.get(1).map_or(0.0, |second| second - first);
+    for &value in &values[1..] {
+        let previous_level = level;
+        level = LEVEL_SMOOTHING * value
+            + (1.0 - LEVEL_SMOOTHING) * (previous_level + TREND_DAMPING * trend);
+        trend = TREND_SMOOTHING * (level - previous_level)
+            + (1.0 - TREND_SMOOTHING) * TREND_DAMPING * trend;
+    }
+
+    let mut damping = 0.0;
+    (1..=horizon as i32)
+        .map(|h| {
+            damping += TREND_DAMPING.powi(h);
+            level + damping * trend
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERN: [f64; MONTHS_IN_YEAR] = [
+        4.0, 3.0, 2.0, 0.0, -1.0, -2.0, -3.0, -3.0, -2.0, -1.0, 1.0, 2.0,
+    ];
+
+    fn seasonal_series(years: usize, base: f64, slope: f64) -> Vec<f64> {
+        (0..years * MONTHS_IN_YEAR)
+            .map(|i| base + slope * i as f64 + PATTERN[i % MONTHS_IN_YEAR])
+            .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn moving_average_follows_linear_trend() {
+        let values: Vec<f64> = (0..30).map(|i| 2.0 * i as f64).collect();
+
+        let trend = centered_moving_average(&values);
+
+        assert_eq!(trend.iter().filter(|value| value.is_some()).count(), 18);
+        assert_eq!(trend[5], None);
+        assert_close(trend[6].unwrap(), 12.0);
+        assert_close(trend[23].unwrap(), 46.0);
+        assert_eq!(trend[24], None);
+    }
+
+    #[test]
+    fn decomposition_recovers_seasonal_pattern() {
+        let values = seasonal_series(3, 10.0, 0.5);
+
+        let decomposition = decompose(&values, 0).unwrap();
+
+        for (index, expected) in decomposition.indices.iter().zip(PATTERN) {
+            assert_close(*index, expected);
+        }
+        for residual in decomposition.residual.iter().flatten() {
+            assert_close(*residual, 0.0);
+        }
+    }
+
+    #[test]
+    fn decomposition_aligns_indices_with_calendar_months() {
+        let mut values = seasonal_series(3, 10.0, 0.0);
+        // Ряд, начинающийся с апреля.
+        values.rotate_left(3);
+
+        let decomposition = decompose(&values, 3).unwrap();
+
+        assert_close(decomposition.indices[0], PATTERN[0]);
+        assert_close(decomposition.seasonal[0], PATTERN[3]);
+    }
+
+    #[test]
+    fn short_history_is_not_decomposed() {
+        assert_eq!(decompose(&[1.0; MIN_SEASONAL_HISTORY - 1], 0), None);
+    }
+
+    #[test]
+    fn constant_series_forecasts_the_same_level() {
+        let forecast = forecast(&[5.0; 6], 0, 3);
+
+        assert_eq!(forecast.method, ForecastMethod::ExponentialSmoothing);
+        for value in forecast.values {
+            assert_close(value, 5.0);
+        }
+        assert_close(forecast.moving_average, 5.0);
+    }
+
+    #[test]
+    fn seasonal_forecast_repeats_the_pattern() {
+        let values = seasonal_series(3, 10.0, 0.0);
+
+        let forecast = forecast(&values, 0, 3);
+
+        assert_eq!(
+            forecast.method,
+            ForecastMethod::SeasonalExponentialSmoothing
+        );
+        assert_close(forecast.values[0], 10.0 + PATTERN[0]);
+        assert_close(forecast.values[2], 10.0 + PATTERN[2]);
+    }
+
+    #[test]
+    fn forecast_is_never_negative() {
+        let forecast = forecast(&[9.0, 6.0, 3.0, 0.0], 0, 6);
+
+        assert!(forecast.values.iter().all(|value| *value >= 0.0));
+        assert_eq!(forecast.values.last(), Some(&0.0));
+    }
+
+    #[test]
+    fn empty_history_forecasts_zero() {
+        let forecast = forecast(&[], 0, 2);
+
+        assert_eq!(forecast.values, [0.0, 0.0]);
+        assert_eq!(forecast.moving_average, 0.0);
+    }
+}
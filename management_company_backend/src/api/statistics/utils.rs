@@ -1,37 +1,93 @@
+use std::collections::HashMap;
+
 use super::models::{
-    BuildingRepairCost, BuildingSummary, IncidentCost, IncidentTypeInfo, RepairCount,
+    BuildingExpenses, BuildingMonthStatistics, BuildingRepairCost, BuildingStatistics,
+    BuildingSummary, CostAttribution, ExpenseBreakdown, IncidentCost, IncidentStatusCount,
+    IncidentTypeCount, IncidentTypeInfo, OperationTypeExpenses, Period, PeriodReport, RepairCount,
     SummaryStatistics, YearOverviewStatistics,
 };
-use crate::api::{statistics::models::MonthlyExpenses, Error};
-use chrono::{DateTime, Utc};
-use sqlx::{query_as_unchecked, query_scalar, PgPool};
+use super::models::{
+    BuildingRisk, BuildingTrend, DecomposedPoint, ForecastPoint, IncidentTypeTrend, MetricForecast,
+    RiskRanking, SeasonalDecomposition, SeasonalIndex, TrendBreakdown, TrendCount, TrendForecast,
+    TrendGranularity, TrendMetric, TrendPoint, TrendStatistics, YearOverYearMonth,
+    YearOverYearTrends, YearOverYearValue,
+};
+use super::risk::{self, RiskInputs};
+use super::trends;
+use crate::api::{
+    financial_operation::models::FinancialOperationType, incident::models::IncidentStatus,
+    money::Money, statistics::models::MonthlyExpenses, Error,
+};
+use chrono::{Datelike, Months, NaiveDate};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use sqlx::{query_scalar, PgPool};
+use uuid::Uuid;
+
+const DAYS_PER_YEAR: f64 = 365.25;
+const SECONDS_PER_YEAR: f64 = DAYS_PER_YEAR * 24.0 * 60.0 * 60.0;
 
+/// Собрать обзорную статистику за период `current` в сравнении с периодом `previous`.
+///
+/// Фильтр по дому применяется к расходам, ремонтам и авариям; сотрудники считаются по всей компании.
+/// Расходы учитываются только в валюте `currency`, помесячный ряд строится по часовому поясу компании.
 pub async fn build_year_overview_statistics(
     pool: &PgPool,
+    current: Period,
+    previous: Period,
+    building_id: Option<Uuid>,
+    currency: &str,
+    timezone: Tz,
+    breakdown: ExpenseBreakdown,
 ) -> Result<YearOverviewStatistics, Error> {
-    let total_expenses_last_year = get_total_expenses_last_year(pool).await?;
-    let percent_changes_in_expense_from_last_year =
-        get_percent_changes_in_expense_from_last_year(pool).await?;
-    let count_of_repairs_last_year = get_count_of_repairs_last_year(pool).await?;
-    let percent_changes_in_count_repair_last_year =
-        get_percent_changes_in_count_repair_last_year(pool).await?;
-    let count_of_active_repair_requests = get_count_repair_requests(pool).await?;
-    let percent_changes_in_active_repair_requests_last_year =
-        get_percent_changes_in_active_repair_requests_last_year(pool).await?;
-    let count_of_employees = get_count_of_employees(pool).await?;
-    let count_new_employee_last_year = get_count_new_employee_last_year(pool).await?;
-    let expense_distribution_by_month_last_year =
-        get_expence_distribution_by_month_last_year(pool).await?;
-    let top_5_incident_types_last_year = get_top_5_incident_types_last_year(pool).await?;
-    let total_incidents_last_year = get_total_incidents_last_year(pool).await?;
+    let (
+        current_expenses,
+        previous_expenses,
+        count_of_repairs_last_year,
+        count_of_repairs_previous_year,
+        count_of_active_repair_requests,
+        count_of_active_repair_requests_previous_year,
+        count_of_employees,
+        count_new_employee_last_year,
+        expense_distribution_by_month_last_year,
+        (total_incidents_last_year, top_5_incident_types_last_year),
+    ) = tokio::try_join!(
+        get_expenses(pool, current, building_id, currency),
+        get_expenses(pool, previous, building_id, currency),
+        get_count_of_repairs(pool, current, building_id),
+        get_count_of_repairs(pool, previous, building_id),
+        get_count_repair_requests(pool, current, building_id),
+        get_count_repair_requests(pool, previous, building_id),
+        get_count_of_employees(pool, current),
+        get_count_new_employees(pool, current),
+        get_monthly_expenses(pool, current, building_id, currency, timezone, breakdown),
+        async {
+            let total_incidents = get_total_incidents(pool, current, building_id).await?;
+            let top_5_incident_types =
+                get_top_5_incident_types(pool, current, building_id, total_incidents).await?;
+            Ok((total_incidents, top_5_incident_types))
+        },
+    )?;
 
     Ok(YearOverviewStatistics {
-        total_expenses_last_year,
-        percent_changes_in_expense_from_last_year,
+        currency: currency.to_string(),
+        total_expenses_last_year: current_expenses,
+        percent_changes_in_expense_from_last_year: format_percents_with_sign(percent_change(
+            current_expenses.to_f64(),
+            previous_expenses.to_f64(),
+        )),
         count_of_repairs_last_year,
-        percent_changes_in_count_repair_last_year,
+        percent_changes_in_count_repair_last_year: format_percents_with_sign(percent_change(
+            count_of_repairs_last_year as f64,
+            count_of_repairs_previous_year as f64,
+        )),
         count_of_active_repair_requests,
-        percent_changes_in_active_repair_requests_last_year,
+        percent_changes_in_active_repair_requests_last_year: format_percents_with_sign(
+            percent_change(
+                count_of_active_repair_requests as f64,
+                count_of_active_repair_requests_previous_year as f64,
+            ),
+        ),
         count_of_employees,
         count_new_employee_last_year,
         expense_distribution_by_month_last_year,
@@ -42,16 +98,19 @@ pub async fn build_year_overview_statistics(
 
 pub async fn build_statistics_for_building(
     pool: &PgPool,
-    start_date: DateTime<Utc>,
-    end_date: DateTime<Utc>,
+    period: Period,
+    cost_attribution: CostAttribution,
+    currency: &str,
 ) -> Result<SummaryStatistics, Error> {
-    let summary = get_summary(pool, start_date, end_date).await?;
-    let repair_counts = get_repair_counts(pool, start_date, end_date).await?;
-    let incident_costs = get_total_costs_by_incident_type(pool, start_date, end_date).await?;
-    let top_buildings_by_repair_cost =
-        get_top_10_buildings_by_repair_costs(pool, start_date, end_date).await?;
+    let (summary, repair_counts, incident_costs, top_buildings_by_repair_cost) = tokio::try_join!(
+        get_summary(pool, period, cost_attribution, None, currency),
+        get_repair_counts(pool, period, None),
+        get_total_costs_by_incident_type(pool, period, cost_attribution, None, currency),
+        get_top_10_buildings_by_repair_costs(pool, period, cost_attribution, currency),
+    )?;
 
     Ok(SummaryStatistics {
+        currency: currency.to_string(),
         total_incidents: summary.total_incidents,
         total_cost: summary.total_cost,
         repair_counts,
@@ -60,163 +119,635 @@ pub async fn build_statistics_for_building(
     })
 }
 
-/// Рассчитать общую сумму расходов от всех финансовых операций за последний год.
-async fn get_total_expenses_last_year(pool: &PgPool) -> Result<String, Error> {
-    let total_expense = query_scalar!(
+/// Собрать данные для печатного отчёта за период: сводку, аварии по типам и помесячные расходы.
+pub async fn build_period_report(
+    pool: &PgPool,
+    period: Period,
+    cost_attribution: CostAttribution,
+    timezone: Tz,
+    currency: &str,
+) -> Result<PeriodReport, Error> {
+    let (summary, incidents_by_type, monthly_expenses) = tokio::try_join!(
+        build_statistics_for_building(pool, period, cost_attribution, currency),
+        get_incident_counts_by_type(pool, period, None),
+        get_monthly_expenses(
+            pool,
+            period,
+            None,
+            currency,
+            timezone,
+            ExpenseBreakdown::default()
+        ),
+    )?;
+
+    Ok(PeriodReport {
+        summary,
+        incidents_by_type,
+        monthly_expenses,
+    })
+}
+
+/// Собрать статистику одного дома за период; помесячный ряд строится по часовому поясу компании.
+pub async fn build_single_building_statistics(
+    pool: &PgPool,
+    building_id: Uuid,
+    period: Period,
+    cost_attribution: CostAttribution,
+    timezone: Tz,
+    currency: &str,
+) -> Result<BuildingStatistics, Error> {
+    let building = sqlx::query!(
         r#"
         SELECT
-            SUM(amount)::text AS total_expenses
+            b.number,
+            a.region,
+            a.city,
+            a.street,
+            (
+                SELECT COALESCE(SUM(ap.square_metres), 0)::float
+                FROM apartment ap
+                WHERE ap.building_id = b.id AND ap.deleted_at IS NULL
+            ) AS "total_square_metres!"
         FROM
-            financial_operation
+            building b
+        JOIN
+            address a ON b.address_id = a.id
         WHERE
-            type IN ('withdrawal', 'payment', 'adjustment')
-            AND happen_at >= NOW() - INTERVAL '1 year';
-        "#
+            b.id = $1
+            AND b.deleted_at IS NULL
+        "#,
+        building_id
     )
-    .fetch_one(pool)
-    .await?;
+    .fetch_optional(pool)
+    .await?
+    .ok_or(Error::BuildingNotFound)?;
 
-    Ok(total_expense.unwrap())
+    let (
+        summary,
+        total_cost,
+        repair_counts,
+        incident_costs,
+        incident_counts_by_type,
+        incident_counts_by_status,
+        mean_hours_to_resolve,
+        monthly,
+    ) = tokio::try_join!(
+        get_summary(pool, period, cost_attribution, Some(building_id), currency),
+        get_building_repair_cost(pool, period, cost_attribution, building_id, currency),
+        get_repair_counts(pool, period, Some(building_id)),
+        get_total_costs_by_incident_type(
+            pool,
+            period,
+            cost_attribution,
+            Some(building_id),
+            currency
+        ),
+        get_incident_counts_by_type(pool, period, Some(building_id)),
+        get_incident_counts_by_status(pool, period, building_id),
+        get_mean_hours_to_resolve(pool, period, building_id),
+        get_building_monthly_statistics(
+            pool,
+            period,
+            cost_attribution,
+            building_id,
+            timezone,
+            currency
+        ),
+    )?;
+
+    let cost_per_square_metre = Decimal::try_from(building.total_square_metres)
+        .ok()
+        .filter(|square_metres| !square_metres.is_zero())
+        .map(|square_metres| Money((total_cost.0 / square_metres).round_dp(2)));
+
+    Ok(BuildingStatistics {
+        building_id,
+        building_number: building.number,
+        address: format_address(
+            building.region,
+            building.city,
+            building.street,
+            building.number,
+        ),
+        currency: currency.to_string(),
+        total_incidents: summary.total_incidents,
+        total_cost,
+        total_square_metres: building.total_square_metres,
+        cost_per_square_metre,
+        mean_hours_to_resolve,
+        repair_counts,
+        incident_costs,
+        incident_counts_by_type,
+        incident_counts_by_status,
+        monthly,
+    })
 }
 
-/// Рассчитать процент изменения расходов за текущий год по сравнению с прошлым.
-async fn get_percent_changes_in_expense_from_last_year(pool: &PgPool) -> Result<String, Error> {
-    let changes_in_percents = query_scalar!(
+/// Оценить риск аварий по всем домам за период и отсортировать дома от самого рискованного.
+///
+/// Затраты на ремонт относятся к периоду по дате начала ремонта или по дате оплаты,
+/// возраст здания считается на конец периода.
+pub async fn build_risk_ranking(
+    pool: &PgPool,
+    period: Period,
+    cost_attribution: CostAttribution,
+    currency: &str,
+) -> Result<RiskRanking, Error> {
+    let buildings = sqlx::query!(
         r#"
-        WITH current_year AS (
-            SELECT SUM(amount)::numeric::float AS expenses
-            FROM financial_operation
-            WHERE type IN ('withdrawal', 'payment', 'adjustment')
-              AND date_trunc('year', happen_at) = date_trunc('year', NOW())
-        ),
-        previous_year AS (
-            SELECT SUM(amount)::numeric::float AS expenses
-            FROM financial_operation
-            WHERE type IN ('withdrawal', 'payment', 'adjustment')
-              AND date_trunc('year', happen_at) = date_trunc('year', NOW() - INTERVAL '1 year')
-        )
         SELECT
-            CASE
-                WHEN previous_year.expenses = 0 THEN NULL
-                ELSE (current_year.expenses - previous_year.expenses) / previous_year.expenses * 100
-            END AS percent_change
+            b.id,
+            b.number,
+            b.construction_date,
+            b.number_of_floors,
+            a.region AS "region?",
+            a.city AS "city?",
+            a.street AS "street?",
+            (
+                SELECT COALESCE(SUM(ap.square_metres), 0)::float
+                FROM apartment ap
+                WHERE ap.building_id = b.id AND ap.deleted_at IS NULL
+            ) AS "total_square_metres!",
+            (
+                SELECT COUNT(*)
+                FROM incident i
+                WHERE
+                    i.building_id = b.id
+                    AND i.deleted_at IS NULL
+                    AND i.reported_at >= $1
+                    AND i.reported_at < $2
+            ) AS "incidents!",
+            (
+                SELECT COUNT(*)
+                FROM repair r
+                WHERE
+                    r.building_id = b.id
+                    AND r.deleted_at IS NULL
+                    AND r.started_at >= $1
+                    AND r.started_at < $2
+            ) AS "repairs!",
+            (
+                SELECT COUNT(*)
+                FROM repair r
+                WHERE
+                    r.building_id = b.id
+                    AND r.deleted_at IS NULL
+                    AND r.type = 'emergency'
+                    AND r.started_at >= $1
+                    AND r.started_at < $2
+            ) AS "emergency_repairs!",
+            (
+                SELECT COALESCE(SUM(fo.amount), 0)
+                FROM repair r
+                JOIN financial_operation fo ON r.id = fo.repair_id
+                WHERE
+                    r.building_id = b.id
+                    AND r.deleted_at IS NULL
+                    AND fo.type IN ('withdrawal', 'payment', 'adjustment')
+                    AND CASE WHEN $3
+                        THEN fo.happen_at >= $1 AND fo.happen_at < $2
+                        ELSE r.started_at >= $1 AND r.started_at < $2
+                    END
+                    AND fo.currency = $4
+            ) AS "repair_cost!: Money"
         FROM
-            current_year, previous_year;
-    "#
+            building b
+        LEFT JOIN
+            address a ON b.address_id = a.id
+        WHERE
+            b.deleted_at IS NULL
+        ORDER BY
+            b.number, b.id
+        "#,
+        period.start,
+        period.end,
+        cost_attribution == CostAttribution::PaymentDate,
+        currency
     )
-    .fetch_one(pool)
+    .fetch_all(pool)
     .await?;
 
-    let percent_change = changes_in_percents.unwrap_or(0.0);
-    let formatted_percent_change = format_percents_with_sign(percent_change);
-    Ok(formatted_percent_change)
+    let years = (period.end - period.start).num_seconds() as f64 / SECONDS_PER_YEAR;
+    let as_of = period.end.date_naive();
+    let inputs: Vec<RiskInputs> = buildings
+        .iter()
+        .map(|building| RiskInputs {
+            incidents_per_year: building.incidents as f64 / years,
+            emergency_repair_ratio: if building.repairs == 0 {
+                0.0
+            } else {
+                building.emergency_repairs as f64 / building.repairs as f64
+            },
+            age_years: ((as_of - building.construction_date).num_days() as f64 / DAYS_PER_YEAR)
+                .max(0.0),
+            floors: f64::from(building.number_of_floors),
+            repair_cost_per_square_metre: if building.total_square_metres > 0.0 {
+                building.repair_cost.to_f64() / building.total_square_metres
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    let mut ranking: Vec<BuildingRisk> = buildings
+        .into_iter()
+        .zip(risk::score_buildings(&inputs))
+        .map(|(building, risk)| BuildingRisk {
+            rank: 0,
+            building_id: building.id,
+            building_number: building.number,
+            address: format_address(
+                building.region,
+                building.city,
+                building.street,
+                building.number,
+            ),
+            score: risk.score,
+            factors: risk.factors,
+        })
+        .collect();
+    ranking.sort_by(|a, b| b.score.total_cmp(&a.score));
+    for (rank, building) in (1..).zip(&mut ranking) {
+        building.rank = rank;
+    }
+
+    Ok(RiskRanking {
+        currency: currency.to_string(),
+        buildings: ranking,
+    })
 }
 
-/// Подсчитать общее количество завершенных ремонтов за последний год.
-async fn get_count_of_repairs_last_year(pool: &PgPool) -> Result<i64, Error> {
-    let count_repairs = query_scalar!(
-        r#"
-        SELECT
-            COUNT(*) AS total_repairs
-        FROM
-            repair
-        WHERE
-            ended_at IS NOT NULL
-            AND started_at >= NOW() - INTERVAL '1 year';
-    "#
+fn format_address(
+    region: Option<String>,
+    city: Option<String>,
+    street: Option<String>,
+    number: i32,
+) -> String {
+    format!(
+        "{}, {}, {}, дом {}",
+        region.unwrap_or_default(),
+        city.unwrap_or_default(),
+        street.unwrap_or_default(),
+        number
+    )
+}
+
+/// Собрать ряд аварий и расходов на ремонт с шагом `granularity` и запрошенные разбивки аварий.
+pub async fn build_trend_statistics(
+    pool: &PgPool,
+    period: Period,
+    granularity: TrendGranularity,
+    building_id: Option<Uuid>,
+    breakdown: TrendBreakdown,
+    currency: &str,
+    timezone: Tz,
+) -> Result<TrendStatistics, Error> {
+    let (series, by_incident_type, by_building) = tokio::try_join!(
+        get_trend_series(pool, period, granularity, building_id, currency, timezone),
+        async {
+            if !breakdown.by_incident_type {
+                return Ok(None);
+            }
+            get_incident_trends_by_type(pool, period, granularity, building_id, timezone)
+                .await
+                .map(Some)
+        },
+        async {
+            if !breakdown.by_building {
+                return Ok(None);
+            }
+            get_incident_trends_by_building(pool, period, granularity, building_id, timezone)
+                .await
+                .map(Some)
+        },
+    )?;
+
+    Ok(TrendStatistics {
+        granularity,
+        currency: currency.to_string(),
+        series,
+        by_incident_type,
+        by_building,
+    })
+}
+
+/// Сравнить аварии и расходы на ремонт за год `year` с предыдущим годом помесячно.
+pub async fn build_year_over_year_trends(
+    pool: &PgPool,
+    year: i32,
+    building_id: Option<Uuid>,
+    currency: &str,
+    timezone: Tz,
+) -> Result<YearOverYearTrends, Error> {
+    let out_of_range = || Error::UnprocessableEntity(format!("year {year} is out of range"));
+    let current = Period::year(year, timezone).ok_or_else(out_of_range)?;
+    let previous = Period::year(year - 1, timezone).ok_or_else(out_of_range)?;
+
+    let (current_series, previous_series) = tokio::try_join!(
+        get_trend_series(
+            pool,
+            current,
+            TrendGranularity::Month,
+            building_id,
+            currency,
+            timezone
+        ),
+        get_trend_series(
+            pool,
+            previous,
+            TrendGranularity::Month,
+            building_id,
+            currency,
+            timezone
+        ),
+    )?;
+
+    let months = (1..)
+        .zip(current_series.iter().zip(&previous_series))
+        .map(|(month, (current, previous))| YearOverYearMonth {
+            month,
+            incidents: count_over_year(current.incidents, previous.incidents),
+            repair_spend: money_over_year(current.repair_spend, previous.repair_spend),
+        })
+        .collect();
+
+    Ok(YearOverYearTrends {
+        currency: currency.to_string(),
+        year,
+        previous_year: year - 1,
+        incidents: count_over_year(
+            current_series.iter().map(|point| point.incidents).sum(),
+            previous_series.iter().map(|point| point.incidents).sum(),
+        ),
+        repair_spend: money_over_year(
+            Money(
+                current_series
+                    .iter()
+                    .map(|point| point.repair_spend.0)
+                    .sum(),
+            ),
+            Money(
+                previous_series
+                    .iter()
+                    .map(|point| point.repair_spend.0)
+                    .sum(),
+            ),
+        ),
+        months,
+    })
+}
+
+fn count_over_year(current: i64, previous: i64) -> YearOverYearValue<i64> {
+    YearOverYearValue {
+        current,
+        previous,
+        change: format_percents_with_sign(percent_change(current as f64, previous as f64)),
+    }
+}
+
+fn money_over_year(current: Money, previous: Money) -> YearOverYearValue<Money> {
+    YearOverYearValue {
+        current,
+        previous,
+        change: format_percents_with_sign(percent_change(current.to_f64(), previous.to_f64())),
+    }
+}
+
+/// Разложить помесячный ряд показателя `metric` на тренд, сезонность и остаток.
+pub async fn build_seasonal_decomposition(
+    pool: &PgPool,
+    period: Period,
+    metric: TrendMetric,
+    building_id: Option<Uuid>,
+    currency: &str,
+    timezone: Tz,
+) -> Result<SeasonalDecomposition, Error> {
+    let series = get_trend_series(
+        pool,
+        period,
+        TrendGranularity::Month,
+        building_id,
+        currency,
+        timezone,
     )
-    .fetch_one(pool)
     .await?;
+    let observed: Vec<f64> = series
+        .iter()
+        .map(|point| metric_value(point, metric))
+        .collect();
+    let first_month = series
+        .first()
+        .map_or(0, |point| point.start.month0() as usize);
+    let decomposition = trends::decompose(&observed, first_month).ok_or_else(|| {
+        Error::UnprocessableEntity("at least two years of history are required".to_string())
+    })?;
+
+    let months = series
+        .into_iter()
+        .zip(observed)
+        .enumerate()
+        .map(|(i, (point, observed))| DecomposedPoint {
+            start: point.start,
+            label: point.label,
+            observed,
+            trend: decomposition.trend[i].map(round_to_cents),
+            seasonal: round_to_cents(decomposition.seasonal[i]),
+            residual: decomposition.residual[i].map(round_to_cents),
+        })
+        .collect();
 
-    Ok(count_repairs.unwrap())
+    Ok(SeasonalDecomposition {
+        metric,
+        currency: currency.to_string(),
+        months,
+        seasonal_indices: (1..)
+            .zip(decomposition.indices)
+            .map(|(month, index)| SeasonalIndex {
+                month,
+                index: round_to_cents(index),
+            })
+            .collect(),
+    })
 }
 
-/// Рассчитать процент изменения количества завершенных ремонтов за текущий год по сравнению с предыдущим.
-async fn get_percent_changes_in_count_repair_last_year(pool: &PgPool) -> Result<String, Error> {
-    let changes_in_percents = query_scalar!(
-        r#"
-        WITH current_year AS (
-            SELECT COUNT(*) AS repairs
-            FROM repair
-            WHERE ended_at IS NOT NULL
-              AND date_trunc('year', started_at) = date_trunc('year', NOW())
+/// Спрогнозировать аварии и расходы на ремонт до конца квартала, следующего за месяцем `month`.
+///
+/// История — полные месяцы периода `history`, заканчивающегося в начале `month`;
+/// сам `month` ещё не закончился и тоже прогнозируется.
+pub async fn build_trend_forecast(
+    pool: &PgPool,
+    month: NaiveDate,
+    history: Period,
+    building_id: Option<Uuid>,
+    currency: &str,
+    timezone: Tz,
+) -> Result<TrendForecast, Error> {
+    let series = get_trend_series(
+        pool,
+        history,
+        TrendGranularity::Month,
+        building_id,
+        currency,
+        timezone,
+    )
+    .await?;
+
+    let months_to_quarter = 3 - month.month0() % 3;
+    let quarter_start = month + Months::new(months_to_quarter);
+    let horizon = months_to_quarter as usize + 3;
+    let first_month = series
+        .first()
+        .map_or(0, |point| point.start.month0() as usize);
+
+    let forecast_metric = |metric: TrendMetric| {
+        let values: Vec<f64> = series
+            .iter()
+            .map(|point| metric_value(point, metric))
+            .collect();
+        let forecast = trends::forecast(&values, first_month, horizon);
+        MetricForecast {
+            method: forecast.method,
+            quarter_total: round_to_cents(forecast.values[horizon - 3..].iter().sum()),
+            moving_average_total: round_to_cents(forecast.moving_average * 3.0),
+            months: (0..)
+                .zip(forecast.values)
+                .map(|(h, value)| {
+                    let start = month + Months::new(h);
+                    ForecastPoint {
+                        start,
+                        label: TrendGranularity::Month.label(start),
+                        value: round_to_cents(value),
+                    }
+                })
+                .collect(),
+        }
+    };
+
+    Ok(TrendForecast {
+        currency: currency.to_string(),
+        history_months: series.len() as u32,
+        quarter: format!(
+            "{}-Q{}",
+            quarter_start.year(),
+            quarter_start.month0() / 3 + 1
         ),
-        previous_year AS (
-            SELECT COUNT(*) AS repairs
-            FROM repair
-            WHERE ended_at IS NOT NULL
-              AND date_trunc('year', started_at) = date_trunc('year', NOW() - INTERVAL '1 year')
-        )
+        quarter_start,
+        incidents: forecast_metric(TrendMetric::Incidents),
+        repair_spend: forecast_metric(TrendMetric::RepairSpend),
+    })
+}
+
+fn metric_value(point: &TrendPoint, metric: TrendMetric) -> f64 {
+    match metric {
+        TrendMetric::Incidents => point.incidents as f64,
+        TrendMetric::RepairSpend => point.repair_spend.to_f64(),
+    }
+}
+
+fn round_to_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Рассчитать сумму расходов от всех финансовых операций за период.
+async fn get_expenses(
+    pool: &PgPool,
+    period: Period,
+    building_id: Option<Uuid>,
+    currency: &str,
+) -> Result<Money, Error> {
+    let expenses = query_scalar!(
+        r#"
         SELECT
-            CASE
-                WHEN previous_year.repairs = 0 THEN NULL
-                ELSE ((current_year.repairs - previous_year.repairs) / previous_year.repairs * 100.0)::numeric::float
-            END AS percent_change
+            COALESCE(SUM(fo.amount), 0) AS "total!: Money"
         FROM
-            current_year, previous_year;
-    "#
+            financial_operation fo
+        LEFT JOIN
+            repair r ON fo.repair_id = r.id
+        WHERE
+            fo.type IN ('withdrawal', 'payment', 'adjustment')
+            AND fo.happen_at >= $1
+            AND fo.happen_at < $2
+            AND ($3::uuid IS NULL OR r.building_id = $3)
+            AND fo.currency = $4;
+        "#,
+        period.start,
+        period.end,
+        building_id,
+        currency
     )
     .fetch_one(pool)
     .await?;
 
-    let percent_change = changes_in_percents.unwrap_or(0.0) + 34.23; //TODO: remove after testing
-    let formatted_percent_change = format_percents_with_sign(percent_change);
-    Ok(formatted_percent_change)
+    Ok(expenses)
 }
 
-/// Получить количество текущих активных заявок на ремонт.
-async fn get_count_repair_requests(pool: &PgPool) -> Result<i64, Error> {
-    let count_active_requests = query_scalar!(
+/// Подсчитать количество завершенных ремонтов, начатых за период.
+async fn get_count_of_repairs(
+    pool: &PgPool,
+    period: Period,
+    building_id: Option<Uuid>,
+) -> Result<i64, Error> {
+    let count_repairs = query_scalar!(
         r#"
         SELECT
-            COUNT(*) AS active_incidents
+            COUNT(*) AS "total_repairs!"
         FROM
-            incident
+            repair
         WHERE
-            status IN ('reported', 'in_progress');
-    "#
+            deleted_at IS NULL
+            AND ended_at IS NOT NULL
+            AND started_at >= $1
+            AND started_at < $2
+            AND ($3::uuid IS NULL OR building_id = $3);
+    "#,
+        period.start,
+        period.end,
+        building_id
     )
     .fetch_one(pool)
     .await?;
 
-    Ok(count_active_requests.unwrap())
+    Ok(count_repairs)
 }
 
-/// Подсчитать изменение количества активных заявок на ремонт в этот год по сравнению с предыдущим.
-async fn get_percent_changes_in_active_repair_requests_last_year(
+/// Получить количество заявок на ремонт, остававшихся активными на конец периода.
+async fn get_count_repair_requests(
     pool: &PgPool,
-) -> Result<String, Error> {
-    let changes_in_percents = query_scalar!(
+    period: Period,
+    building_id: Option<Uuid>,
+) -> Result<i64, Error> {
+    let count_active_requests = query_scalar!(
         r#"
-        WITH current_year AS (
-            SELECT COUNT(*) AS active_incidents
-            FROM incident
-            WHERE status IN ('reported', 'in_progress')
-              AND reported_at >= date_trunc('year', NOW())
-        ),
-        previous_year AS (
-            SELECT COUNT(*) AS active_incidents
-            FROM incident
-            WHERE status IN ('reported', 'in_progress')
-              AND reported_at >= date_trunc('year', NOW() - INTERVAL '1 year')
-              AND reported_at < date_trunc('year', NOW())
-        )
         SELECT
-            CASE
-                WHEN previous_year.active_incidents = 0 THEN NULL
-                ELSE ((current_year.active_incidents - previous_year.active_incidents) / previous_year.active_incidents * 100.0)::numeric::float
-            END AS percent_change
+            COUNT(*) AS "active_incidents!"
         FROM
-            current_year, previous_year;
-    "#
+            incident
+        WHERE
+            deleted_at IS NULL
+            AND reported_at < $1
+            AND status <> 'cancelled'
+            AND (
+                (resolved_at IS NULL AND status IN ('reported', 'in_progress'))
+                OR resolved_at >= $1
+            )
+            AND ($2::uuid IS NULL OR building_id = $2);
+    "#,
+        period.end,
+        building_id
     )
     .fetch_one(pool)
     .await?;
 
-    let percent_change = changes_in_percents.unwrap_or(0.0) + 29.2; //TODO: remove after testing
-    let formatted_percent_change = format_percents_with_sign(percent_change);
+    Ok(count_active_requests)
+}
 
-    Ok(formatted_percent_change)
+/// Процент изменения `current` относительно `previous`; ноль, если сравнивать не с чем.
+fn percent_change(current: f64, previous: f64) -> f64 {
+    if previous == 0.0 {
+        0.0
+    } else {
+        (current - previous) / previous * 100.0
+    }
 }
 
 fn format_percents_with_sign(percent_change: f64) -> String {
@@ -228,100 +759,284 @@ fn format_percents_with_sign(percent_change: f64) -> String {
     formatted_percent_change
 }
 
-/// Подсчитать общее количество сотрудников.
-async fn get_count_of_employees(pool: &PgPool) -> Result<i64, Error> {
+/// Подсчитать количество сотрудников, работающих на конец периода.
+async fn get_count_of_employees(pool: &PgPool, period: Period) -> Result<i64, Error> {
     let employee_count = query_scalar!(
         r#"
         SELECT
-            COUNT(*) AS total_employees
+            COUNT(*) AS "total_employees!"
         FROM
-            employee;
-    "#
+            employee
+        WHERE
+            deleted_at IS NULL
+            AND started_at < $1
+            AND (ended_at IS NULL OR ended_at >= $1);
+    "#,
+        period.end
     )
     .fetch_one(pool)
     .await?;
 
-    Ok(employee_count.unwrap())
+    Ok(employee_count)
 }
 
-/// Рассчитать количество новых сотрудников в этом году.
-async fn get_count_new_employee_last_year(pool: &PgPool) -> Result<i64, Error> {
+/// Рассчитать количество сотрудников, принятых на работу за период.
+async fn get_count_new_employees(pool: &PgPool, period: Period) -> Result<i64, Error> {
     let new_employees_count = query_scalar!(
         r#"
-        SELECT COUNT(*) AS employees
+        SELECT COUNT(*) AS "employees!"
         FROM employee
-        WHERE started_at >= date_trunc('year', NOW());
-    "#
+        WHERE deleted_at IS NULL AND started_at >= $1 AND started_at < $2;
+    "#,
+        period.start,
+        period.end
     )
     .fetch_one(pool)
     .await?;
 
-    Ok(new_employees_count.unwrap_or(0) - 14) //TODO: remove after testing
+    Ok(new_employees_count)
 }
 
-/// Рассчитать распределение затрат по месяцам за последний год.
-async fn get_expence_distribution_by_month_last_year(
+/// Помесячный ряд расходов за период в хронологическом порядке.
+///
+/// Месяцы берутся из `period_buckets` в часовом поясе компании, поэтому месяцы без расходов
+/// не пропадают, а одинаковые месяцы разных лет не склеиваются. Разбивки тоже заполнены нулями:
+/// по всем типам расходных операций и по всем домам, у которых были расходы за период.
+async fn get_monthly_expenses(
     pool: &PgPool,
+    period: Period,
+    building_id: Option<Uuid>,
+    currency: &str,
+    timezone: Tz,
+    breakdown: ExpenseBreakdown,
 ) -> Result<Vec<MonthlyExpenses>, Error> {
-    let distributions = query_as_unchecked!(
-        MonthlyExpenses,
-        r#"
-        SELECT
-            TO_CHAR(happen_at, 'TMMon')::text AS name,
-            SUM(amount)::Numeric::Int AS total
-        FROM
-            financial_operation
-        WHERE
-            type IN ('withdrawal', 'payment', 'adjustment')
-            AND happen_at >= NOW() - INTERVAL '1 year'
-        GROUP BY
-            TO_CHAR(happen_at, 'TMMon'),
-            EXTRACT(MONTH FROM happen_at)
-        ORDER BY
-            EXTRACT(MONTH FROM happen_at);
-            "#
-    )
-    .fetch_all(pool)
-    .await?;
+    let (totals, by_operation_type, by_building) = tokio::try_join!(
+        sqlx::query!(
+            r#"
+            WITH expenses AS (
+                SELECT fo.happen_at, fo.amount
+                FROM financial_operation fo
+                LEFT JOIN repair r ON fo.repair_id = r.id
+                WHERE
+                    fo.type IN ('withdrawal', 'payment', 'adjustment')
+                    AND fo.happen_at >= $1
+                    AND fo.happen_at < $2
+                    AND ($4::uuid IS NULL OR r.building_id = $4)
+                    AND fo.currency = $5
+            )
+            SELECT
+                TO_CHAR(m.month, 'YYYY-MM') AS "month!",
+                TO_CHAR(m.month, 'TMMon') AS "name!",
+                COALESCE(SUM(e.amount), 0) AS "total!: Money"
+            FROM
+                period_buckets($1, $2, $3, 'month') AS m(month, month_start, month_end)
+            LEFT JOIN
+                expenses e ON e.happen_at >= m.month_start AND e.happen_at < m.month_end
+            GROUP BY
+                m.month
+            ORDER BY
+                m.month
+            "#,
+            period.start,
+            period.end,
+            timezone.name(),
+            building_id,
+            currency
+        )
+        .fetch_all(pool),
+        async {
+            if !breakdown.by_operation_type {
+                return Ok(Vec::new());
+            }
+            sqlx::query!(
+                r#"
+                WITH expenses AS (
+                    SELECT fo.happen_at, fo.amount, fo.type
+                    FROM financial_operation fo
+                    LEFT JOIN repair r ON fo.repair_id = r.id
+                    WHERE
+                        fo.type IN ('withdrawal', 'payment', 'adjustment')
+                        AND fo.happen_at >= $1
+                        AND fo.happen_at < $2
+                        AND ($4::uuid IS NULL OR r.building_id = $4)
+                        AND fo.currency = $5
+                )
+                SELECT
+                    TO_CHAR(m.month, 'YYYY-MM') AS "month!",
+                    t.type AS "operation_type!: FinancialOperationType",
+                    COALESCE(SUM(e.amount), 0) AS "total!: Money"
+                FROM
+                    period_buckets($1, $2, $3, 'month') AS m(month, month_start, month_end)
+                CROSS JOIN
+                    unnest(ARRAY['withdrawal', 'payment', 'adjustment']::financial_operation_type[]) AS t(type)
+                LEFT JOIN
+                    expenses e ON e.type = t.type
+                        AND e.happen_at >= m.month_start
+                        AND e.happen_at < m.month_end
+                GROUP BY
+                    m.month, t.type
+                ORDER BY
+                    m.month, t.type
+                "#,
+                period.start,
+                period.end,
+                timezone.name(),
+                building_id,
+                currency
+            )
+            .fetch_all(pool)
+            .await
+        },
+        async {
+            if !breakdown.by_building {
+                return Ok(Vec::new());
+            }
+            sqlx::query!(
+                r#"
+                WITH expenses AS (
+                    SELECT fo.happen_at, fo.amount, r.building_id
+                    FROM financial_operation fo
+                    LEFT JOIN repair r ON fo.repair_id = r.id
+                    WHERE
+                        fo.type IN ('withdrawal', 'payment', 'adjustment')
+                        AND fo.happen_at >= $1
+                        AND fo.happen_at < $2
+                        AND ($4::uuid IS NULL OR r.building_id = $4)
+                        AND fo.currency = $5
+                ),
+                buildings AS (
+                    SELECT DISTINCT e.building_id, b.number
+                    FROM expenses e
+                    LEFT JOIN building b ON e.building_id = b.id
+                )
+                SELECT
+                    TO_CHAR(m.month, 'YYYY-MM') AS "month!",
+                    bs.building_id AS "building_id?",
+                    bs.number AS "building_number?",
+                    COALESCE(SUM(e.amount), 0) AS "total!: Money"
+                FROM
+                    period_buckets($1, $2, $3, 'month') AS m(month, month_start, month_end)
+                CROSS JOIN
+                    buildings bs
+                LEFT JOIN
+                    expenses e ON e.building_id IS NOT DISTINCT FROM bs.building_id
+                        AND e.happen_at >= m.month_start
+                        AND e.happen_at < m.month_end
+                GROUP BY
+                    m.month, bs.building_id, bs.number
+                ORDER BY
+                    m.month, bs.number NULLS LAST
+                "#,
+                period.start,
+                period.end,
+                timezone.name(),
+                building_id,
+                currency
+            )
+            .fetch_all(pool)
+            .await
+        },
+    )?;
+
+    let mut months: Vec<MonthlyExpenses> = totals
+        .into_iter()
+        .map(|row| MonthlyExpenses {
+            month: row.month,
+            name: row.name,
+            total: row.total,
+            by_operation_type: breakdown.by_operation_type.then(Vec::new),
+            by_building: breakdown.by_building.then(Vec::new),
+        })
+        .collect();
+    let index: HashMap<String, usize> = months
+        .iter()
+        .enumerate()
+        .map(|(i, month)| (month.month.clone(), i))
+        .collect();
 
-    Ok(distributions)
+    for row in by_operation_type {
+        if let Some(items) = index
+            .get(&row.month)
+            .and_then(|&i| months[i].by_operation_type.as_mut())
+        {
+            items.push(OperationTypeExpenses {
+                operation_type: row.operation_type,
+                total: row.total,
+            });
+        }
+    }
+    for row in by_building {
+        if let Some(items) = index
+            .get(&row.month)
+            .and_then(|&i| months[i].by_building.as_mut())
+        {
+            items.push(BuildingExpenses {
+                building_id: row.building_id,
+                building_number: row.building_number,
+                total: row.total,
+            });
+        }
+    }
+
+    Ok(months)
 }
 
-async fn get_total_incidents_last_year(pool: &PgPool) -> Result<i64, Error> {
+/// Подсчитать количество аварий, зарегистрированных за период.
+async fn get_total_incidents(
+    pool: &PgPool,
+    period: Period,
+    building_id: Option<Uuid>,
+) -> Result<i64, Error> {
     let total_incidents = sqlx::query_scalar!(
         r#"
-        SELECT COUNT(*)
+        SELECT COUNT(*) AS "count!"
         FROM incident
-        WHERE reported_at >= NOW() - INTERVAL '1 year';
-        "#
+        WHERE deleted_at IS NULL
+            AND reported_at >= $1
+            AND reported_at < $2
+            AND ($3::uuid IS NULL OR building_id = $3);
+        "#,
+        period.start,
+        period.end,
+        building_id
     )
     .fetch_one(pool)
     .await?;
 
-    Ok(total_incidents.unwrap_or(0))
+    Ok(total_incidents)
 }
 
-async fn get_top_5_incident_types_last_year(pool: &PgPool) -> Result<Vec<IncidentTypeInfo>, Error> {
-    let total_incidents = get_total_incidents_last_year(pool).await?;
-
+/// Получить пять самых частых типов аварий за период и их долю от `total_incidents`.
+async fn get_top_5_incident_types(
+    pool: &PgPool,
+    period: Period,
+    building_id: Option<Uuid>,
+    total_incidents: i64,
+) -> Result<Vec<IncidentTypeInfo>, Error> {
     let incident_types = sqlx::query!(
         r#"
         SELECT
             it.id,
             it.name,
-            COUNT(i.id) AS count,
-            (COUNT(i.id) * 100.0 / $1)::numeric::float AS percentage
+            COUNT(i.id) AS "count!",
+            (COUNT(i.id) * 100.0 / NULLIF($4, 0))::numeric::float AS percentage
         FROM
             incident_type it
             JOIN incident i ON i.incident_type_id = it.id
         WHERE
-            i.reported_at >= NOW() - INTERVAL '1 year'
+            i.deleted_at IS NULL
+            AND i.reported_at >= $1
+            AND i.reported_at < $2
+            AND ($3::uuid IS NULL OR i.building_id = $3)
         GROUP BY
             it.id, it.name
         ORDER BY
-            count DESC
+            "count!" DESC, it.name
         LIMIT 5;
         "#,
+        period.start,
+        period.end,
+        building_id,
         total_incidents as f64
     )
     .fetch_all(pool)
@@ -332,8 +1047,8 @@ async fn get_top_5_incident_types_last_year(pool: &PgPool) -> Result<Vec<Inciden
         .map(|record| IncidentTypeInfo {
             id: record.id,
             name: record.name,
-            count: record.count.unwrap(),
-            percentage: format!("{:.2}%", record.percentage.unwrap()),
+            count: record.count,
+            percentage: format!("{:.2}%", record.percentage.unwrap_or(0.0)),
         })
         .collect();
 
@@ -343,8 +1058,8 @@ async fn get_top_5_incident_types_last_year(pool: &PgPool) -> Result<Vec<Inciden
 /// Число аварийных и плановых ремонтов за период
 async fn get_repair_counts(
     pool: &PgPool,
-    start_date: DateTime<Utc>,
-    end_date: DateTime<Utc>,
+    period: Period,
+    building_id: Option<Uuid>,
 ) -> Result<RepairCount, Error> {
     let result = sqlx::query!(
         r#"
@@ -354,10 +1069,14 @@ async fn get_repair_counts(
         FROM
             repair r
         WHERE
-            r.started_at BETWEEN $1 AND $2
+            r.deleted_at IS NULL
+            AND r.started_at >= $1
+            AND r.started_at < $2
+            AND ($3::uuid IS NULL OR r.building_id = $3)
         "#,
-        start_date,
-        end_date
+        period.start,
+        period.end,
+        building_id
     )
     .fetch_one(pool)
     .await?;
@@ -372,30 +1091,48 @@ async fn get_repair_counts(
     })
 }
 
-/// Получение общего числа аварий и суммарных затрат за период
+/// Получение общего числа аварий и суммарных затрат на их устранение за период
+///
+/// Затраты относятся к периоду по дате аварии или по дате оплаты в зависимости от `cost_attribution`.
 async fn get_summary(
     pool: &PgPool,
-    start_date: DateTime<Utc>,
-    end_date: DateTime<Utc>,
+    period: Period,
+    cost_attribution: CostAttribution,
+    building_id: Option<Uuid>,
+    currency: &str,
 ) -> Result<BuildingSummary, Error> {
     let result = sqlx::query_as_unchecked!(
         BuildingSummary,
         r#"
         SELECT
-            COUNT(i.id) AS total_incidents,
-            COALESCE(SUM(fo.amount)::NUMERIC::FLOAT, 0) AS total_cost
-        FROM
-            incident i
-        JOIN
-            repair r ON i.id = r.incident_id
-        JOIN
-            financial_operation fo ON r.id = fo.repair_id
-        WHERE
-            i.reported_at BETWEEN $1 AND $2
-            AND fo.happen_at BETWEEN $1 AND $2
+            (
+                SELECT COUNT(*)
+                FROM incident
+                WHERE deleted_at IS NULL
+                    AND reported_at >= $1 AND reported_at < $2
+                    AND ($4::uuid IS NULL OR building_id = $4)
+            ) AS total_incidents,
+            (
+                SELECT COALESCE(SUM(fo.amount), 0)
+                FROM incident i
+                JOIN repair r ON i.id = r.incident_id
+                JOIN financial_operation fo ON r.id = fo.repair_id
+                WHERE
+                    i.deleted_at IS NULL
+                    AND r.deleted_at IS NULL
+                    AND CASE WHEN $3
+                        THEN fo.happen_at >= $1 AND fo.happen_at < $2
+                        ELSE i.reported_at >= $1 AND i.reported_at < $2
+                    END
+                    AND ($4::uuid IS NULL OR i.building_id = $4)
+                    AND fo.currency = $5
+            ) AS total_cost
         "#,
-        start_date,
-        end_date
+        period.start,
+        period.end,
+        cost_attribution == CostAttribution::PaymentDate,
+        building_id,
+        currency
     )
     .fetch_one(pool)
     .await?;
@@ -403,18 +1140,58 @@ async fn get_summary(
     Ok(result)
 }
 
+/// Затраты на все ремонты дома, включая плановые; ремонт без аварии относится к периоду
+/// по дате начала, если затраты считаются по дате аварии.
+async fn get_building_repair_cost(
+    pool: &PgPool,
+    period: Period,
+    cost_attribution: CostAttribution,
+    building_id: Uuid,
+    currency: &str,
+) -> Result<Money, Error> {
+    let total_cost = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(fo.amount), 0) AS "total_cost!: Money"
+        FROM repair r
+        LEFT JOIN incident i ON r.incident_id = i.id
+        JOIN financial_operation fo ON r.id = fo.repair_id
+        WHERE
+            r.building_id = $4
+            AND r.deleted_at IS NULL
+            AND i.deleted_at IS NULL
+            AND CASE WHEN $3
+                THEN fo.happen_at >= $1 AND fo.happen_at < $2
+                ELSE COALESCE(i.reported_at, r.started_at) >= $1
+                    AND COALESCE(i.reported_at, r.started_at) < $2
+            END
+            AND fo.currency = $5
+        "#,
+        period.start,
+        period.end,
+        cost_attribution == CostAttribution::PaymentDate,
+        building_id,
+        currency
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(total_cost)
+}
+
 /// Получение суммарных затрат на устранение аварий по типам инцидентов
 async fn get_total_costs_by_incident_type(
     pool: &PgPool,
-    start_date: DateTime<Utc>,
-    end_date: DateTime<Utc>,
+    period: Period,
+    cost_attribution: CostAttribution,
+    building_id: Option<Uuid>,
+    currency: &str,
 ) -> Result<Vec<IncidentCost>, Error> {
     let results = sqlx::query_as_unchecked!(
         IncidentCost,
         r#"
         SELECT
             it.name AS incident_type,
-            COALESCE(SUM(fo.amount)::NUMERIC::FLOAT, 0) AS total_cost
+            COALESCE(SUM(fo.amount), 0) AS total_cost
         FROM
             financial_operation fo
         JOIN
@@ -424,14 +1201,23 @@ async fn get_total_costs_by_incident_type(
         JOIN
             incident_type it ON i.incident_type_id = it.id
         WHERE
-            i.reported_at BETWEEN $1 AND $2
-            AND fo.happen_at BETWEEN $1 AND $2
+            i.deleted_at IS NULL
+            AND r.deleted_at IS NULL
+            AND CASE WHEN $3
+                THEN fo.happen_at >= $1 AND fo.happen_at < $2
+                ELSE i.reported_at >= $1 AND i.reported_at < $2
+            END
+            AND ($4::uuid IS NULL OR i.building_id = $4)
+            AND fo.currency = $5
         GROUP BY
             it.name
         ORDER BY total_cost DESC
         "#,
-        start_date,
-        end_date
+        period.start,
+        period.end,
+        cost_attribution == CostAttribution::PaymentDate,
+        building_id,
+        currency
     )
     .fetch_all(pool)
     .await?;
@@ -440,10 +1226,13 @@ async fn get_total_costs_by_incident_type(
 }
 
 /// Получение 10 домов с наибольшими тратами на ремонты, с количеством ремонтов за указанный период
+///
+/// При отнесении затрат по дате аварии используется дата начала ремонта.
 async fn get_top_10_buildings_by_repair_costs(
     pool: &PgPool,
-    start_date: DateTime<Utc>,
-    end_date: DateTime<Utc>,
+    period: Period,
+    cost_attribution: CostAttribution,
+    currency: &str,
 ) -> Result<Vec<BuildingRepairCost>, Error> {
     let results = sqlx::query_as_unchecked!(
         BuildingRepairCost,
@@ -451,8 +1240,8 @@ async fn get_top_10_buildings_by_repair_costs(
         SELECT
             b.id AS building_id,
             b.number AS building_number,
-            COALESCE(SUM(fo.amount)::NUMERIC::FLOAT, 0) AS total_cost,
-            COUNT(r.id) AS repair_count
+            COALESCE(SUM(fo.amount), 0) AS total_cost,
+            COUNT(DISTINCT r.id) AS repair_count
         FROM
             building b
         JOIN
@@ -460,19 +1249,1146 @@ async fn get_top_10_buildings_by_repair_costs(
         JOIN
             financial_operation fo ON r.id = fo.repair_id
         WHERE
-            r.started_at BETWEEN $1 AND $2
-            AND fo.happen_at BETWEEN $1 AND $2
+            b.deleted_at IS NULL
+            AND r.deleted_at IS NULL
+            AND CASE WHEN $3
+                THEN fo.happen_at >= $1 AND fo.happen_at < $2
+                ELSE r.started_at >= $1 AND r.started_at < $2
+            END
+            AND fo.currency = $4
         GROUP BY
             b.id, b.number
         ORDER BY
             total_cost DESC
         LIMIT 10;
         "#,
-        start_date,
-        end_date
+        period.start,
+        period.end,
+        cost_attribution == CostAttribution::PaymentDate,
+        currency
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(results)
+}
+
+/// Число аварий за период по типам, по всем домам или по одному
+async fn get_incident_counts_by_type(
+    pool: &PgPool,
+    period: Period,
+    building_id: Option<Uuid>,
+) -> Result<Vec<IncidentTypeCount>, Error> {
+    let results = sqlx::query_as!(
+        IncidentTypeCount,
+        r#"
+        SELECT
+            it.name AS incident_type,
+            COUNT(i.id) AS "count!"
+        FROM
+            incident i
+        JOIN
+            incident_type it ON i.incident_type_id = it.id
+        WHERE
+            i.deleted_at IS NULL
+            AND ($3::uuid IS NULL OR i.building_id = $3)
+            AND i.reported_at >= $1
+            AND i.reported_at < $2
+        GROUP BY
+            it.name
+        ORDER BY
+            "count!" DESC, it.name
+        "#,
+        period.start,
+        period.end,
+        building_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(results)
+}
+
+/// Число аварий в доме за период по статусам
+async fn get_incident_counts_by_status(
+    pool: &PgPool,
+    period: Period,
+    building_id: Uuid,
+) -> Result<Vec<IncidentStatusCount>, Error> {
+    let results = sqlx::query!(
+        r#"
+        SELECT
+            i.status AS "status: IncidentStatus",
+            COUNT(*) AS "count!"
+        FROM
+            incident i
+        WHERE
+            i.building_id = $3
+            AND i.deleted_at IS NULL
+            AND i.reported_at >= $1
+            AND i.reported_at < $2
+        GROUP BY
+            i.status
+        ORDER BY
+            i.status
+        "#,
+        period.start,
+        period.end,
+        building_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(results
+        .into_iter()
+        .map(|row| IncidentStatusCount {
+            status: row.status.to_string(),
+            count: row.count,
+        })
+        .collect())
+}
+
+/// Среднее время устранения аварий в доме, зарегистрированных за период, в часах
+async fn get_mean_hours_to_resolve(
+    pool: &PgPool,
+    period: Period,
+    building_id: Uuid,
+) -> Result<Option<f64>, Error> {
+    let mean_hours = query_scalar!(
+        r#"
+        SELECT
+            AVG(EXTRACT(EPOCH FROM i.resolved_at - i.reported_at) / 3600)::float
+        FROM
+            incident i
+        WHERE
+            i.building_id = $3
+            AND i.deleted_at IS NULL
+            AND i.resolved_at IS NOT NULL
+            AND i.reported_at >= $1
+            AND i.reported_at < $2
+        "#,
+        period.start,
+        period.end,
+        building_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(mean_hours)
+}
+
+/// Помесячная динамика аварий, ремонтов и затрат по дому за период
+async fn get_building_monthly_statistics(
+    pool: &PgPool,
+    period: Period,
+    cost_attribution: CostAttribution,
+    building_id: Uuid,
+    timezone: Tz,
+    currency: &str,
+) -> Result<Vec<BuildingMonthStatistics>, Error> {
+    let results = sqlx::query_as!(
+        BuildingMonthStatistics,
+        r#"
+        SELECT
+            TO_CHAR(m.month, 'YYYY-MM') AS "month!",
+            (
+                SELECT COUNT(*)
+                FROM incident i
+                WHERE i.building_id = $4
+                    AND i.deleted_at IS NULL
+                    AND i.reported_at >= m.month_start
+                    AND i.reported_at < m.month_end
+            ) AS "incidents!",
+            (
+                SELECT COUNT(*)
+                FROM repair r
+                WHERE r.building_id = $4
+                    AND r.deleted_at IS NULL
+                    AND r.started_at >= m.month_start
+                    AND r.started_at < m.month_end
+            ) AS "repairs!",
+            (
+                SELECT COALESCE(SUM(fo.amount), 0)
+                FROM repair r
+                LEFT JOIN incident i ON r.incident_id = i.id
+                JOIN financial_operation fo ON r.id = fo.repair_id
+                WHERE r.building_id = $4
+                    AND r.deleted_at IS NULL
+                    AND i.deleted_at IS NULL
+                    AND fo.currency = $6
+                    AND CASE WHEN $5
+                        THEN fo.happen_at >= m.month_start AND fo.happen_at < m.month_end
+                        ELSE COALESCE(i.reported_at, r.started_at) >= m.month_start
+                            AND COALESCE(i.reported_at, r.started_at) < m.month_end
+                    END
+            ) AS "total_cost!: Money"
+        FROM
+            period_buckets($1, $2, $3, 'month') AS m(month, month_start, month_end)
+        ORDER BY
+            m.month
+        "#,
+        period.start,
+        period.end,
+        timezone.name(),
+        building_id,
+        cost_attribution == CostAttribution::PaymentDate,
+        currency
     )
     .fetch_all(pool)
     .await?;
 
     Ok(results)
 }
+
+/// Ряд аварий и расходов на ремонт с шагом `granularity`.
+///
+/// Шаги берутся из `period_buckets` в часовом поясе компании, как и в помесячных расходах;
+/// крайние шаги обрезаются границами периода. Расходы относятся к шагу по дате операции.
+async fn get_trend_series(
+    pool: &PgPool,
+    period: Period,
+    granularity: TrendGranularity,
+    building_id: Option<Uuid>,
+    currency: &str,
+    timezone: Tz,
+) -> Result<Vec<TrendPoint>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            b.bucket::date AS "start!",
+            (
+                SELECT COUNT(*)
+                FROM incident i
+                WHERE
+                    i.deleted_at IS NULL
+                    AND i.reported_at >= b.bucket_start
+                    AND i.reported_at < b.bucket_end
+                    AND ($5::uuid IS NULL OR i.building_id = $5)
+            ) AS "incidents!",
+            (
+                SELECT COALESCE(SUM(fo.amount), 0)
+                FROM financial_operation fo
+                JOIN repair r ON fo.repair_id = r.id
+                WHERE
+                    fo.type IN ('withdrawal', 'payment', 'adjustment')
+                    AND fo.happen_at >= b.bucket_start
+                    AND fo.happen_at < b.bucket_end
+                    AND ($5::uuid IS NULL OR r.building_id = $5)
+                    AND fo.currency = $6
+            ) AS "repair_spend!: Money"
+        FROM
+            period_buckets($1, $2, $3, $4) AS b
+        ORDER BY
+            b.bucket
+        "#,
+        period.start,
+        period.end,
+        timezone.name(),
+        granularity.unit(),
+        building_id,
+        currency
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| TrendPoint {
+            label: granularity.label(row.start),
+            start: row.start,
+            incidents: row.incidents,
+            repair_spend: row.repair_spend,
+        })
+        .collect())
+}
+
+/// Ряды аварий по типам, встречавшимся за период; шаги без аварий заполнены нулями.
+async fn get_incident_trends_by_type(
+    pool: &PgPool,
+    period: Period,
+    granularity: TrendGranularity,
+    building_id: Option<Uuid>,
+    timezone: Tz,
+) -> Result<Vec<IncidentTypeTrend>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        WITH incidents AS (
+            SELECT i.reported_at, i.incident_type_id
+            FROM incident i
+            WHERE
+                i.deleted_at IS NULL
+                AND i.reported_at >= $1
+                AND i.reported_at < $2
+                AND ($5::uuid IS NULL OR i.building_id = $5)
+        ),
+        types AS (
+            SELECT DISTINCT inc.incident_type_id, it.name
+            FROM incidents inc
+            LEFT JOIN incident_type it ON inc.incident_type_id = it.id
+        )
+        SELECT
+            b.bucket::date AS "start!",
+            t.incident_type_id AS "incident_type_id?",
+            t.name AS "incident_type?",
+            COUNT(inc.reported_at) AS "count!"
+        FROM
+            period_buckets($1, $2, $3, $4) AS b
+        CROSS JOIN
+            types t
+        LEFT JOIN
+            incidents inc ON inc.incident_type_id IS NOT DISTINCT FROM t.incident_type_id
+                AND inc.reported_at >= b.bucket_start
+                AND inc.reported_at < b.bucket_end
+        GROUP BY
+            t.incident_type_id, t.name, b.bucket
+        ORDER BY
+            t.name NULLS LAST, t.incident_type_id, b.bucket
+        "#,
+        period.start,
+        period.end,
+        timezone.name(),
+        granularity.unit(),
+        building_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut trends: Vec<IncidentTypeTrend> = Vec::new();
+    for row in rows {
+        let point = TrendCount {
+            label: granularity.label(row.start),
+            start: row.start,
+            count: row.count,
+        };
+        match trends.last_mut() {
+            Some(trend) if trend.incident_type_id == row.incident_type_id => {
+                trend.points.push(point)
+            }
+            _ => trends.push(IncidentTypeTrend {
+                incident_type_id: row.incident_type_id,
+                incident_type: row.incident_type,
+                points: vec![point],
+            }),
+        }
+    }
+
+    Ok(trends)
+}
+
+/// Ряды аварий по домам, в которых были аварии за период; шаги без аварий заполнены нулями.
+async fn get_incident_trends_by_building(
+    pool: &PgPool,
+    period: Period,
+    granularity: TrendGranularity,
+    building_id: Option<Uuid>,
+    timezone: Tz,
+) -> Result<Vec<BuildingTrend>, Error> {
+    let rows = sqlx::query!(
+        r#"
+        WITH incidents AS (
+            SELECT i.reported_at, i.building_id
+            FROM incident i
+            WHERE
+                i.deleted_at IS NULL
+                AND i.reported_at >= $1
+                AND i.reported_at < $2
+                AND ($5::uuid IS NULL OR i.building_id = $5)
+        ),
+        buildings AS (
+            SELECT DISTINCT b.id, b.number
+            FROM incidents inc
+            JOIN building b ON inc.building_id = b.id
+            WHERE b.deleted_at IS NULL
+        )
+        SELECT
+            b.bucket::date AS "start!",
+            bs.id AS "building_id!",
+            bs.number AS "building_number!",
+            COUNT(inc.reported_at) AS "count!"
+        FROM
+            period_buckets($1, $2, $3, $4) AS b
+        CROSS JOIN
+            buildings bs
+        LEFT JOIN
+            incidents inc ON inc.building_id = bs.id
+                AND inc.reported_at >= b.bucket_start
+                AND inc.reported_at < b.bucket_end
+        GROUP BY
+            bs.id, bs.number, b.bucket
+        ORDER BY
+            bs.number, bs.id, b.bucket
+        "#,
+        period.start,
+        period.end,
+        timezone.name(),
+        granularity.unit(),
+        building_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut trends: Vec<BuildingTrend> = Vec::new();
+    for row in rows {
+        let point = TrendCount {
+            label: granularity.label(row.start),
+            start: row.start,
+            count: row.count,
+        };
+        match trends.last_mut() {
+            Some(trend) if trend.building_id == row.building_id => trend.points.push(point),
+            _ => trends.push(BuildingTrend {
+                building_id: row.building_id,
+                building_number: row.building_number,
+                points: vec![point],
+            }),
+        }
+    }
+
+    Ok(trends)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Utc};
+    use chrono_tz::Tz;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::api::statistics::models::{
+        ForecastMethod, QueryTimeDiapasonParams, RiskFactorKind, TrendParams, YearOverviewParams,
+    };
+
+    const BUILDING_ON_LENINA: Uuid = Uuid::from_u128(0xb1);
+    const BUILDING_IN_KHIMKI: Uuid = Uuid::from_u128(0xb2);
+    const TIMEZONE: Tz = Tz::UTC;
+    const CURRENCY: &str = "RUB";
+
+    fn money(amount: &str) -> Money {
+        amount.parse().unwrap()
+    }
+
+    fn year_2024() -> (Period, Period) {
+        YearOverviewParams {
+            year: Some(2024),
+            ..Default::default()
+        }
+        .periods(Utc::now(), TIMEZONE)
+        .unwrap()
+    }
+
+    #[test]
+    fn year_is_compared_with_previous_calendar_year() {
+        let (current, previous) = year_2024();
+
+        assert_eq!(current, Period::year(2024, TIMEZONE).unwrap());
+        assert_eq!(previous, Period::year(2023, TIMEZONE).unwrap());
+    }
+
+    #[test]
+    fn date_range_is_compared_with_preceding_range_of_same_length() {
+        let params = YearOverviewParams {
+            start_date: NaiveDate::from_ymd_opt(2024, 3, 1),
+            end_date: NaiveDate::from_ymd_opt(2024, 3, 10),
+            ..Default::default()
+        };
+
+        let (current, previous) = params.periods(Utc::now(), TIMEZONE).unwrap();
+
+        assert_eq!(previous.end, current.start);
+        assert_eq!(previous.end - previous.start, chrono::Duration::days(10));
+    }
+
+    #[test]
+    fn inverted_or_mixed_periods_are_rejected() {
+        let inverted = YearOverviewParams {
+            start_date: NaiveDate::from_ymd_opt(2024, 3, 10),
+            end_date: NaiveDate::from_ymd_opt(2024, 3, 1),
+            ..Default::default()
+        };
+        let mixed = YearOverviewParams {
+            year: Some(2024),
+            start_date: NaiveDate::from_ymd_opt(2024, 3, 1),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            inverted.periods(Utc::now(), TIMEZONE),
+            Err(Error::UnprocessableEntity(_))
+        ));
+        assert!(matches!(
+            mixed.periods(Utc::now(), TIMEZONE),
+            Err(Error::UnprocessableEntity(_))
+        ));
+    }
+
+    #[test]
+    fn periods_at_the_edge_of_the_calendar_are_rejected() {
+        for params in [
+            YearOverviewParams {
+                year: Some(NaiveDate::MIN.year()),
+                ..Default::default()
+            },
+            YearOverviewParams {
+                start_date: Some(NaiveDate::MIN),
+                end_date: NaiveDate::from_ymd_opt(NaiveDate::MIN.year(), 3, 1),
+                ..Default::default()
+            },
+        ] {
+            assert!(
+                matches!(
+                    params.periods(Utc::now(), TIMEZONE),
+                    Err(Error::UnprocessableEntity(_))
+                ),
+                "{params:?}"
+            );
+        }
+
+        for year in [NaiveDate::MIN.year() + 1, NaiveDate::MAX.year() - 1] {
+            let params = YearOverviewParams {
+                year: Some(year),
+                ..Default::default()
+            };
+            assert!(params.periods(Utc::now(), TIMEZONE).is_ok(), "{year}");
+        }
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn year_overview_for_past_year(pool: PgPool) {
+        let (current, previous) = year_2024();
+
+        let statistics = build_year_overview_statistics(
+            &pool,
+            current,
+            previous,
+            None,
+            CURRENCY,
+            TIMEZONE,
+            ExpenseBreakdown::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(statistics.total_expenses_last_year, money("68000.25"));
+        assert_eq!(
+            statistics.percent_changes_in_expense_from_last_year,
+            "+353.32%"
+        );
+        assert_eq!(statistics.count_of_repairs_last_year, 2);
+        assert_eq!(
+            statistics.percent_changes_in_count_repair_last_year,
+            "+100.00%"
+        );
+        assert_eq!(statistics.count_of_active_repair_requests, 2);
+        assert_eq!(
+            statistics.percent_changes_in_active_repair_requests_last_year,
+            "+0.00%"
+        );
+        assert_eq!(statistics.count_of_employees, 3);
+        assert_eq!(statistics.count_new_employee_last_year, 2);
+        assert_eq!(statistics.total_incidents_last_year, 3);
+
+        let top_types: Vec<_> = statistics
+            .top_5_incident_types_last_year
+            .iter()
+            .map(|info| (info.name.as_str(), info.count, info.percentage.as_str()))
+            .collect();
+        assert_eq!(
+            top_types,
+            [
+                ("Отключение электричества", 2, "66.67%"),
+                ("Протечка", 1, "33.33%")
+            ]
+        );
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn year_overview_filtered_by_building(pool: PgPool) {
+        let (current, previous) = year_2024();
+
+        let statistics = build_year_overview_statistics(
+            &pool,
+            current,
+            previous,
+            Some(BUILDING_ON_LENINA),
+            CURRENCY,
+            TIMEZONE,
+            ExpenseBreakdown::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            statistics.percent_changes_in_expense_from_last_year,
+            "+33.33%"
+        );
+        assert_eq!(statistics.count_of_repairs_last_year, 0);
+        assert_eq!(
+            statistics.percent_changes_in_count_repair_last_year,
+            "-100.00%"
+        );
+        assert_eq!(statistics.count_of_active_repair_requests, 1);
+        assert_eq!(statistics.total_incidents_last_year, 1);
+        assert_eq!(statistics.top_5_incident_types_last_year.len(), 1);
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn monthly_expenses_are_chronological_and_zero_filled(pool: PgPool) {
+        let period = Period::between_dates(
+            NaiveDate::from_ymd_opt(2023, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            TIMEZONE,
+        )
+        .unwrap();
+
+        let months = get_monthly_expenses(
+            &pool,
+            period,
+            None,
+            CURRENCY,
+            TIMEZONE,
+            ExpenseBreakdown::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(months.len(), 13);
+        assert_eq!(months[0].month, "2023-03");
+        assert_eq!(months[0].total, money("15000.50"));
+        assert_eq!(months[1].month, "2023-04");
+        assert_eq!(months[1].total, money("0.00"));
+        assert_eq!(months[10].month, "2024-01");
+        assert_eq!(months[10].total, money("20000.00"));
+        assert_eq!(months[12].month, "2024-03");
+        assert_eq!(months[12].total, money("5000.25"));
+        assert!(months[0].by_operation_type.is_none());
+        assert!(months[0].by_building.is_none());
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn monthly_expenses_breakdowns(pool: PgPool) {
+        let (current, _) = year_2024();
+
+        let months = get_monthly_expenses(
+            &pool,
+            current,
+            None,
+            CURRENCY,
+            TIMEZONE,
+            ExpenseBreakdown {
+                by_operation_type: true,
+                by_building: true,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(months.len(), 12);
+        let june = &months[5];
+        assert_eq!(june.month, "2024-06");
+
+        let by_operation_type: Vec<_> = june
+            .by_operation_type
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|item| (item.operation_type.to_string(), item.total))
+            .collect();
+        assert_eq!(
+            by_operation_type,
+            [
+                ("Списание".to_string(), money("40000.00")),
+                ("Оплата".to_string(), money("0.00")),
+                ("Корректировка".to_string(), money("0.00")),
+            ]
+        );
+
+        for month in &months {
+            let by_building = month.by_building.as_ref().unwrap();
+            assert_eq!(by_building.len(), 2);
+            let sum = by_building
+                .iter()
+                .fold(Decimal::ZERO, |sum, item| sum + item.total.0);
+            assert_eq!(Money(sum), month.total);
+        }
+    }
+
+    fn march_2024(cost_attribution: CostAttribution) -> QueryTimeDiapasonParams {
+        QueryTimeDiapasonParams {
+            start_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            cost_attribution,
+        }
+    }
+
+    #[test]
+    fn period_days_follow_company_timezone() {
+        let period = march_2024(CostAttribution::IncidentDate)
+            .period(Tz::Europe__Moscow)
+            .unwrap();
+
+        assert_eq!(period.start.to_rfc3339(), "2024-02-29T21:00:00+00:00");
+        assert_eq!(period.end.to_rfc3339(), "2024-03-31T21:00:00+00:00");
+    }
+
+    #[test]
+    fn inverted_date_range_is_rejected() {
+        let params = QueryTimeDiapasonParams {
+            start_date: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            cost_attribution: CostAttribution::default(),
+        };
+
+        assert!(matches!(
+            params.period(TIMEZONE),
+            Err(Error::UnprocessableEntity(_))
+        ));
+    }
+
+    #[test]
+    fn date_range_ending_on_the_last_day_is_rejected() {
+        let params = QueryTimeDiapasonParams {
+            start_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            end_date: NaiveDate::MAX,
+            cost_attribution: CostAttribution::default(),
+        };
+
+        assert!(matches!(
+            params.period(TIMEZONE),
+            Err(Error::UnprocessableEntity(_))
+        ));
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn building_statistics_include_last_day_and_later_payments(pool: PgPool) {
+        let period = march_2024(CostAttribution::IncidentDate)
+            .period(TIMEZONE)
+            .unwrap();
+
+        let statistics =
+            build_statistics_for_building(&pool, period, CostAttribution::IncidentDate, CURRENCY)
+                .await
+                .unwrap();
+
+        assert_eq!(statistics.total_incidents, 2);
+        assert_eq!(statistics.total_cost, money("8000.25"));
+        assert_eq!(statistics.repair_counts.emergency_repairs, 1);
+        assert_eq!(statistics.top_buildings_by_repair_cost.len(), 1);
+        assert_eq!(statistics.top_buildings_by_repair_cost[0].repair_count, 1);
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn building_statistics_by_payment_date(pool: PgPool) {
+        let period = march_2024(CostAttribution::PaymentDate)
+            .period(TIMEZONE)
+            .unwrap();
+
+        let statistics =
+            build_statistics_for_building(&pool, period, CostAttribution::PaymentDate, CURRENCY)
+                .await
+                .unwrap();
+
+        assert_eq!(statistics.total_incidents, 2);
+        assert_eq!(statistics.total_cost, money("5000.25"));
+        assert_eq!(statistics.incident_costs[0].total_cost, money("5000.25"));
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn operations_in_other_currencies_are_not_summed(pool: PgPool) {
+        sqlx::query!(
+            r#"
+            INSERT INTO financial_operation (amount, currency, happen_at, description, repair_id, type)
+            VALUES (100, 'USD', '2024-03-22 11:00+00', 'Импортные детали', '00000000-0000-0000-0000-000000000303', 'payment')
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let period = march_2024(CostAttribution::PaymentDate)
+            .period(TIMEZONE)
+            .unwrap();
+
+        let roubles =
+            build_statistics_for_building(&pool, period, CostAttribution::PaymentDate, CURRENCY)
+                .await
+                .unwrap();
+        let dollars =
+            build_statistics_for_building(&pool, period, CostAttribution::PaymentDate, "USD")
+                .await
+                .unwrap();
+
+        assert_eq!(roubles.total_cost, money("5000.25"));
+        assert_eq!(dollars.currency, "USD");
+        assert_eq!(dollars.total_cost, money("100.00"));
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn period_report_covers_all_buildings(pool: PgPool) {
+        let period = march_2024(CostAttribution::IncidentDate)
+            .period(TIMEZONE)
+            .unwrap();
+
+        let report = build_period_report(
+            &pool,
+            period,
+            CostAttribution::IncidentDate,
+            TIMEZONE,
+            CURRENCY,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.summary.total_cost, money("8000.25"));
+        let incidents_by_type: Vec<_> = report
+            .incidents_by_type
+            .iter()
+            .map(|count| (count.incident_type.as_str(), count.count))
+            .collect();
+        assert_eq!(incidents_by_type, [("Отключение электричества", 2)]);
+        assert_eq!(report.monthly_expenses.len(), 1);
+        assert_eq!(report.monthly_expenses[0].total, money("5000.25"));
+    }
+
+    fn trend_params(
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        granularity: TrendGranularity,
+    ) -> TrendParams {
+        TrendParams {
+            start_date,
+            end_date,
+            granularity,
+            building_id: None,
+            by_incident_type: false,
+            by_building: false,
+        }
+    }
+
+    #[test]
+    fn too_long_trend_is_rejected() {
+        let start = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+
+        assert!(matches!(
+            trend_params(start, end, TrendGranularity::Week).period(TIMEZONE),
+            Err(Error::UnprocessableEntity(_))
+        ));
+        assert!(trend_params(start, end, TrendGranularity::Month)
+            .period(TIMEZONE)
+            .is_ok());
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn weekly_trend_is_zero_filled(pool: PgPool) {
+        let period = trend_params(
+            NaiveDate::from_ymd_opt(2024, 3, 11).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            TrendGranularity::Week,
+        )
+        .period(TIMEZONE)
+        .unwrap();
+
+        let trends = build_trend_statistics(
+            &pool,
+            period,
+            TrendGranularity::Week,
+            None,
+            TrendBreakdown::default(),
+            CURRENCY,
+            TIMEZONE,
+        )
+        .await
+        .unwrap();
+
+        let series: Vec<_> = trends
+            .series
+            .iter()
+            .map(|point| (point.label.as_str(), point.incidents, point.repair_spend))
+            .collect();
+        assert_eq!(
+            series,
+            [
+                ("2024-W11", 0, money("0")),
+                ("2024-W12", 1, money("5000.25")),
+                ("2024-W13", 1, money("0")),
+            ]
+        );
+        assert!(trends.by_incident_type.is_none());
+        assert!(trends.by_building.is_none());
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn monthly_trend_breakdowns(pool: PgPool) {
+        let (current, _) = year_2024();
+
+        let trends = build_trend_statistics(
+            &pool,
+            current,
+            TrendGranularity::Month,
+            None,
+            TrendBreakdown {
+                by_incident_type: true,
+                by_building: true,
+            },
+            CURRENCY,
+            TIMEZONE,
+        )
+        .await
+        .unwrap();
+
+        let by_type = trends.by_incident_type.unwrap();
+        let types: Vec<_> = by_type
+            .iter()
+            .map(|trend| {
+                (
+                    trend.incident_type.as_deref(),
+                    trend.points.len(),
+                    trend.points.iter().map(|point| point.count).sum::<i64>(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            types,
+            [
+                (Some("Отключение электричества"), 12, 2),
+                (Some("Протечка"), 12, 1)
+            ]
+        );
+        assert_eq!(by_type[0].points[2].label, "2024-03");
+        assert_eq!(by_type[0].points[2].count, 2);
+
+        let by_building = trends.by_building.unwrap();
+        let buildings: Vec<_> = by_building
+            .iter()
+            .map(|trend| (trend.building_number, trend.points[0].count))
+            .collect();
+        assert_eq!(buildings, [(5, 1), (12, 0)]);
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn year_over_year_trends(pool: PgPool) {
+        let trends = build_year_over_year_trends(&pool, 2024, None, CURRENCY, TIMEZONE)
+            .await
+            .unwrap();
+
+        assert_eq!(trends.previous_year, 2023);
+        assert_eq!(trends.incidents.current, 3);
+        assert_eq!(trends.incidents.previous, 1);
+        assert_eq!(trends.incidents.change, "+200.00%");
+        assert_eq!(trends.repair_spend.current, money("68000.25"));
+        assert_eq!(trends.repair_spend.previous, money("15000.50"));
+
+        assert_eq!(trends.months.len(), 12);
+        let march = &trends.months[2];
+        assert_eq!(march.month, 3);
+        assert_eq!(march.incidents.current, 2);
+        assert_eq!(march.incidents.previous, 1);
+        assert_eq!(march.repair_spend.change, "-66.67%");
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn seasonal_decomposition_of_two_years(pool: PgPool) {
+        let month = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let period = Period::months_before(month, 24, TIMEZONE);
+
+        let decomposition = build_seasonal_decomposition(
+            &pool,
+            period,
+            TrendMetric::Incidents,
+            None,
+            CURRENCY,
+            TIMEZONE,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(decomposition.months.len(), 24);
+        assert_eq!(decomposition.months[0].label, "2023-01");
+        assert_eq!(decomposition.months[0].trend, None);
+        assert!(decomposition.months[6].trend.is_some());
+        assert_eq!(decomposition.seasonal_indices.len(), 12);
+        let total: f64 = decomposition
+            .seasonal_indices
+            .iter()
+            .map(|index| index.index)
+            .sum();
+        assert!(total.abs() < 0.1);
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn too_short_history_is_not_decomposed(pool: PgPool) {
+        let month = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let period = Period::months_before(month, 12, TIMEZONE);
+
+        let result = build_seasonal_decomposition(
+            &pool,
+            period,
+            TrendMetric::RepairSpend,
+            None,
+            CURRENCY,
+            TIMEZONE,
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::UnprocessableEntity(_))));
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn forecast_covers_months_until_end_of_next_quarter(pool: PgPool) {
+        let month = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+        let history = Period::months_before(month, 24, TIMEZONE);
+
+        let forecast = build_trend_forecast(&pool, month, history, None, CURRENCY, TIMEZONE)
+            .await
+            .unwrap();
+
+        assert_eq!(forecast.history_months, 24);
+        assert_eq!(forecast.quarter, "2025-Q2");
+        assert_eq!(
+            forecast.quarter_start,
+            NaiveDate::from_ymd_opt(2025, 4, 1).unwrap()
+        );
+        for metric in [&forecast.incidents, &forecast.repair_spend] {
+            assert_eq!(metric.method, ForecastMethod::SeasonalExponentialSmoothing);
+            let labels: Vec<_> = metric
+                .months
+                .iter()
+                .map(|point| point.label.as_str())
+                .collect();
+            assert_eq!(
+                labels,
+                ["2025-02", "2025-03", "2025-04", "2025-05", "2025-06"]
+            );
+            let quarter: f64 = metric.months[2..].iter().map(|point| point.value).sum();
+            assert!((metric.quarter_total - quarter).abs() < 0.05);
+            assert!(metric.months.iter().all(|point| point.value >= 0.0));
+        }
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn risk_ranking_puts_riskiest_building_first(pool: PgPool) {
+        let (current, _) = year_2024();
+
+        let ranking = build_risk_ranking(&pool, current, CostAttribution::IncidentDate, CURRENCY)
+            .await
+            .unwrap();
+
+        let ranks: Vec<_> = ranking
+            .buildings
+            .iter()
+            .map(|building| (building.rank, building.building_number))
+            .collect();
+        assert_eq!(ranks, [(1, 12), (2, 5)]);
+
+        let riskiest = &ranking.buildings[0];
+        assert_eq!(
+            riskiest.factors[0].factor,
+            RiskFactorKind::IncidentFrequency
+        );
+        let cost = riskiest
+            .factors
+            .iter()
+            .find(|factor| factor.factor == RiskFactorKind::RepairCostPerSquareMetre)
+            .unwrap();
+        assert_eq!(cost.value, 480.0);
+        assert_eq!(cost.normalized, 1.0);
+
+        let on_lenina = &ranking.buildings[1];
+        assert_eq!(on_lenina.building_id, BUILDING_ON_LENINA);
+        assert_eq!(
+            on_lenina.factors[0].factor,
+            RiskFactorKind::EmergencyRepairRatio
+        );
+        assert!(on_lenina.score < riskiest.score);
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn single_building_statistics(pool: PgPool) {
+        let period = Period::between_dates(
+            NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+            TIMEZONE,
+        )
+        .unwrap();
+
+        let statistics = build_single_building_statistics(
+            &pool,
+            BUILDING_ON_LENINA,
+            period,
+            CostAttribution::IncidentDate,
+            TIMEZONE,
+            CURRENCY,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(statistics.total_incidents, 2);
+        assert_eq!(statistics.total_cost, money("35000.50"));
+        assert_eq!(statistics.total_square_metres, 120.0);
+        assert_eq!(statistics.cost_per_square_metre, Some(money("291.67")));
+        assert_eq!(statistics.mean_hours_to_resolve, Some(48.0));
+        assert_eq!(statistics.repair_counts.emergency_repairs, 2);
+        assert_eq!(statistics.incident_counts_by_type[0].count, 2);
+        assert_eq!(statistics.incident_counts_by_status.len(), 2);
+
+        assert_eq!(statistics.monthly.len(), 24);
+        let march_2023 = &statistics.monthly[2];
+        assert_eq!(march_2023.month, "2023-03");
+        assert_eq!(march_2023.incidents, 1);
+        assert_eq!(march_2023.repairs, 1);
+        assert_eq!(march_2023.total_cost, money("15000.50"));
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn single_building_statistics_include_scheduled_repairs(pool: PgPool) {
+        let period = Period::between_dates(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+            TIMEZONE,
+        )
+        .unwrap();
+
+        let statistics = build_single_building_statistics(
+            &pool,
+            BUILDING_IN_KHIMKI,
+            period,
+            CostAttribution::IncidentDate,
+            TIMEZONE,
+            CURRENCY,
+        )
+        .await
+        .unwrap();
+
+        // Аварийный ремонт за 8000.25 и плановый за 40000 без аварии.
+        assert_eq!(statistics.total_cost, money("48000.25"));
+        assert_eq!(statistics.cost_per_square_metre, Some(money("480.00")));
+        let june = &statistics.monthly[5];
+        assert_eq!(june.month, "2024-06");
+        assert_eq!(june.total_cost, money("40000.00"));
+        assert_eq!(
+            statistics
+                .monthly
+                .iter()
+                .map(|month| month.total_cost.0)
+                .sum::<Decimal>(),
+            statistics.total_cost.0
+        );
+    }
+
+    #[sqlx::test(fixtures("statistics"))]
+    async fn single_building_statistics_for_unknown_building(pool: PgPool) {
+        let (current, _) = year_2024();
+
+        let result = build_single_building_statistics(
+            &pool,
+            Uuid::nil(),
+            current,
+            CostAttribution::IncidentDate,
+            TIMEZONE,
+            CURRENCY,
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::BuildingNotFound)));
+    }
+}
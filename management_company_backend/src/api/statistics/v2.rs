@@ -0,0 +1,304 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Response,
+    routing::get,
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::api::{
+    envelope::{Envelope, ErrorEnvelope},
+    export::ExportParams,
+    extractor::AuthUser,
+    ApiContext, Error,
+};
+
+use super::{
+    controllers,
+    models::{
+        BuildingStatistics, ForecastParams, QueryTimeDiapasonParams, RiskRanking,
+        SeasonalDecomposition, SeasonalityParams, SummaryStatistics, TrendForecast, TrendParams,
+        TrendStatistics, YearOverYearParams, YearOverYearTrends, YearOverviewParams,
+        YearOverviewStatistics,
+    },
+};
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route(
+            "/api/v2/statistics/year_overview",
+            get(get_year_overview_statistics),
+        )
+        .route(
+            "/api/v2/statistics/year_overview/export",
+            get(export_year_overview_statistics),
+        )
+        .route("/api/v2/statistics/buildings", get(get_building_statistics))
+        .route(
+            "/api/v2/statistics/buildings/export",
+            get(export_building_statistics),
+        )
+        .route(
+            "/api/v2/statistics/buildings/report",
+            get(get_period_report),
+        )
+        .route(
+            "/api/v2/statistics/buildings/:id",
+            get(get_single_building_statistics),
+        )
+        .route("/api/v2/statistics/risk", get(get_risk_ranking))
+        .route("/api/v2/statistics/trends", get(get_trend_statistics))
+        .route(
+            "/api/v2/statistics/trends/year_over_year",
+            get(get_year_over_year_trends),
+        )
+        .route(
+            "/api/v2/statistics/trends/seasonality",
+            get(get_seasonal_decomposition),
+        )
+        .route(
+            "/api/v2/statistics/trends/forecast",
+            get(get_trend_forecast),
+        )
+}
+
+/// Сводка за год, за период или за последние двенадцать месяцев в сравнении с предыдущими.
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/year_overview",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(YearOverviewParams),
+    responses(
+        (status = 200, description = "Сводка за год или период", body = Envelope<YearOverviewStatistics>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_year_overview_statistics(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<YearOverviewParams>,
+) -> Result<Json<Envelope<YearOverviewStatistics>>, Error> {
+    let Json(statistics) = controllers::get_year_overview_statistics(user, ctx, params).await?;
+    Ok(Envelope::new(statistics))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/year_overview/export",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(YearOverviewParams, ExportParams),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn export_year_overview_statistics(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<YearOverviewParams>,
+    export: Query<ExportParams>,
+) -> Result<Response, Error> {
+    controllers::export_year_overview_statistics(user, ctx, params, export).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/buildings",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(QueryTimeDiapasonParams),
+    responses(
+        (status = 200, description = "Статистика по всем домам за период", body = Envelope<SummaryStatistics>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_building_statistics(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<QueryTimeDiapasonParams>,
+) -> Result<Json<Envelope<SummaryStatistics>>, Error> {
+    let Json(statistics) = controllers::get_building_statistics(user, ctx, params).await?;
+    Ok(Envelope::new(statistics))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/buildings/export",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(QueryTimeDiapasonParams, ExportParams),
+    responses(
+        (status = 200, description = "Файл выгрузки", content(
+            (String = "text/csv; charset=utf-8"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn export_building_statistics(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<QueryTimeDiapasonParams>,
+    export: Query<ExportParams>,
+) -> Result<Response, Error> {
+    controllers::export_building_statistics(user, ctx, params, export).await
+}
+
+/// Печатный отчёт за период в PDF.
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/buildings/report",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(QueryTimeDiapasonParams),
+    responses(
+        (status = 200, description = "Печатный отчёт", body = Vec<u8>, content_type = "application/pdf"),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_period_report(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<QueryTimeDiapasonParams>,
+) -> Result<Response, Error> {
+    controllers::get_period_report(user, ctx, params).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/buildings/{id}",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "Идентификатор дома"), QueryTimeDiapasonParams),
+    responses(
+        (status = 200, description = "Статистика дома за период", body = Envelope<BuildingStatistics>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_single_building_statistics(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    id: Path<Uuid>,
+    params: Query<QueryTimeDiapasonParams>,
+) -> Result<Json<Envelope<BuildingStatistics>>, Error> {
+    let Json(statistics) =
+        controllers::get_single_building_statistics(user, ctx, id, params).await?;
+    Ok(Envelope::new(statistics))
+}
+
+/// Дома, упорядоченные по риску аварий, с вкладом каждого фактора в оценку.
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/risk",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(QueryTimeDiapasonParams),
+    responses(
+        (status = 200, description = "Дома по убыванию оценки риска", body = Envelope<RiskRanking>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_risk_ranking(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<QueryTimeDiapasonParams>,
+) -> Result<Json<Envelope<RiskRanking>>, Error> {
+    let Json(ranking) = controllers::get_risk_ranking(user, ctx, params).await?;
+    Ok(Envelope::new(ranking))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/trends",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(TrendParams),
+    responses(
+        (status = 200, description = "Ряды аварий и расходов", body = Envelope<TrendStatistics>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_trend_statistics(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<TrendParams>,
+) -> Result<Json<Envelope<TrendStatistics>>, Error> {
+    let Json(trends) = controllers::get_trend_statistics(user, ctx, params).await?;
+    Ok(Envelope::new(trends))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/trends/year_over_year",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(YearOverYearParams),
+    responses(
+        (status = 200, description = "Помесячное сравнение года с предыдущим", body = Envelope<YearOverYearTrends>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_year_over_year_trends(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<YearOverYearParams>,
+) -> Result<Json<Envelope<YearOverYearTrends>>, Error> {
+    let Json(trends) = controllers::get_year_over_year_trends(user, ctx, params).await?;
+    Ok(Envelope::new(trends))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/trends/seasonality",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(SeasonalityParams),
+    responses(
+        (status = 200, description = "Тренд, сезонность и остаток по месяцам", body = Envelope<SeasonalDecomposition>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_seasonal_decomposition(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<SeasonalityParams>,
+) -> Result<Json<Envelope<SeasonalDecomposition>>, Error> {
+    let Json(decomposition) = controllers::get_seasonal_decomposition(user, ctx, params).await?;
+    Ok(Envelope::new(decomposition))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/statistics/trends/forecast",
+    tag = "statistics",
+    security(("bearer" = [])),
+    params(ForecastParams),
+    responses(
+        (status = 200, description = "Прогноз на текущий и следующий квартал", body = Envelope<TrendForecast>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 422, description = "Неверный период или параметры", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_trend_forecast(
+    user: AuthUser,
+    ctx: State<ApiContext>,
+    params: Query<ForecastParams>,
+) -> Result<Json<Envelope<TrendForecast>>, Error> {
+    let Json(forecast) = controllers::get_trend_forecast(user, ctx, params).await?;
+    Ok(Envelope::new(forecast))
+}
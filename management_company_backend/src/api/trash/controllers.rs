@@ -0,0 +1,259 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::api::{
+    audit::utils::AuditRecord,
+    envelope::{Envelope, ErrorEnvelope},
+    extractor::AdminUser,
+    pagination::{fetch_page, ListQuery, ListSource, PageParams},
+    ApiContext, Error,
+};
+
+use super::{
+    models::{TrashEntity, TrashEntry, TrashFilter, TrashSortField},
+    utils::restore,
+};
+
+const TRASH: ListSource = ListSource {
+    select: r#"
+        SELECT
+            t.entity_type,
+            t.id,
+            t.deleted_at,
+            t.snapshot
+        "#,
+    from: r#"
+        FROM (
+            SELECT 'employee' AS entity_type, id, deleted_at, to_jsonb(e) - 'search_vector' AS snapshot
+            FROM employee e WHERE deleted_at IS NOT NULL
+            UNION ALL
+            SELECT 'building', id, deleted_at, to_jsonb(b)
+            FROM building b WHERE deleted_at IS NOT NULL
+            UNION ALL
+            SELECT 'apartment', id, deleted_at, to_jsonb(ap)
+            FROM apartment ap WHERE deleted_at IS NOT NULL
+            UNION ALL
+            SELECT 'incident', id, deleted_at, to_jsonb(i) - 'search_vector'
+            FROM incident i WHERE deleted_at IS NOT NULL
+            UNION ALL
+            SELECT 'repair', id, deleted_at, to_jsonb(r)
+            FROM repair r WHERE deleted_at IS NOT NULL
+        ) t
+        "#,
+    condition: "TRUE",
+    id_column: "t.id",
+};
+
+/// Удалённые записи всех видов или одного (`entityType`), сначала удалённые последними.
+#[utoipa::path(
+    get,
+    path = "/api/v2/trash",
+    tag = "trash",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<TrashSortField>, Query, description = "Поле сортировки"),
+        TrashFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница корзины", body = Envelope<Vec<TrashEntry>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 403, description = "Нужны права администратора", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_trash(
+    _: AdminUser,
+    State(ctx): State<ApiContext>,
+    query: ListQuery<TrashFilter, TrashSortField>,
+) -> Result<Json<Envelope<Vec<TrashEntry>>>, Error> {
+    let (entries, page_info) =
+        fetch_page(&ctx.db, &TRASH, &query, |entry: &TrashEntry| entry.id).await?;
+
+    Ok(Envelope::page(entries, page_info))
+}
+
+/// Восстановить запись вместе с записями, удалёнными одновременно с ней.
+#[utoipa::path(
+    post,
+    path = "/api/v2/trash/{entity_type}/{id}/restore",
+    tag = "trash",
+    security(("bearer" = [])),
+    params(
+        ("entity_type" = TrashEntity, Path, description = "Вид записи"),
+        ("id" = Uuid, Path, description = "Идентификатор записи"),
+    ),
+    responses(
+        (status = 204, description = "Запись восстановлена"),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 403, description = "Нужны права администратора", body = ErrorEnvelope),
+        (status = 404, description = "В корзине нет такой записи", body = ErrorEnvelope),
+        (status = 409, description = "Удалён дом или авария, к которой относится запись", body = ErrorEnvelope),
+    )
+)]
+pub async fn restore_from_trash(
+    admin: AdminUser,
+    State(ctx): State<ApiContext>,
+    Path((entity, id)): Path<(TrashEntity, Uuid)>,
+) -> Result<StatusCode, Error> {
+    let mut transaction = ctx.db.begin().await?;
+
+    let (before, after) = restore(&mut transaction, entity, id).await?;
+    AuditRecord::updated(entity.into(), id, before, after)
+        .save(&mut transaction, Some(admin.user_id))
+        .await?;
+    transaction.commit().await?;
+
+    ctx.statistics_cache.invalidate();
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{
+            header::{AUTHORIZATION, IF_MATCH},
+            Method, Request,
+        },
+        Router,
+    };
+    use serde_json::Value;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::api::{api_router, extractor::AuthUser, test_context};
+
+    const PETROVA: Uuid = Uuid::from_u128(0xe2);
+    const LENINA_5: Uuid = Uuid::from_u128(0xb1);
+    const LEAK: Uuid = Uuid::from_u128(0x202);
+    const BASEMENT_REPAIR: Uuid = Uuid::from_u128(0x301);
+
+    async fn sign_up(ctx: &ApiContext, email: &str, is_admin: bool) -> String {
+        let user_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO user_account (email, password_hash, employee_id, is_admin)
+            VALUES (($1::text)::domain_email, '', $2, $3)
+            RETURNING id
+            "#,
+            email,
+            PETROVA,
+            is_admin
+        )
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+        AuthUser { user_id }.to_jwt(ctx)
+    }
+
+    async fn send(app: &Router, method: Method, uri: &str, token: &str) -> (StatusCode, Value) {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(AUTHORIZATION, format!("Bearer {token}"))
+                    // Проверка версий покрыта тестами `conditional`.
+                    .header(IF_MATCH, "*")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    async fn ids(app: &Router, uri: &str, token: &str) -> Vec<Uuid> {
+        let (status, body) = send(app, Method::GET, uri, token).await;
+        assert_eq!(status, StatusCode::OK, "{uri}: {body}");
+        body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_str().unwrap().parse().unwrap())
+            .collect()
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn deleted_records_are_hidden_and_restored_together(pool: PgPool) {
+        let ctx = test_context(pool);
+        let admin = sign_up(&ctx, "admin@example.com", true).await;
+        let clerk = sign_up(&ctx, "clerk@example.com", false).await;
+        let app = api_router(ctx);
+        let risk = "/api/v2/statistics/risk?startDate=2023-01-01&endDate=2024-12-31";
+
+        // Ремонт удалён раньше дома и по отдельности, поэтому с домом не восстанавливается.
+        let uri = format!("/api/v2/repairs/{BASEMENT_REPAIR}");
+        let (status, _) = send(&app, Method::DELETE, &uri, &clerk).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let uri = format!("/api/v2/buildings/{LENINA_5}");
+        let (status, _) = send(&app, Method::DELETE, &uri, &clerk).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = send(&app, Method::DELETE, &uri, &clerk).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        assert!(!ids(&app, "/api/v2/buildings", &clerk)
+            .await
+            .contains(&LENINA_5));
+        assert!(!ids(&app, "/api/v2/incidents", &clerk).await.contains(&LEAK));
+        assert_eq!(ids(&app, "/api/v2/repairs", &clerk).await.len(), 2);
+        let (_, body) = send(&app, Method::GET, risk, &clerk).await;
+        assert_eq!(body["data"]["buildings"].as_array().unwrap().len(), 1);
+        let uri = format!("/api/v2/incidents/{LEAK}");
+        let (status, _) = send(&app, Method::GET, &uri, &clerk).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = send(&app, Method::GET, "/api/v2/trash", &clerk).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (_, body) = send(&app, Method::GET, "/api/v2/trash", &admin).await;
+        assert_eq!(body["meta"]["total"], 1 + 2 + 2 + 2);
+        let (_, body) = send(
+            &app,
+            Method::GET,
+            "/api/v2/trash?entityType=building",
+            &admin,
+        )
+        .await;
+        assert_eq!(body["data"][0]["id"], LENINA_5.to_string());
+        assert_eq!(body["data"][0]["snapshot"]["number"], 5);
+
+        let uri = format!("/api/v2/trash/incident/{LEAK}/restore");
+        let (status, _) = send(&app, Method::POST, &uri, &admin).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let uri = format!("/api/v2/trash/building/{LENINA_5}/restore");
+        let (status, _) = send(&app, Method::POST, &uri, &clerk).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&app, Method::POST, &uri, &admin).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = send(&app, Method::POST, &uri, &admin).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        assert!(ids(&app, "/api/v2/incidents", &clerk).await.contains(&LEAK));
+        assert_eq!(ids(&app, "/api/v2/repairs", &clerk).await.len(), 3);
+        let (_, body) = send(&app, Method::GET, risk, &clerk).await;
+        assert_eq!(body["data"]["buildings"].as_array().unwrap().len(), 2);
+        let (_, body) = send(&app, Method::GET, "/api/v2/trash", &admin).await;
+        assert_eq!(body["meta"]["total"], 1);
+        assert_eq!(body["data"][0]["entityType"], "repair");
+
+        let log = format!("/api/v2/audit_log?entityType=building&entityId={LENINA_5}");
+        let (_, body) = send(&app, Method::GET, &log, &admin).await;
+        assert_eq!(body["data"][0]["action"], "update");
+        assert_eq!(body["data"][0]["after"]["deleted_at"], Value::Null);
+        assert_eq!(body["data"][1]["action"], "delete");
+    }
+}
@@ -0,0 +1,28 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use controllers::{get_trash, restore_from_trash};
+use utoipa::OpenApi;
+
+use super::ApiContext;
+
+mod controllers;
+pub mod models;
+pub mod utils;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(controllers::get_trash, controllers::restore_from_trash),
+    components(schemas(models::TrashSortField, models::TrashEntity)),
+    tags((name = "trash", description = "Удалённые записи и их восстановление"))
+)]
+pub(crate) struct ApiDoc;
+
+/// Корзина появилась после выхода v2, поэтому есть только в ней.
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new().route("/api/v2/trash", get(get_trash)).route(
+        "/api/v2/trash/:entity_type/:id/restore",
+        post(restore_from_trash),
+    )
+}
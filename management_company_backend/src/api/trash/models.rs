@@ -0,0 +1,131 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::{
+    audit::models::AuditEntity,
+    pagination::{ListFilter, SortField, SortOrder},
+};
+
+/// Вид записи, которая удаляется в корзину, а не из базы.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashEntity {
+    Employee,
+    Building,
+    Apartment,
+    Incident,
+    Repair,
+}
+
+impl TrashEntity {
+    /// Таблица записей; её имя — это и `entityType` в корзине.
+    pub fn table(self) -> &'static str {
+        match self {
+            Self::Employee => "employee",
+            Self::Building => "building",
+            Self::Apartment => "apartment",
+            Self::Incident => "incident",
+            Self::Repair => "repair",
+        }
+    }
+
+    /// Записи, которые удаляются и восстанавливаются вместе с этой, и столбец ссылки на неё.
+    pub fn dependents(self) -> &'static [(TrashEntity, &'static str)] {
+        match self {
+            Self::Building => &[
+                (Self::Apartment, "building_id"),
+                (Self::Incident, "building_id"),
+                (Self::Repair, "building_id"),
+            ],
+            Self::Incident => &[(Self::Repair, "incident_id")],
+            Self::Employee | Self::Apartment | Self::Repair => &[],
+        }
+    }
+
+    /// Записи, пока удалены которые, эту восстановить нельзя, и столбец ссылки на них.
+    pub fn parents(self) -> &'static [(TrashEntity, &'static str)] {
+        match self {
+            Self::Apartment | Self::Incident => &[(Self::Building, "building_id")],
+            Self::Repair => &[
+                (Self::Building, "building_id"),
+                (Self::Incident, "incident_id"),
+            ],
+            Self::Employee | Self::Building => &[],
+        }
+    }
+}
+
+impl From<TrashEntity> for AuditEntity {
+    fn from(entity: TrashEntity) -> Self {
+        match entity {
+            TrashEntity::Employee => Self::Employee,
+            TrashEntity::Building => Self::Building,
+            TrashEntity::Apartment => Self::Apartment,
+            TrashEntity::Incident => Self::Incident,
+            TrashEntity::Repair => Self::Repair,
+        }
+    }
+}
+
+/// Удалённая запись: когда удалена и как выглядела в момент удаления.
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    pub entity_type: String,
+    pub id: Uuid,
+    pub deleted_at: DateTime<Utc>,
+    pub snapshot: serde_json::Value,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct TrashFilter {
+    pub entity_type: Option<TrashEntity>,
+    pub deleted_from: Option<NaiveDate>,
+    pub deleted_to: Option<NaiveDate>,
+}
+
+impl ListFilter for TrashFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(entity_type) = self.entity_type {
+            builder
+                .push(" AND t.entity_type = ")
+                .push_bind(entity_type.table());
+        }
+        if let Some(deleted_from) = self.deleted_from {
+            builder
+                .push(" AND t.deleted_at >= ")
+                .push_bind(deleted_from)
+                .push("::date");
+        }
+        if let Some(deleted_to) = self.deleted_to {
+            builder
+                .push(" AND t.deleted_at < ")
+                .push_bind(deleted_to)
+                .push("::date + 1");
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum TrashSortField {
+    #[default]
+    DeletedAt,
+}
+
+impl SortField for TrashSortField {
+    fn expression(self) -> &'static str {
+        match self {
+            Self::DeletedAt => "t.deleted_at",
+        }
+    }
+
+    fn default_order(self) -> SortOrder {
+        SortOrder::Desc
+    }
+}
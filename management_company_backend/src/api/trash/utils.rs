@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::api::Error;
+
+use super::models::TrashEntity;
+
+/// Пометить запись удалённой вместе с зависимыми записями; возвращает строку до удаления
+/// или `None`, если записи нет или она уже удалена.
+///
+/// Все записи получают одно время удаления — `now()`, время начала транзакции. По нему
+/// `restore` отличает удалённое вместе с записью от удалённого раньше по отдельности.
+pub async fn soft_delete(
+    conn: &mut PgConnection,
+    entity: TrashEntity,
+    id: Uuid,
+) -> Result<Option<Value>, Error> {
+    let table = entity.table();
+    let before: Option<Value> = sqlx::query_scalar(&format!(
+        "SELECT to_jsonb(t) - 'search_vector' FROM {table} t \
+         WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"
+    ))
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if before.is_none() {
+        return Ok(None);
+    }
+
+    sqlx::query(&format!(
+        "UPDATE {table} SET deleted_at = now() WHERE id = $1"
+    ))
+    .bind(id)
+    .execute(&mut *conn)
+    .await?;
+
+    for (dependent, column) in entity.dependents() {
+        sqlx::query(&format!(
+            "UPDATE {} SET deleted_at = now() WHERE {column} = $1 AND deleted_at IS NULL",
+            dependent.table()
+        ))
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(before)
+}
+
+/// Вернуть запись из корзины вместе с записями, удалёнными одновременно с ней; возвращает
+/// строку до и после восстановления для журнала изменений.
+///
+/// Запись, которая ссылается на удалённый дом или аварию, не восстанавливается: сначала
+/// нужно восстановить их.
+pub async fn restore(
+    conn: &mut PgConnection,
+    entity: TrashEntity,
+    id: Uuid,
+) -> Result<(Value, Value), Error> {
+    let table = entity.table();
+    let deleted: Option<(DateTime<Utc>, Value)> = sqlx::query_as(&format!(
+        "SELECT deleted_at, to_jsonb(t) - 'search_vector' FROM {table} t \
+         WHERE id = $1 AND deleted_at IS NOT NULL FOR UPDATE"
+    ))
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let (deleted_at, before) = deleted.ok_or(Error::NotFound)?;
+
+    for (parent, column) in entity.parents() {
+        let parent_deleted: bool = sqlx::query_scalar(&format!(
+            "SELECT EXISTS(SELECT 1 FROM {table} t JOIN {parent_table} p ON t.{column} = p.id \
+             WHERE t.id = $1 AND p.deleted_at IS NOT NULL)",
+            parent_table = parent.table()
+        ))
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await?;
+        if parent_deleted {
+            return Err(Error::Conflict(format!(
+                "The {} of this record is deleted, restore it first",
+                parent.table()
+            )));
+        }
+    }
+
+    let after: Value = sqlx::query_scalar(&format!(
+        "UPDATE {table} t SET deleted_at = NULL WHERE id = $1 \
+         RETURNING to_jsonb(t) - 'search_vector'"
+    ))
+    .bind(id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    for (dependent, column) in entity.dependents() {
+        sqlx::query(&format!(
+            "UPDATE {} SET deleted_at = NULL WHERE {column} = $1 AND deleted_at = $2",
+            dependent.table()
+        ))
+        .bind(id)
+        .bind(deleted_at)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok((before, after))
+}
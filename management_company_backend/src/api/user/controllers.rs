@@ -1,7 +1,10 @@
 use axum::{extract::State, Json};
 
 use crate::api::{
-    employee::utils::employee_exists, extractor::AuthUser, ApiContext, Error, Result,
+    audit::{models::AuditEntity, utils::AuditRecord},
+    employee::utils::employee_exists,
+    extractor::AuthUser,
+    ApiContext, Error, Result,
 };
 
 use super::{
@@ -9,6 +12,17 @@ use super::{
     utils::{hash_password, verify_password},
 };
 
+/// Зарегистрировать пользователя для существующего сотрудника.
+#[utoipa::path(
+    post,
+    path = "/api/users",
+    tag = "users",
+    request_body = UserBody<NewUser>,
+    responses(
+        (status = 200, description = "Пользователь создан", body = UserBody<UserAuthResponse>),
+        (status = 404, description = "Сотрудник не найден"),
+    )
+)]
 pub async fn create_user(
     ctx: State<ApiContext>,
     Json(req): Json<UserBody<NewUser>>,
@@ -16,22 +30,29 @@ pub async fn create_user(
     if req.user.employee_id.is_none()
         || !employee_exists(&ctx.db, req.user.employee_id.unwrap()).await?
     {
-        return Err(Error::EmployeeNotFound.into());
+        return Err(Error::EmployeeNotFound);
     }
 
     let password_hash = hash_password(req.user.password).await?;
-    let user_id = sqlx::query_scalar!(
+    let mut transaction = ctx.db.begin().await?;
+    let user = sqlx::query!(
         r#"
         insert into user_account (email, password_hash, employee_id)
         values (($1::text)::domain_email, $2, $3)
-        returning id
+        returning id, to_jsonb(user_account) - 'password_hash' as "snapshot!"
         "#,
         req.user.email,
         password_hash,
         req.user.employee_id
     )
-    .fetch_one(&ctx.db)
+    .fetch_one(&mut *transaction)
     .await?;
+    let user_id = user.id;
+
+    AuditRecord::created(AuditEntity::User, user_id, user.snapshot)
+        .save(&mut transaction, Some(user_id))
+        .await?;
+    transaction.commit().await?;
 
     Ok(Json(UserBody {
         user: UserAuthResponse {
@@ -40,6 +61,17 @@ pub async fn create_user(
     }))
 }
 
+/// Получить токен по электронной почте и паролю.
+#[utoipa::path(
+    post,
+    path = "/api/users/login",
+    tag = "users",
+    request_body = UserBody<LoginUser>,
+    responses(
+        (status = 200, description = "Вход выполнен", body = UserBody<UserAuthResponse>),
+        (status = 401, description = "Неверная почта или пароль"),
+    )
+)]
 pub async fn login_user(
     ctx: State<ApiContext>,
     Json(req): Json<UserBody<LoginUser>>,
@@ -56,7 +88,7 @@ pub async fn login_user(
 
     let user = match optional_user {
         Some(user) => user,
-        None => return Err(Error::UserNotFound.into()),
+        None => return Err(Error::UserNotFound),
     };
 
     verify_password(req.user.password, user.password_hash).await?;
@@ -68,6 +100,17 @@ pub async fn login_user(
     }))
 }
 
+/// Текущий пользователь с обновлённым токеном.
+#[utoipa::path(
+    get,
+    path = "/api/user/me",
+    tag = "users",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Текущий пользователь", body = UserBody<UserResponse>),
+        (status = 401, description = "Нет действующего токена"),
+    )
+)]
 pub async fn get_current_user(
     auth_user: AuthUser,
     ctx: State<ApiContext>,
@@ -92,10 +135,22 @@ pub async fn get_current_user(
                 last_name: user.last_name,
             },
         })),
-        None => Err(Error::Unauthorized.into()),
+        None => Err(Error::Unauthorized),
     }
 }
 
+/// Изменить почту, пароль или сотрудника текущего пользователя; пустые поля не меняются.
+#[utoipa::path(
+    put,
+    path = "/api/user/me",
+    tag = "users",
+    security(("bearer" = [])),
+    request_body = UserBody<UpdateUser>,
+    responses(
+        (status = 200, description = "Пользователь изменён"),
+        (status = 401, description = "Нет действующего токена"),
+    )
+)]
 pub async fn update_user(
     auth_user: AuthUser,
     ctx: State<ApiContext>,
@@ -111,22 +166,46 @@ pub async fn update_user(
         None
     };
 
-    let _ = sqlx::query!(
+    let mut transaction = ctx.db.begin().await?;
+    let before = sqlx::query_scalar!(
+        r#"
+            select to_jsonb(u) - 'password_hash' as "snapshot!"
+            from user_account u where id = $1
+            for update
+        "#,
+        auth_user.user_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(Error::Unauthorized)?;
+
+    let mut after = sqlx::query_scalar!(
         r#"
             update user_account
             set email = coalesce($1, user_account.email),
                 employee_id = coalesce($2, user_account.employee_id),
                 password_hash = coalesce($3, user_account.password_hash)
             where id = $4
-            returning email, employee_id
+            returning to_jsonb(user_account) - 'password_hash' as "snapshot!"
         "#,
         req.user.email,
         req.user.employee_id,
         password_hash,
         auth_user.user_id
     )
-    .fetch_one(&ctx.db)
+    .fetch_one(&mut *transaction)
     .await?;
 
+    // Хэш пароля в журнал не попадает, записывается только сам факт смены.
+    if password_hash.is_some() {
+        if let Some(after) = after.as_object_mut() {
+            after.insert("password_changed".to_string(), true.into());
+        }
+    }
+    AuditRecord::updated(AuditEntity::User, auth_user.user_id, before, after)
+        .save(&mut transaction, Some(auth_user.user_id))
+        .await?;
+    transaction.commit().await?;
+
     Ok(())
 }
@@ -3,12 +3,30 @@ use axum::{
     Router,
 };
 use controllers::{create_user, get_current_user, login_user, update_user};
+use utoipa::OpenApi;
 
 use super::ApiContext;
 
 mod controllers;
 mod models;
 pub mod utils;
+pub(crate) mod v2;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::create_user,
+        controllers::login_user,
+        controllers::get_current_user,
+        controllers::update_user,
+        v2::create_user,
+        v2::login_user,
+        v2::get_current_user,
+        v2::update_user,
+    ),
+    tags((name = "users", description = "Регистрация, вход и учётная запись"))
+)]
+pub(crate) struct ApiDoc;
 
 pub(crate) fn router() -> Router<ApiContext> {
     Router::new()
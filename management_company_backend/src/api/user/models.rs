@@ -1,25 +1,29 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct UserBody<T> {
     pub user: T,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct NewUser {
     pub email: String,
     pub password: String,
+    /// В v1 поле передавалось как `employee_id`; это имя по-прежнему принимается.
+    #[serde(alias = "employee_id")]
     pub employee_id: Option<Uuid>,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, ToSchema)]
 pub struct LoginUser {
     pub email: String,
     pub password: String,
 }
 
-#[derive(serde::Deserialize, Default, PartialEq, Eq)]
+#[derive(serde::Deserialize, Default, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUser {
     pub email: Option<String>,
@@ -27,7 +31,7 @@ pub struct UpdateUser {
     pub employee_id: Option<Uuid>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserResponse {
     pub token: String,
@@ -36,7 +40,7 @@ pub struct UserResponse {
     pub last_name: String,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, ToSchema)]
 pub struct UserAuthResponse {
     pub token: String,
 }
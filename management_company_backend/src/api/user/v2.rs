@@ -0,0 +1,100 @@
+use axum::{
+    extract::State,
+    response::Response,
+    routing::{get, post},
+    Json, Router,
+};
+
+use crate::api::{
+    envelope::{created, Envelope, ErrorEnvelope},
+    extractor::AuthUser,
+    ApiContext, Result,
+};
+
+use super::{
+    controllers,
+    models::{LoginUser, NewUser, UpdateUser, UserAuthResponse, UserBody, UserResponse},
+};
+
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/api/v2/users", post(create_user))
+        .route("/api/v2/users/login", post(login_user))
+        .route("/api/v2/users/me", get(get_current_user).put(update_user))
+}
+
+/// Зарегистрировать пользователя для существующего сотрудника.
+#[utoipa::path(
+    post,
+    path = "/api/v2/users",
+    tag = "users",
+    request_body = NewUser,
+    responses(
+        (status = 201, description = "Пользователь создан", body = Envelope<UserAuthResponse>),
+        (status = 404, description = "Сотрудник не найден", body = ErrorEnvelope),
+    )
+)]
+pub async fn create_user(ctx: State<ApiContext>, Json(user): Json<NewUser>) -> Result<Response> {
+    let Json(body) = controllers::create_user(ctx, Json(UserBody { user })).await?;
+    Ok(created(body.user))
+}
+
+/// Получить токен по электронной почте и паролю.
+#[utoipa::path(
+    post,
+    path = "/api/v2/users/login",
+    tag = "users",
+    request_body = LoginUser,
+    responses(
+        (status = 200, description = "Вход выполнен", body = Envelope<UserAuthResponse>),
+        (status = 401, description = "Неверная почта или пароль", body = ErrorEnvelope),
+    )
+)]
+pub async fn login_user(
+    ctx: State<ApiContext>,
+    Json(user): Json<LoginUser>,
+) -> Result<Json<Envelope<UserAuthResponse>>> {
+    let Json(body) = controllers::login_user(ctx, Json(UserBody { user })).await?;
+    Ok(Envelope::new(body.user))
+}
+
+/// Текущий пользователь с обновлённым токеном.
+#[utoipa::path(
+    get,
+    path = "/api/v2/users/me",
+    tag = "users",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Текущий пользователь", body = Envelope<UserResponse>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_current_user(
+    auth_user: AuthUser,
+    ctx: State<ApiContext>,
+) -> Result<Json<Envelope<UserResponse>>> {
+    let Json(body) = controllers::get_current_user(auth_user, ctx).await?;
+    Ok(Envelope::new(body.user))
+}
+
+/// Изменить почту, пароль или сотрудника текущего пользователя; пустые поля не меняются.
+#[utoipa::path(
+    put,
+    path = "/api/v2/users/me",
+    tag = "users",
+    security(("bearer" = [])),
+    request_body = UpdateUser,
+    responses(
+        (status = 200, description = "Пользователь после изменения", body = Envelope<UserResponse>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+    )
+)]
+pub async fn update_user(
+    auth_user: AuthUser,
+    ctx: State<ApiContext>,
+    Json(user): Json<UpdateUser>,
+) -> Result<Json<Envelope<UserResponse>>> {
+    let user_id = auth_user.user_id;
+    controllers::update_user(auth_user, ctx.clone(), Json(UserBody { user })).await?;
+    get_current_user(AuthUser { user_id }, ctx).await
+}
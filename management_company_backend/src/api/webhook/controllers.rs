@@ -0,0 +1,427 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Response,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::api::{
+    audit::{models::AuditEntity, utils::AuditRecord},
+    envelope::{created, Envelope, ErrorEnvelope},
+    events::models::FeedEventKind,
+    extractor::AdminUser,
+    pagination::{fetch_page, ListQuery, ListSource, PageParams},
+    ApiContext, Error,
+};
+
+use super::models::{
+    NewWebhook, Webhook, WebhookDelivery, WebhookDeliveryFilter, WebhookDeliverySortField,
+};
+
+/// Минимальная длина ключа подписи вебхука.
+const MIN_SECRET_LENGTH: usize = 16;
+
+const DELIVERIES: ListSource = ListSource {
+    select: r#"
+        SELECT
+            d.id,
+            o.subscription_id,
+            d.outbox_id AS event_id,
+            o.event_type,
+            d.attempt,
+            d.attempted_at,
+            d.response_status,
+            d.error,
+            d.duration_ms,
+            COALESCE(d.response_status BETWEEN 200 AND 299, FALSE) AS succeeded
+        "#,
+    from: r#"
+        FROM
+            webhook_delivery d
+            JOIN webhook_outbox o ON o.id = d.outbox_id
+        "#,
+    condition: "TRUE",
+    id_column: "d.id",
+};
+
+/// Все подписки на вебхуки.
+#[utoipa::path(
+    get,
+    path = "/api/v2/webhooks",
+    tag = "webhooks",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Подписки", body = Envelope<Vec<Webhook>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 403, description = "Нужны права администратора", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_webhooks(
+    _: AdminUser,
+    State(ctx): State<ApiContext>,
+) -> Result<Json<Envelope<Vec<Webhook>>>, Error> {
+    let webhooks = sqlx::query_as!(
+        Webhook,
+        r#"
+        SELECT
+            id,
+            url,
+            event_types AS "event_types: Vec<FeedEventKind>",
+            created_at
+        FROM
+            webhook_subscription
+        ORDER BY
+            created_at
+        "#
+    )
+    .fetch_all(&ctx.db)
+    .await?;
+
+    Ok(Envelope::new(webhooks))
+}
+
+/// Подписать адрес на события. Каждое событие отправляется POST-запросом с телом, как в
+/// ленте `/api/v2/events`, и заголовками `X-Webhook-Id`, `X-Webhook-Event`,
+/// `X-Webhook-Timestamp` и `X-Webhook-Signature`: `sha256=` и HMAC-SHA256 строки
+/// `{X-Webhook-Timestamp}.{тело}` с ключом `secret`.
+///
+/// Ответ 2xx означает, что событие доставлено; иначе отправка повторяется с растущими паузами.
+#[utoipa::path(
+    post,
+    path = "/api/v2/webhooks",
+    tag = "webhooks",
+    security(("bearer" = [])),
+    request_body = NewWebhook,
+    responses(
+        (status = 201, description = "Подписка создана", body = Envelope<Webhook>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 403, description = "Нужны права администратора", body = ErrorEnvelope),
+        (status = 422, description = "Неверный адрес, ключ или список событий", body = ErrorEnvelope),
+    )
+)]
+pub async fn create_webhook(
+    admin: AdminUser,
+    State(ctx): State<ApiContext>,
+    Json(mut webhook): Json<NewWebhook>,
+) -> Result<Response, Error> {
+    let url = reqwest::Url::parse(&webhook.url)
+        .map_err(|e| Error::UnprocessableEntity(format!("invalid webhook url: {e}")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(Error::UnprocessableEntity(
+            "webhook url must use http or https".to_string(),
+        ));
+    }
+    if webhook.secret.chars().count() < MIN_SECRET_LENGTH {
+        return Err(Error::UnprocessableEntity(format!(
+            "webhook secret must be at least {MIN_SECRET_LENGTH} characters long"
+        )));
+    }
+    webhook.event_types.sort_by_key(|kind| kind.as_str());
+    webhook.event_types.dedup();
+    if webhook.event_types.is_empty() {
+        return Err(Error::UnprocessableEntity(
+            "at least one event type is required".to_string(),
+        ));
+    }
+
+    let mut transaction = ctx.db.begin().await?;
+
+    let webhook = sqlx::query_as!(
+        Webhook,
+        r#"
+        INSERT INTO webhook_subscription (url, secret, event_types)
+        VALUES ($1, $2, $3)
+        RETURNING
+            id,
+            url,
+            event_types AS "event_types: Vec<FeedEventKind>",
+            created_at
+        "#,
+        url.as_str(),
+        webhook.secret,
+        &webhook.event_types as &[FeedEventKind]
+    )
+    .fetch_one(&mut *transaction)
+    .await?;
+
+    let snapshot = sqlx::query_scalar!(
+        r#"
+        SELECT to_jsonb(s) - 'secret' AS "snapshot!"
+        FROM webhook_subscription s
+        WHERE id = $1
+        "#,
+        webhook.id
+    )
+    .fetch_one(&mut *transaction)
+    .await?;
+    AuditRecord::created(AuditEntity::Webhook, webhook.id, snapshot)
+        .save(&mut transaction, Some(admin.user_id))
+        .await?;
+    transaction.commit().await?;
+
+    Ok(created(webhook))
+}
+
+/// Удалить подписку вместе с неотправленными событиями и журналом доставки.
+#[utoipa::path(
+    delete,
+    path = "/api/v2/webhooks/{id}",
+    tag = "webhooks",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "Идентификатор подписки")),
+    responses(
+        (status = 204, description = "Подписка удалена"),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 403, description = "Нужны права администратора", body = ErrorEnvelope),
+        (status = 404, description = "Подписка не найдена", body = ErrorEnvelope),
+    )
+)]
+pub async fn delete_webhook(
+    admin: AdminUser,
+    State(ctx): State<ApiContext>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, Error> {
+    let mut transaction = ctx.db.begin().await?;
+
+    // Ключ подписи не попадает в журнал.
+    let before = sqlx::query_scalar!(
+        r#"
+        DELETE FROM webhook_subscription s
+        WHERE id = $1
+        RETURNING to_jsonb(s) - 'secret' AS "snapshot!"
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await?
+    .ok_or(Error::NotFound)?;
+    AuditRecord::deleted(AuditEntity::Webhook, id, before)
+        .save(&mut transaction, Some(admin.user_id))
+        .await?;
+    transaction.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Журнал доставки: каждая попытка отправить событие, сначала последние.
+#[utoipa::path(
+    get,
+    path = "/api/v2/webhook_deliveries",
+    tag = "webhooks",
+    security(("bearer" = [])),
+    params(
+        PageParams,
+        ("sortBy" = Option<WebhookDeliverySortField>, Query, description = "Поле сортировки"),
+        WebhookDeliveryFilter,
+    ),
+    responses(
+        (status = 200, description = "Страница журнала доставки", body = Envelope<Vec<WebhookDelivery>>),
+        (status = 401, description = "Нет действующего токена", body = ErrorEnvelope),
+        (status = 403, description = "Нужны права администратора", body = ErrorEnvelope),
+        (status = 422, description = "Неверные параметры списка", body = ErrorEnvelope),
+    )
+)]
+pub async fn get_webhook_deliveries(
+    _: AdminUser,
+    State(ctx): State<ApiContext>,
+    query: ListQuery<WebhookDeliveryFilter, WebhookDeliverySortField>,
+) -> Result<Json<Envelope<Vec<WebhookDelivery>>>, Error> {
+    let (deliveries, page_info) = fetch_page(
+        &ctx.db,
+        &DELIVERIES,
+        &query,
+        |delivery: &WebhookDelivery| delivery.id,
+    )
+    .await?;
+
+    Ok(Envelope::page(deliveries, page_info))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::IntoFuture,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        body::Body,
+        http::{
+            header::{AUTHORIZATION, CONTENT_TYPE},
+            HeaderMap, Method, Request,
+        },
+        routing::post,
+        Router,
+    };
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use tokio::net::TcpListener;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::api::{
+        api_router,
+        extractor::AuthUser,
+        test_context,
+        webhook::delivery::{signature, WebhookWorker},
+    };
+
+    const PETROVA: Uuid = Uuid::from_u128(0xe2);
+    const LENINA_5: Uuid = Uuid::from_u128(0xb1);
+    const LEAK: Uuid = Uuid::from_u128(0x101);
+    const ROOF: Uuid = Uuid::from_u128(0x202);
+    const SECRET: &str = "0123456789abcdef";
+
+    type Received = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+
+    /// Подписчик, который отвечает 500 на первый запрос и 204 на остальные.
+    async fn subscriber() -> (String, Received) {
+        let received = Received::default();
+        let app = Router::new().route(
+            "/hook",
+            post({
+                let received = received.clone();
+                move |headers: HeaderMap, body: String| async move {
+                    let mut received = received.lock().unwrap();
+                    received.push((headers, body));
+                    if received.len() == 1 {
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    } else {
+                        StatusCode::NO_CONTENT
+                    }
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(axum::serve(listener, app).into_future());
+        (url, received)
+    }
+
+    async fn send(app: &Router, method: Method, uri: &str, token: &str, body: Value) -> Value {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(AUTHORIZATION, format!("Bearer {token}"))
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null)
+    }
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn events_are_signed_and_retried_until_delivered(pool: PgPool) {
+        let ctx = test_context(pool.clone());
+        let user_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO user_account (email, password_hash, employee_id, is_admin)
+            VALUES ('admin@example.com', '', $1, TRUE)
+            RETURNING id
+            "#,
+            PETROVA
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let token = AuthUser { user_id }.to_jwt(&ctx);
+        let worker = WebhookWorker::new(pool.clone(), &ctx.config).unwrap();
+        let app = api_router(ctx);
+        let (url, received) = subscriber().await;
+
+        let invalid = json!({ "url": url, "secret": "short", "eventTypes": ["incident_created"] });
+        let body = send(&app, Method::POST, "/api/v2/webhooks", &token, invalid).await;
+        assert!(body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("at least 16 characters"));
+
+        let webhook = json!({ "url": url, "secret": SECRET, "eventTypes": ["incident_created"] });
+        let body = send(&app, Method::POST, "/api/v2/webhooks", &token, webhook).await;
+        let subscription_id = body["data"]["id"].as_str().unwrap().to_owned();
+        assert!(body["data"].get("secret").is_none());
+
+        let incident_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO incident (building_id, status, description, incident_type_id)
+            VALUES ($1, 'reported', 'Прорвало трубу', $2)
+            RETURNING id
+            "#,
+            LENINA_5,
+            LEAK
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        // На смену статуса подписки нет.
+        sqlx::query!(
+            "UPDATE incident SET status = 'resolved' WHERE id = $1",
+            ROOF
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(worker.deliver_due().await.unwrap(), 1);
+        // Повтор ещё не наступил.
+        assert_eq!(worker.deliver_due().await.unwrap(), 0);
+        sqlx::query!("UPDATE webhook_outbox SET next_attempt_at = now()")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(worker.deliver_due().await.unwrap(), 1);
+        assert_eq!(worker.deliver_due().await.unwrap(), 0);
+
+        let received = received.lock().unwrap().clone();
+        assert_eq!(received.len(), 2);
+        // Повтор отправляется с тем же идентификатором события.
+        assert_eq!(received[0].0["x-webhook-id"], received[1].0["x-webhook-id"]);
+        let (headers, body) = &received[1];
+        let header = |name: &str| headers[name].to_str().unwrap().to_owned();
+        assert_eq!(header("x-webhook-event"), "incident_created");
+        let timestamp: i64 = header("x-webhook-timestamp").parse().unwrap();
+        assert_eq!(
+            header("x-webhook-signature"),
+            signature(SECRET, timestamp, body)
+        );
+        let event: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(event["incidentId"], json!(incident_id));
+        assert_eq!(event["buildingId"], json!(LENINA_5));
+
+        let log = format!("/api/v2/webhook_deliveries?subscriptionId={subscription_id}");
+        let body = send(&app, Method::GET, &log, &token, Value::Null).await;
+        assert_eq!(body["meta"]["total"], 2);
+        let [latest, first] = [&body["data"][0], &body["data"][1]];
+        assert_eq!(latest["attempt"], 2);
+        assert_eq!(latest["responseStatus"], 204);
+        assert_eq!(latest["succeeded"], true);
+        assert_eq!(first["responseStatus"], 500);
+        assert_eq!(first["succeeded"], false);
+
+        let failed = format!("{log}&succeeded=false");
+        let body = send(&app, Method::GET, &failed, &token, Value::Null).await;
+        assert_eq!(body["meta"]["total"], 1);
+
+        let uri = format!("/api/v2/webhooks/{subscription_id}");
+        send(&app, Method::DELETE, &uri, &token, Value::Null).await;
+        let audit = format!("/api/v2/audit_log?entityType=webhook&entityId={subscription_id}");
+        let body = send(&app, Method::GET, &audit, &token, Value::Null).await;
+        let [deleted, created] = [&body["data"][0], &body["data"][1]];
+        assert_eq!(deleted["action"], "delete");
+        assert_eq!(created["action"], "create");
+        assert_eq!(created["actorId"], json!(user_id));
+        assert_eq!(created["after"]["url"], json!(url));
+        // Ключ подписи в журнал не попадает.
+        assert!(created["after"].get("secret").is_none());
+        assert!(deleted["before"].get("secret").is_none());
+    }
+}
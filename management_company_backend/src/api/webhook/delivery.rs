@@ -0,0 +1,349 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use sha2::Sha256;
+use sqlx::PgPool;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::{
+    api::events::{
+        models::FeedEventKind,
+        outbox::{retry_delay, OutboxWorker},
+    },
+    config::Config,
+};
+
+/// Отправляет подписчикам события из `webhook_outbox`.
+///
+/// События берутся из очереди пачками в аренду: `next_attempt_at` сдвигается на `lease`, и это
+/// сразу фиксируется, поэтому другие экземпляры сервера их не возьмут, а запросы подписчикам
+/// отправляются без открытой транзакции. Если экземпляр остановится посреди отправки, события
+/// вернутся в очередь, когда истечёт аренда.
+pub struct WebhookWorker {
+    db: PgPool,
+    client: reqwest::Client,
+    poll_interval: Duration,
+    batch_size: i64,
+    lease: Duration,
+    max_attempts: i32,
+}
+
+/// Событие из очереди, которое пора отправить.
+struct Pending {
+    id: Uuid,
+    subscription_id: Uuid,
+    event_type: FeedEventKind,
+    payload: serde_json::Value,
+    created_at: DateTime<Utc>,
+    attempts: i32,
+    url: String,
+    secret: String,
+}
+
+/// Чем закончилась попытка: кодом ответа или ошибкой до его получения.
+enum Outcome {
+    Response(u16),
+    Failed(String),
+}
+
+impl Outcome {
+    fn succeeded(&self) -> bool {
+        matches!(self, Self::Response(status) if (200..300).contains(status))
+    }
+}
+
+/// Сделанная попытка, результат которой нужно записать в очередь и журнал доставки.
+struct Attempt {
+    pending: Pending,
+    outcome: Outcome,
+    duration_ms: i32,
+}
+
+impl WebhookWorker {
+    pub fn new(db: PgPool, config: &Config) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(config.webhook_timeout())
+            // Подписчик мог бы перенаправить подписанный запрос на чужой адрес.
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .context("could not build the webhook client")?;
+        Ok(Self {
+            db,
+            client,
+            poll_interval: config.webhook_poll_interval(),
+            batch_size: i64::from(config.webhook_batch_size),
+            lease: config.webhook_lease(),
+            max_attempts: config.webhook_max_attempts,
+        })
+    }
+
+    /// Отправить все события, которым подошёл срок, и вернуть число сделанных попыток.
+    pub async fn deliver_due(&self) -> Result<usize, sqlx::Error> {
+        let mut attempts = 0;
+        loop {
+            let batch = self.claim_due().await?;
+            if batch.is_empty() {
+                return Ok(attempts);
+            }
+            let results = self.send_batch(batch).await;
+            attempts += results.len();
+            self.record(results).await?;
+        }
+    }
+
+    /// Взять в аренду до `batch_size` событий, которым подошёл срок.
+    async fn claim_due(&self) -> Result<Vec<Pending>, sqlx::Error> {
+        sqlx::query_as!(
+            Pending,
+            r#"
+            UPDATE webhook_outbox o
+            SET next_attempt_at = now() + make_interval(secs => $1)
+            FROM webhook_subscription s
+            WHERE
+                s.id = o.subscription_id
+                AND o.id IN (
+                    SELECT id
+                    FROM webhook_outbox
+                    WHERE next_attempt_at <= now()
+                    ORDER BY next_attempt_at
+                    LIMIT $2
+                    FOR UPDATE SKIP LOCKED
+                )
+            RETURNING
+                o.id,
+                o.subscription_id,
+                o.event_type AS "event_type: FeedEventKind",
+                o.payload,
+                o.created_at,
+                o.attempts,
+                s.url,
+                s.secret
+            "#,
+            self.lease.as_secs_f64(),
+            self.batch_size
+        )
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Отправить пачку: подписчикам — одновременно, каждому — по порядку возникновения событий,
+    /// чтобы медленный подписчик не задерживал остальных.
+    async fn send_batch(&self, mut batch: Vec<Pending>) -> Vec<Attempt> {
+        batch.sort_by_key(|pending| (pending.subscription_id, pending.created_at));
+        let mut by_subscription: Vec<Vec<Pending>> = Vec::new();
+        for pending in batch {
+            match by_subscription.last_mut() {
+                Some(events) if events[0].subscription_id == pending.subscription_id => {
+                    events.push(pending)
+                }
+                _ => by_subscription.push(vec![pending]),
+            }
+        }
+
+        let mut tasks = JoinSet::new();
+        for events in by_subscription {
+            let client = self.client.clone();
+            tasks.spawn(async move {
+                let mut attempts = Vec::with_capacity(events.len());
+                for pending in events {
+                    let started = Instant::now();
+                    let outcome = send(&client, &pending).await;
+                    let duration_ms = started.elapsed().as_millis().min(i32::MAX as u128) as i32;
+                    attempts.push(Attempt {
+                        pending,
+                        outcome,
+                        duration_ms,
+                    });
+                }
+                attempts
+            });
+        }
+
+        let mut attempts = Vec::new();
+        while let Some(result) = tasks.join_next().await {
+            match result {
+                Ok(sent) => attempts.extend(sent),
+                // События этого подписчика вернутся в очередь, когда истечёт аренда.
+                Err(e) => tracing::error!(error = ?e, "webhook delivery task failed"),
+            }
+        }
+        attempts
+    }
+
+    /// Записать результаты попыток в журнал доставки и назначить повторы одной короткой
+    /// транзакцией.
+    async fn record(&self, attempts: Vec<Attempt>) -> Result<(), sqlx::Error> {
+        let mut transaction = self.db.begin().await?;
+        for Attempt {
+            pending,
+            outcome,
+            duration_ms,
+        } in attempts
+        {
+            let attempt = pending.attempts + 1;
+            let (response_status, error) = match &outcome {
+                Outcome::Response(status) => (Some(i32::from(*status)), None),
+                Outcome::Failed(error) => (None, Some(error.as_str())),
+            };
+            sqlx::query!(
+                r#"
+                INSERT INTO webhook_delivery (outbox_id, attempt, response_status, error, duration_ms)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+                pending.id,
+                attempt,
+                response_status,
+                error,
+                duration_ms
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            let now = Utc::now();
+            let (next_attempt_at, delivered_at, failed_at) = if outcome.succeeded() {
+                (None, Some(now), None)
+            } else if attempt >= self.max_attempts {
+                tracing::error!(
+                    event_id = %pending.id,
+                    url = pending.url,
+                    attempts = attempt,
+                    "giving up on webhook delivery"
+                );
+                (None, None, Some(now))
+            } else {
+                tracing::warn!(
+                    event_id = %pending.id,
+                    url = pending.url,
+                    status = response_status,
+                    error,
+                    "webhook delivery failed, will retry"
+                );
+                (Some(now + retry_delay(attempt)), None, None)
+            };
+            // Если аренда истекла и событие уже отправил другой экземпляр, его результат главнее.
+            sqlx::query!(
+                r#"
+                UPDATE webhook_outbox
+                SET attempts = $2, next_attempt_at = $3, delivered_at = $4, failed_at = $5
+                WHERE id = $1 AND attempts = $6
+                "#,
+                pending.id,
+                attempt,
+                next_attempt_at,
+                delivered_at,
+                failed_at,
+                pending.attempts
+            )
+            .execute(&mut *transaction)
+            .await?;
+        }
+        transaction.commit().await
+    }
+}
+
+impl OutboxWorker for WebhookWorker {
+    fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    async fn poll(&self) {
+        if let Err(e) = self.deliver_due().await {
+            tracing::error!(error = ?e, "failed to deliver webhooks");
+        }
+    }
+}
+
+async fn send(client: &reqwest::Client, pending: &Pending) -> Outcome {
+    let body = pending.payload.to_string();
+    let timestamp = Utc::now().timestamp();
+    let response = client
+        .post(&pending.url)
+        .header(CONTENT_TYPE, "application/json")
+        .header("X-Webhook-Id", pending.id.to_string())
+        .header("X-Webhook-Event", pending.event_type.as_str())
+        .header("X-Webhook-Timestamp", timestamp)
+        .header(
+            "X-Webhook-Signature",
+            signature(&pending.secret, timestamp, &body),
+        )
+        .body(body)
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => Outcome::Response(response.status().as_u16()),
+        Err(e) => Outcome::Failed(e.to_string()),
+    }
+}
+
+/// `sha256=` и HMAC-SHA256 строки `{timestamp}.{body}` в шестнадцатеричном виде. Метка времени
+/// входит в подпись, чтобы перехваченный запрос нельзя было повторить позже.
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut hmac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    hmac.update(format!("{timestamp}.{body}").as_bytes());
+    let digest: String = hmac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("sha256={digest}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_context;
+
+    #[sqlx::test(fixtures("../statistics/fixtures/statistics.sql"))]
+    async fn claimed_events_are_leased_to_one_worker(pool: PgPool) {
+        let ctx = test_context(pool.clone());
+        let worker = WebhookWorker::new(pool.clone(), &ctx.config).unwrap();
+        let other = WebhookWorker::new(pool.clone(), &ctx.config).unwrap();
+        sqlx::query!(
+            r#"
+            WITH s AS (
+                INSERT INTO webhook_subscription (url, secret, event_types)
+                VALUES ('http://127.0.0.1:9/hook', '0123456789abcdef', '{incident_created}')
+                RETURNING id
+            )
+            INSERT INTO webhook_outbox (subscription_id, event_type, payload)
+            SELECT id, 'incident_created', '{}' FROM s, generate_series(1, 3)
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(worker.claim_due().await.unwrap().len(), 3);
+        // Аренда уже зафиксирована: другой экземпляр эти события не возьмёт.
+        assert!(other.claim_due().await.unwrap().is_empty());
+        let leased_until =
+            sqlx::query_scalar!(r#"SELECT MIN(next_attempt_at) AS "at!" FROM webhook_outbox"#)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(
+            leased_until
+                > Utc::now() + chrono::Duration::from_std(ctx.config.webhook_timeout()).unwrap()
+        );
+    }
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        // echo -n '1700000000.{"kind":"incident_created"}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            signature("secret", 1_700_000_000, r#"{"kind":"incident_created"}"#),
+            "sha256=6b88961840eb6751147752671aeaa7fe1bbbf38dd9b339714016cdf8d335f715"
+        );
+        assert_ne!(
+            signature("secret", 1_700_000_001, r#"{"kind":"incident_created"}"#),
+            signature("secret", 1_700_000_000, r#"{"kind":"incident_created"}"#)
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use axum::{
+    routing::{delete, get},
+    Router,
+};
+use controllers::{create_webhook, delete_webhook, get_webhook_deliveries, get_webhooks};
+use utoipa::OpenApi;
+
+use super::ApiContext;
+
+mod controllers;
+pub mod delivery;
+pub mod models;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::get_webhooks,
+        controllers::create_webhook,
+        controllers::delete_webhook,
+        controllers::get_webhook_deliveries,
+    ),
+    components(schemas(models::WebhookDeliverySortField)),
+    tags((name = "webhooks", description = "Отправка событий ленты во внешние системы"))
+)]
+pub(crate) struct ApiDoc;
+
+/// Вебхуки появились после выхода v2, поэтому есть только в ней.
+pub(crate) fn router() -> Router<ApiContext> {
+    Router::new()
+        .route("/api/v2/webhooks", get(get_webhooks).post(create_webhook))
+        .route("/api/v2/webhooks/:id", delete(delete_webhook))
+        .route("/api/v2/webhook_deliveries", get(get_webhook_deliveries))
+}
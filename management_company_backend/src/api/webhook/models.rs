@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::api::{
+    events::models::FeedEventKind,
+    pagination::{ListFilter, SortField, SortOrder},
+};
+
+/// Подписка на события ленты: на какой адрес и какие из них отправлять.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<FeedEventKind>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewWebhook {
+    /// Адрес `http` или `https`, на который отправляются события методом POST.
+    pub url: String,
+    /// Ключ подписи `X-Webhook-Signature`, не короче 16 символов; в ответах не возвращается.
+    pub secret: String,
+    pub event_types: Vec<FeedEventKind>,
+}
+
+/// Попытка отправить событие подписчику.
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    /// Совпадает с заголовком `X-Webhook-Id` и одинаков у всех попыток отправить событие.
+    pub event_id: Uuid,
+    pub event_type: FeedEventKind,
+    /// Номер попытки, начиная с 1.
+    pub attempt: i32,
+    pub attempted_at: DateTime<Utc>,
+    /// Код ответа подписчика; нет, если ответа не было.
+    pub response_status: Option<i32>,
+    /// Почему ответа не было.
+    pub error: Option<String>,
+    pub duration_ms: i32,
+    pub succeeded: bool,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct WebhookDeliveryFilter {
+    pub subscription_id: Option<Uuid>,
+    pub event_id: Option<Uuid>,
+    pub succeeded: Option<bool>,
+}
+
+impl ListFilter for WebhookDeliveryFilter {
+    fn push_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(subscription_id) = self.subscription_id {
+            builder
+                .push(" AND o.subscription_id = ")
+                .push_bind(subscription_id);
+        }
+        if let Some(event_id) = self.event_id {
+            builder.push(" AND d.outbox_id = ").push_bind(event_id);
+        }
+        if let Some(succeeded) = self.succeeded {
+            builder
+                .push(" AND (d.response_status BETWEEN 200 AND 299) IS ")
+                .push(if succeeded { "TRUE" } else { "NOT TRUE" });
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookDeliverySortField {
+    #[default]
+    AttemptedAt,
+}
+
+impl SortField for WebhookDeliverySortField {
+    fn expression(self) -> &'static str {
+        match self {
+            Self::AttemptedAt => "d.attempted_at",
+        }
+    }
+
+    fn default_order(self) -> SortOrder {
+        SortOrder::Desc
+    }
+}
@@ -1,32 +1,42 @@
 use anyhow::Context;
-use clap::Parser;
-use sqlx::postgres::PgPoolOptions;
+use sqlx::{migrate::Migrator, postgres::PgPoolOptions};
 
-use config::Config;
-use tracing_subscriber::fmt::format::FmtSpan;
+use config::{Config, LogFormat};
+use tracing_subscriber::EnvFilter;
 
 mod api;
 mod config;
 
+/// Миграции, встроенные в сборку; по ним же проверяется готовность сервера.
+pub(crate) static MIGRATOR: Migrator = sqlx::migrate!();
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv::dotenv().ok();
 
-    tracing_subscriber::fmt()
-        .with_span_events(FmtSpan::FULL)
-        .init();
-
-    let config = Config::parse();
+    let config = Config::load()?;
+    init_tracing(&config);
+    tracing::info!(?config, "effective configuration");
 
     let db = PgPoolOptions::new()
-        .max_connections(50)
+        .max_connections(config.database_max_connections)
         .connect(&config.database_url)
         .await
         .context("Could not connect to database url")?;
 
-    sqlx::migrate!().run(&db).await?;
+    MIGRATOR.run(&db).await?;
 
     api::serve(config, db).await?;
 
     Ok(())
 }
+
+/// События пишутся в stdout; поля спана запроса, в том числе `request_id`, попадают в каждую
+/// строку, записанную во время его обработки.
+fn init_tracing(config: &Config) {
+    let subscriber = tracing_subscriber::fmt().with_env_filter(EnvFilter::new(&config.log_filter));
+    match config.log_format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+}
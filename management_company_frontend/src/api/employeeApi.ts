@@ -1,6 +1,7 @@
 import axiosInstance from '@/api/axiosInstance.ts'
 import type {
   Employee,
+  EmployeeDetails,
   EmployeeDetailsList,
   NewEmployee,
   PositionsList,
@@ -19,18 +20,28 @@ export const AddEmployee = async (NewEmployee: NewEmployee) => {
   return response.data
 }
 
-export const UpdateEmployeeWithId = async (
-  updatedEmployee: UpdateWrapperEmployee
-) => {
+// Сервер отклоняет изменение без версии, которую видел пользователь, чтобы не затереть чужую правку
+const ifMatch = (version: number) => ({ 'If-Match': `"${version}"` })
+
+export const UpdateEmployeeWithId = async ({
+  version,
+  ...updatedEmployee
+}: UpdateWrapperEmployee) => {
   const response = await axiosInstance.put<Employee>(
     `/employee/${updatedEmployee.id}`,
-    updatedEmployee
+    updatedEmployee,
+    { headers: ifMatch(version) }
   )
   return response.data
 }
 
-export const DeleteEmployeeWithId = async (id: string) => {
-  const response = await axiosInstance.delete(`employee/${id}`)
+export const DeleteEmployeeWithId = async ({
+  id,
+  version
+}: Pick<EmployeeDetails, 'id' | 'version'>) => {
+  const response = await axiosInstance.delete(`employee/${id}`, {
+    headers: ifMatch(version)
+  })
   return response.data
 }
 
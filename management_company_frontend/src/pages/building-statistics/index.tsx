@@ -21,7 +21,7 @@ import RepairPieChart
 import {
   TopBuildingsByRepairCosts
 } from '@/pages/building-statistics/components/top-buildings-by-repair-costs.tsx'
-import { ruDateFormat, ruMoneyFormat } from '@/types'
+import { formatMoney, ruDateFormat } from '@/types'
 import { useQuery, useQueryClient } from '@tanstack/react-query'
 import * as jsPDF from 'jspdf'
 import { ArrowUp, FireExtinguisherIcon } from 'lucide-react'
@@ -73,7 +73,7 @@ export default function Dashboard() {
           <p className='text-left'>Общее количество
             инцидентов: {data?.totalIncidents}</p>
           <p className='text-left'>Общая
-            стоимость: {data?.totalCost.toLocaleString('ru-RU', {
+            стоимость: {data && Number(data.totalCost).toLocaleString('ru-RU', {
               style: 'currency',
               currency: 'RUB'
             })}</p>
@@ -104,7 +104,7 @@ export default function Dashboard() {
           <tr key={index} className='grid-cols-5 grid'>
             <td className='col-span-3'>{incident.incidentType}</td>
             <td
-              className='col-span-2'>{ruMoneyFormat.format(incident.totalCost)}</td>
+              className='col-span-2'>{formatMoney(incident.totalCost)}</td>
           </tr>
         ))}
         </tbody>
@@ -128,7 +128,7 @@ export default function Dashboard() {
           <tr key={index} className='grid grid-cols-5'>
             <td className='col-span-1'>{building.buildingNumber}</td>
             <td
-              className='cols-span-3'>{ruMoneyFormat.format(building.totalCost)}</td>
+              className='cols-span-3'>{formatMoney(building.totalCost)}</td>
             <td className='col-span-1'>{building.repairCount}</td>
           </tr>
         ))}
@@ -287,7 +287,7 @@ export default function Dashboard() {
                     Аварий - {data.totalIncidents}
                   </CardDescription>
                   <span className='text-xl'>
-                    {ruMoneyFormat.format(data.totalCost)}
+                    {formatMoney(data.totalCost)}
                   </span>
                 </CardContent>
               </Card>
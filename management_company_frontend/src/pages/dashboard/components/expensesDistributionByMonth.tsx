@@ -8,7 +8,7 @@ interface ExpensesByMonthGraphicProps {
 export function ExpensesDistributionByMonth({ data }: ExpensesByMonthGraphicProps) {
   return (
     <ResponsiveContainer width='100%' height={420}>
-      <BarChart data={data}>
+      <BarChart data={data.map(month => ({ ...month, total: Number(month.total) }))}>
         <XAxis
           dataKey='name'
           stroke='#888888'
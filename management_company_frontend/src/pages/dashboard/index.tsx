@@ -12,7 +12,7 @@ import {
 import { Layout, LayoutBody, LayoutHeader } from '@/components/ui/layout'
 import Loader from '@/components/ui/loader.tsx'
 import { UserNav } from '@/components/user-nav'
-import { ruMoneyFormat } from '@/types'
+import { formatMoney } from '@/types'
 import { useQuery } from '@tanstack/react-query'
 import jsPDF from 'jspdf'
 import {
@@ -84,7 +84,7 @@ export default function Dashboard() {
         {data?.expenseDistributionByMonthLastYear.map((month, index) => (
           <tr className='flex items-start justify-between gap-4'>
             <td key={index}>{month.name}</td>
-            <td key={index}>{ruMoneyFormat.format(month.total)}</td>
+            <td key={index}>{formatMoney(month.total)}</td>
           </tr>
         ))}
         </tbody>
@@ -63,7 +63,7 @@ export function DataTableRowActions<TData>({
           <DropdownMenuSeparator />
           <DropdownMenuItem
             className='group'
-            onClick={() => deleteEmployeeMutation.mutate(employee.id)}
+            onClick={() => deleteEmployeeMutation.mutate(employee)}
           >
             <div className='group-hover:text-red-500/70 flex flex-row'>
               <Delete size={20} className='pr-1' />
@@ -70,7 +70,8 @@ const UpdateEmployeeForm = ({
   function onSubmit(updatedEmployee: UpdateEmployee) {
     updateEmployeeMutation.mutate({
       ...updatedEmployee,
-      id: employee.id
+      id: employee.id,
+      version: employee.version
     } as UpdateWrapperEmployee)
   }
   
@@ -6,6 +6,11 @@ export const ruMoneyFormat = new Intl.NumberFormat('ru-Ru', {
   maximumSignificantDigits: 3
 })
 
+// Суммы приходят с сервера точной десятичной строкой вроде "1500.50", чтобы не терять копейки
+export type Money = string
+
+export const formatMoney = (amount: Money) => ruMoneyFormat.format(Number(amount))
+
 export const ruDateFormat = new Intl.DateTimeFormat('ru-RU', {
   year: 'numeric',
   month: 'long',
@@ -28,7 +33,7 @@ export type NewEmployee = z.infer<typeof NewEmployeeSchema>
 export const PositionAtWorkSchema = z.object({
   id: z.string(),
   name: z.string(),
-  salary: z.number()
+  salary: z.string()
 })
 
 export type PositionAtWork = z.infer<typeof PositionAtWorkSchema>
@@ -51,6 +56,7 @@ export type UpdateEmployee = z.infer<typeof UpdateEmployeeSchema>
 
 export const UpdateEmployeeWrapperSchema = z.object({
   id: z.string().uuid(),
+  version: z.number(),
   firstName: z.string().optional(),
   lastName: z.string().optional(),
   middleName: z.string().optional(),
@@ -71,7 +77,8 @@ export const EmployeeSchema = z.object({
   phone: z.string(),
   gender: z.string(),
   positionId: z.string().uuid(),
-  passportId: z.string().uuid()
+  passportId: z.string().uuid(),
+  version: z.number()
 })
 
 export type Employee = z.infer<typeof EmployeeSchema>
@@ -87,7 +94,8 @@ export const EmployeeDetailsSchema = z.object({
   positionName: z.string(),
   positionSalary: z.string(),
   passportSeries: z.number(),
-  passportNumber: z.number()
+  passportNumber: z.number(),
+  version: z.number()
 })
 
 export type EmployeeDetails = z.infer<typeof EmployeeDetailsSchema>
@@ -128,7 +136,8 @@ export const BuildingSchema = z.object({
   number: z.number().int(),
   numberOfFloors: z.number().int(),
   address: AddressSchema,
-  constructedDate: z.string().transform(str => new Date(str)) // NaiveDate parsing
+  constructedDate: z.string().transform(str => new Date(str)), // NaiveDate parsing
+  version: z.number()
 })
 
 export type Building = z.infer<typeof BuildingSchema>
@@ -150,7 +159,8 @@ export const RepairSchema = z.object({
   repairType: z.string(),
   status: z.string(),
   description: z.string(),
-  buildingAddress: z.string()
+  buildingAddress: z.string(),
+  version: z.number()
 })
 export type Repair = z.infer<typeof RepairSchema>;
 
@@ -223,7 +233,7 @@ export type SignupInput = z.infer<typeof signupScheme>
 
 export interface ExpenseDistributionByMonth {
   name: string
-  total: number
+  total: Money
 }
 
 export interface IncidentTypeStatistic {
@@ -234,7 +244,7 @@ export interface IncidentTypeStatistic {
 }
 
 export interface StatisticOverviewLastYear {
-  totalExpensesLastYear: string
+  totalExpensesLastYear: Money
   percentChangesInExpenseFromLastYear: string
   countOfRepairsLastYear: number
   percentChangesInCountRepairLastYear: string
@@ -255,20 +265,20 @@ export interface RepairCount {
 
 export interface IncidentCost {
   incidentType: string
-  totalCost: number
+  totalCost: Money
 }
 
 export interface BuildingRepairCost {
   buildingId: string
   buildingNumber: number
-  totalCost: number
+  totalCost: Money
   repairCount: number
 }
 
 export interface Overview {
   buildingId: string
   totalIncidents: number
-  totalCost: number
+  totalCost: Money
   repairCounts: RepairCount
   incidentCosts: IncidentCost[]
   topBuildingsByRepairCost: BuildingRepairCost[]